@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::manager::Manager;
+pub use crate::manager::export::ProcessInfo;
+use crate::manager::system;
+use crate::user::{self, Privilege, User};
+
+/// System-wide stats, as returned by `GET /system`
+#[derive(Debug, Serialize)]
+pub struct SystemInfo {
+    pub process_count: usize,
+    pub state_counts: HashMap<char, usize>, // e.g. {"R": 3, "S": 250, "Z": 1}
+    pub cpu_percent: Option<f32>,           // None until two samples have been taken
+    pub memory_total_kb: u64,
+    pub memory_available_kb: u64,
+    pub memory_percent: f32,
+}
+
+/// Body of every non-200 response
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+/// Address the API listens on unless --serve-addr says otherwise: there is no authentication and
+/// command lines often carry tokens or passwords, so only local clients by default
+pub const DEFAULT_SERVE_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// Serve a read-only JSON view of the process list on addr:port until the process is killed.
+/// The snapshot is refreshed on a request once it is older than refresh_interval, so
+/// CPU percentages are always measured over at least that long.
+pub fn serve(addr: IpAddr, port: u16, refresh_interval: Duration) -> Result<(), String> {
+    // Read-only: the API never performs operations, so it runs as the invoking user without admin rights
+    let uid = nix::unistd::getuid().as_raw();
+    let name = user::name_for_uid(uid).unwrap_or_else(|| uid.to_string());
+    let mut manager = Manager::new(User::new(uid, &name, Privilege::Normal))?;
+    let mut last_refresh = Instant::now();
+
+    let server = Server::http((addr, port))
+        .map_err(|e| format!("Failed to listen on {}:{}: {}", addr, port, e))?;
+    if !addr.is_loopback() {
+        log::warn!("Process API reachable beyond this machine on {}: it has no authentication and returns every command line", addr);
+    }
+
+    for request in server.incoming_requests() {
+        if last_refresh.elapsed() >= refresh_interval {
+            if let Err(e) = manager.refresh() {
+                log::warn!("Refresh failed: {}", e);
+            }
+            last_refresh = Instant::now();
+        }
+
+        let (status, body) = route(&manager, &request);
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("static header is valid"));
+        if let Err(e) = request.respond(response) {
+            log::warn!("Failed to send response: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Pick the response (status code and JSON body) for a request
+fn route(manager: &Manager, request: &Request) -> (u16, String) {
+    if *request.method() != Method::Get {
+        return error(405, "Only GET is supported");
+    }
+
+    // Ignore any query string
+    let path = request.url().split('?').next().unwrap_or("");
+    match path.trim_end_matches('/') {
+        "/processes" => {
+            let mut processes: Vec<ProcessInfo> = manager.iter().map(ProcessInfo::from).collect();
+            processes.sort_by_key(|p| p.pid);
+            json(&processes)
+        }
+        "/system" => json(&system_info(manager)),
+        other => match other.strip_prefix("/processes/").map(str::parse::<u32>) {
+            Some(Ok(pid)) => match manager.processes.get(&pid) {
+                Some(process) => json(&ProcessInfo::from(process)),
+                None => error(404, &format!("No process with PID {}", pid)),
+            },
+            Some(Err(_)) => error(400, "PID must be a number"),
+            None => error(404, "Not found"),
+        },
+    }
+}
+
+fn system_info(manager: &Manager) -> SystemInfo {
+    let memory = system::read_memory_info().unwrap_or(system::MemoryInfo { total_kb: 0, available_kb: 0 });
+    SystemInfo {
+        process_count: manager.processes.len(),
+        state_counts: manager.state_counts(),
+        cpu_percent: manager.system_history.cpu_percent.back().copied(),
+        memory_total_kb: memory.total_kb,
+        memory_available_kb: memory.available_kb,
+        memory_percent: memory.used_percent(),
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> (u16, String) {
+    match serde_json::to_string(value) {
+        Ok(body) => (200, body),
+        Err(e) => error(500, &e.to_string()),
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    let body = serde_json::to_string(&ErrorBody { error: message.to_string() })
+        .unwrap_or_else(|_| "{}".to_string());
+    (status, body)
+}
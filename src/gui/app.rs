@@ -1,18 +1,66 @@
-use crate::process::Process;
+use crate::process::{format_elapsed, format_memory, sched_policy_name, Capabilities, MemoryBreakdown, MemoryUnit, MountEntry, NamespaceInfo, Connection, Process, ResourceLimit, RlimitResource, SchedStats};
+use crate::process::compare;
 use crate::process::tree::ProcessNode;
-use crate::manager::Manager;
+use crate::process::thread::ThreadInfo;
+use crate::manager::{Manager, ManagerError};
+use crate::manager::growth::GrowthKind;
+use crate::manager::monitoring;
+use crate::manager::system::PressureAverages;
 use crate::manager::operations;
-use crate::manager::creation;
-use crate::user::{User, Privilege};
+use crate::manager::creation::{self, SpawnOptions};
+use crate::manager::command::{self, COMMAND_HELP};
+use crate::manager::escalation::EscalationMethod;
+use crate::manager::export::{self, ExportFormat};
+use crate::manager::batch::{self, ZombieOutcome, ZombieReport};
+use crate::manager::query::{self, Query};
+use crate::user::{self, User, Privilege};
+use super::config::{AppConfig, HighlightColors, CONFIG_KEY};
+use super::options::LaunchOptions;
+use super::sorting::{self, CpuDisplay, ProcessComparator, SortColumn};
 use egui::{Color32, RichText, ScrollArea, TextEdit};
-use std::collections::{HashSet, HashMap};
+use egui_plot::{Line, Plot, PlotPoints};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, HashMap, VecDeque};
 use std::time::{Duration, Instant};
 
+/// Background of the FG badge on processes in a terminal's foreground process group
+const FOREGROUND_BADGE: Color32 = Color32::from_rgb(120, 220, 120);
+
+/// How long a process that renamed itself stays highlighted in the table
+const RENAME_HIGHLIGHT: Duration = Duration::from_secs(10);
+
+/// Default number of command-line characters shown per tree node
+pub const DEFAULT_TREE_CMDLINE_WIDTH: usize = 60;
+
+/// Default number of process-name characters shown in the table and tree
+pub const DEFAULT_NAME_MAX_CHARS: usize = 32;
+
+/// Most rows a fuzzy search shows
+const FUZZY_MAX_RESULTS: usize = 200;
+
+/// Most rows the Mounts section lists; container hosts can have thousands of mounts
+const MAX_MOUNT_ROWS: usize = 500;
+
+/// Default time a success message stays up
+pub const DEFAULT_MESSAGE_TTL: Duration = Duration::from_secs(3);
+
+/// Error TTL offered when auto-clearing errors is switched on
+const DEFAULT_ERROR_MESSAGE_TTL: Duration = Duration::from_secs(30);
+
+/// How long a process whose real UID changed stays flagged in the table
+const UID_CHANGE_HIGHLIGHT: Duration = Duration::from_secs(60);
+
+/// How long the CPU cell of a process whose CPU% just jumped flashes (fading out)
+const CPU_SPIKE_FLASH: Duration = Duration::from_millis(1500);
+
 /// Resource thresholds for monitoring abnormal processes
 #[derive(Clone)]
 struct ResourceThresholds {
     cpu_percent: f32,
     memory_mb: u64,
+    spawn_storm: usize, // New processes with one name per refresh that count as a fork storm
+    cpu_spike: f32, // Rise in CPU% (as displayed) between two refreshes that flashes the row
+    pressure: f32, // PSI 10s average (% of time stalled) of memory or I/O that raises a system alert
 }
 
 impl Default for ResourceThresholds {
@@ -20,6 +68,9 @@ impl Default for ResourceThresholds {
         Self {
             cpu_percent: 80.0,
             memory_mb: 1000,
+            spawn_storm: 50,
+            cpu_spike: 30.0,
+            pressure: 10.0,
         }
     }
 }
@@ -30,110 +81,532 @@ pub struct ProcessManagerApp {
     processes_vec: Vec<Process>, // Cached vector for display
     filtered_processes: Vec<usize>, // Indices into processes_vec
     search_filter: String,
+    search_query: Query, // Parsed form of search_filter
+    fuzzy_search: bool, // Rank names by fuzzy_score instead of filtering with the query syntax
     sort_column: SortColumn,
     sort_ascending: bool,
+    abnormal_first: bool, // Zombies and threshold-breakers above everything else, then the sort column
+    foreground_pgrp: Option<(String, u32)>, // (tty, process group) in the foreground of the terminal the search filters on
     last_refresh: Instant,
     refresh_interval: Duration,
     selected_pid: Option<u32>,
     selected_pids: HashSet<u32>, // For batch operations
+    compare_pair: Option<(u32, u32)>, // Processes pinned for side-by-side comparison (otherwise two ticked processes are compared)
+    pinned_pid: Option<u32>, // Process being followed across refreshes
+    scroll_to_pinned: bool, // Scroll the followed row into view on the next frame
+    follow_notice: Option<String>, // Shown when the followed process exits, until dismissed
+    selection_notice: Option<String>, // Shown when the selected process exits, until dismissed
     error_message: Option<String>,
     success_message: Option<String>,
     success_message_time: Option<Instant>, // Track when success message was set
+    error_message_time: Option<Instant>, // Track when error message was set
+    message_ttl: Duration, // How long success messages stay up
+    error_message_ttl: Option<Duration>, // How long error messages stay up (None = until dismissed)
+    escalation_message: Option<String>, // Result of the last sudo/pkexec retry, shown until dismissed
     auto_refresh: bool,
+    refresh_in_background: bool, // Keep auto-refreshing while the window is unfocused or minimized
     show_tree_view: bool,
+    show_system_graphs: bool,
+    show_favorites: bool,
+    favorites: Vec<String>, // Starred process names, kept by name so they survive restarts (new PIDs)
     show_threshold_config: bool,
     thresholds: ResourceThresholds,
     priority_input: String,
+    priority_recursive: bool, // Also renice the selected process's descendants
     show_create_process: bool,
     create_process_command: String,
     create_process_args: String,
     create_process_background: bool,
+    create_process_log_path: String, // Optional file for a background process's output (empty = discard)
+    create_process_nice: String,     // Optional nice value for a background process (empty = inherit)
+    create_process_affinity: String, // Optional CPU list such as "0-3,6" (empty = any CPU)
+    create_process_cgroup: String,   // Optional cgroup to start it in (empty = ours)
     show_only_zombies: bool,
+    show_only_stuck: bool, // Only list processes in uninterruptible sleep (D)
+    show_only_stale: bool, // Only list processes running a deleted executable
+    zombie_reports: Vec<ZombieReport>, // Zombies the last cleanup couldn't clear
+    show_zombie_cleanup: bool,
+    full_report: Option<(u32, String)>, // PID and text of the open "Full Report" window
+    command_palette: Option<String>, // Text typed in the Ctrl+P command palette, if open
+    export_dot_path: Option<String>, // Destination being edited in the "Export Tree (DOT)" window, if open
+    export_processes_path: Option<String>, // Destination being edited in the "Export Processes" window, if open
+    export_format: ExportFormat,
+    export_filtered_only: bool, // Export what the table shows (filtered, in display order) rather than every process
+    recent_renames: HashMap<u32, (String, Instant)>, // PID -> previous name and when the rename was noticed
+    recent_uid_changes: HashMap<u32, (u32, Instant)>, // PID -> previous real UID and when the change was noticed
+    cpu_spikes: HashMap<u32, (f32, Instant)>, // PID -> CPU% rise (as displayed) and when it was seen, while flashing
+    thread_cache: Option<(u32, Result<Vec<ThreadInfo>, String>)>, // Threads of the selected process, cleared on refresh
+    namespace_cache: Option<(u32, NamespaceInfo)>, // Root/mount namespace of the selected process, cleared on refresh
+    mount_cache: Option<(u32, Result<Vec<MountEntry>, String>)>, // Mount table of the selected process, cleared on refresh
+    connection_cache: Option<(u32, Result<Vec<Connection>, String>)>, // Sockets of the selected process, cleared on refresh
+    numa_cache: Option<(u32, Option<i32>)>, // NUMA node of the selected process, cleared on refresh
+    memory_cache: Option<(u32, Option<MemoryBreakdown>)>, // smaps_rollup of the selected process, cleared on refresh
+    rlimit_cache: Option<(u32, Result<Vec<ResourceLimit>, String>)>, // Limits of the selected process, cleared on refresh
+    capability_cache: Option<(u32, Result<Capabilities, String>)>, // Decoded capability sets of the selected process, cleared on refresh
+    sched_sample: Option<(u32, Option<SchedStats>, Instant)>, // Last schedstat reading of the selected process (taken each refresh)
+    sched_wait_rate: Option<f64>, // Runqueue wait of the selected process in ms/s between its last two readings
+    rlimit_resource: RlimitResource, // Resource chosen in the details panel's "Set limit" form
+    rlimit_soft_input: String,
+    rlimit_hard_input: String,
+    visible_columns: Vec<Column>, // Table columns to display, in order
+    max_rows: usize, // Render at most this many table rows (0 = unlimited)
+    smooth_cpu: bool, // Show and sort by the moving-average CPU% instead of the raw per-refresh value
+    normalize_cpu: bool, // Show CPU% as a share of the whole machine (0-100) instead of per core (0-100 × cores)
+    cpu_cores: f32, // Core count, read once at startup
+    state_glyphs: bool, // Prefix states with a shape (● R, ◐ S, ...) so they don't rely on color alone
+    new_process_highlight_secs: u64, // Tint processes younger than this (0 = off)
+    memory_unit: MemoryUnit, // Unit for memory sizes in the table, details and footer
+    tree_show_cmdline: bool, // Show each node's command line after its name in the tree view
+    tree_cmdline_width: usize, // Characters of command line shown before truncating with "…"
+    name_max_chars: usize, // Characters of process name shown in the table and tree; the full name is in the tooltip
+    collapsed_tree_nodes: HashSet<u32>, // PIDs whose subtree is hidden in the tree view (everything else is expanded)
+    colors: HighlightColors, // Abnormal/threshold/state highlight colors
+    show_protection_config: bool,
+    protect_input: String, // Name or PID being added in the "Protected Processes" window
+    launch_protected_names: HashSet<String>, // Names from --protect: enforced this run, but not saved to the config
+    last_killed_batch: Vec<(String, Vec<String>)>, // (command, args) of each process in the last batch kill, for "Undo"
+    show_undo_kill: bool,
+    select_pids_input: String, // PID list such as "1001,1002,2000-2010" to select for batch operations
+}
+
+/// Actions offered by the tree view's right-click menu
+#[derive(Clone, Copy)]
+enum TreeAction {
+    Kill,
+    Terminate,
+    Pause,
+    Resume,
+    KillSubtree,  // The process and all its descendants
+    PauseSubtree, // The process and all its descendants
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum SortColumn {
+/// A data column that can be shown or hidden in the process table
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Column {
     Pid,
     Name,
     Uid,
     State,
     Cpu,
+    CpuTime,
     Memory,
     Priority,
     Timer,
+    LastCpu,
 }
 
-impl Default for ProcessManagerApp {
-    fn default() -> Self {
-        // Create a default admin user for GUI
-        let admin_user = User::new(0, "admin", Privilege::Admin);
-        let manager = Manager::new(admin_user.clone()).unwrap_or_else(|e| {
-            eprintln!("Failed to initialize manager: {}", e);
-            // Create a minimal manager if initialization fails
-            Manager {
-                processes: HashMap::new(),
-                active_user: admin_user,
-                root_pid: 1,
-                previous_cpu_times: HashMap::new(),
-            }
-        });
-        
+impl Column {
+    /// Every column, in the order they appear in the table
+    pub const ALL: [Column; 10] = [
+        Column::Pid,
+        Column::Name,
+        Column::Uid,
+        Column::State,
+        Column::Cpu,
+        Column::CpuTime,
+        Column::Memory,
+        Column::Priority,
+        Column::Timer,
+        Column::LastCpu,
+    ];
+
+    /// Columns shown until the user picks their own set (optional columns are left off)
+    pub fn defaults() -> Vec<Column> {
+        Column::ALL
+            .into_iter()
+            .filter(|&c| !matches!(c, Column::LastCpu | Column::CpuTime))
+            .collect()
+    }
+
+    /// Header text for the column
+    fn label(self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Name => "Name",
+            Column::Uid => "UID",
+            Column::State => "State",
+            Column::Cpu => "CPU %",
+            Column::CpuTime => "CPU Time",
+            Column::Memory => "Memory",
+            Column::Priority => "Priority",
+            Column::Timer => "Timer",
+            Column::LastCpu => "Core",
+        }
+    }
+
+    /// Sort order applied when the column header is clicked
+    fn sort_column(self) -> SortColumn {
+        match self {
+            Column::Pid => SortColumn::Pid,
+            Column::Name => SortColumn::Name,
+            Column::Uid => SortColumn::Uid,
+            Column::State => SortColumn::State,
+            Column::Cpu => SortColumn::Cpu,
+            Column::CpuTime => SortColumn::CpuTime,
+            Column::Memory => SortColumn::Memory,
+            Column::Priority => SortColumn::Priority,
+            Column::Timer => SortColumn::Timer,
+            Column::LastCpu => SortColumn::LastCpu,
+        }
+    }
+}
+
+impl ProcessManagerApp {
+    /// App state around an initialized manager, with every setting at its default
+    fn with_manager(manager: Manager) -> Self {
         Self {
             manager,
             processes_vec: Vec::new(),
             filtered_processes: Vec::new(),
             search_filter: String::new(),
+            search_query: Query::default(),
+            fuzzy_search: false,
             sort_column: SortColumn::Pid,
             sort_ascending: true,
+            abnormal_first: false,
+            foreground_pgrp: None,
             last_refresh: Instant::now(),
             refresh_interval: Duration::from_secs(2),
             selected_pid: None,
             selected_pids: HashSet::new(),
+            compare_pair: None,
+            pinned_pid: None,
+            scroll_to_pinned: false,
+            follow_notice: None,
+            selection_notice: None,
             error_message: None,
             success_message: None,
             success_message_time: None,
+            error_message_time: None,
+            message_ttl: DEFAULT_MESSAGE_TTL,
+            error_message_ttl: None,
+            escalation_message: None,
             auto_refresh: true,
+            refresh_in_background: false,
             show_tree_view: false,
+            show_system_graphs: true,
+            show_favorites: true,
+            favorites: Vec::new(),
             show_threshold_config: false,
             thresholds: ResourceThresholds::default(),
             priority_input: String::new(),
+            priority_recursive: false,
             show_create_process: false,
             create_process_command: String::new(),
             create_process_args: String::new(),
             create_process_background: false,
+            create_process_log_path: String::new(),
+            create_process_nice: String::new(),
+            create_process_affinity: String::new(),
+            create_process_cgroup: String::new(),
             show_only_zombies: false,
+            show_only_stuck: false,
+            show_only_stale: false,
+            zombie_reports: Vec::new(),
+            show_zombie_cleanup: false,
+            full_report: None,
+            command_palette: None,
+            export_dot_path: None,
+            export_processes_path: None,
+            export_format: ExportFormat::default(),
+            export_filtered_only: true,
+            recent_renames: HashMap::new(),
+            recent_uid_changes: HashMap::new(),
+            cpu_spikes: HashMap::new(),
+            thread_cache: None,
+            namespace_cache: None,
+            mount_cache: None,
+            connection_cache: None,
+            numa_cache: None,
+            memory_cache: None,
+            rlimit_cache: None,
+            capability_cache: None,
+            sched_sample: None,
+            sched_wait_rate: None,
+            rlimit_resource: RlimitResource::OpenFiles,
+            rlimit_soft_input: String::new(),
+            rlimit_hard_input: String::new(),
+            visible_columns: Column::defaults(),
+            max_rows: 0,
+            smooth_cpu: true,
+            normalize_cpu: false,
+            cpu_cores: monitoring::num_cores().max(1.0),
+            state_glyphs: true,
+            new_process_highlight_secs: 5,
+            memory_unit: MemoryUnit::default(),
+            tree_show_cmdline: false,
+            tree_cmdline_width: DEFAULT_TREE_CMDLINE_WIDTH,
+            name_max_chars: DEFAULT_NAME_MAX_CHARS,
+            collapsed_tree_nodes: HashSet::new(),
+            colors: HighlightColors::default(),
+            show_protection_config: false,
+            last_killed_batch: Vec::new(),
+            show_undo_kill: false,
+            select_pids_input: String::new(),
+            protect_input: String::new(),
+            launch_protected_names: HashSet::new(),
         }
     }
 }
 
 impl ProcessManagerApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let mut app = Self::default();
+    /// Load the process list and apply saved settings and launch options.
+    /// Fails if /proc can't be read; the caller shows the error instead of an empty app.
+    pub(super) fn new(config: Option<AppConfig>, options: LaunchOptions) -> Result<Self, ManagerError> {
+        // The GUI acts as an admin user; actual permissions are enforced by the kernel
+        let manager = Manager::new(User::new(0, "admin", Privilege::Admin))?;
+        let mut app = Self::with_manager(manager);
+        // Restore settings saved by a previous run
+        if let Some(config) = config {
+            app.apply_config(config);
+        }
         app.refresh_processes();
-        app
+        app.manager.dry_run = options.dry_run;
+        app.manager.read_only = options.read_only;
+        for name in options.protect_names {
+            // Already remembered from the config: keep saving it
+            if app.manager.protected_names.insert(name.clone()) {
+                app.launch_protected_names.insert(name);
+            }
+        }
+        if let Some(path) = options.audit_log
+            && let Err(e) = app.manager.enable_audit(path)
+        {
+            app.set_error(e);
+        }
+        Ok(app)
+    }
+
+    /// Snapshot the persistable settings
+    fn config(&self) -> AppConfig {
+        AppConfig {
+            visible_columns: self.visible_columns.clone(),
+            refresh_in_background: self.refresh_in_background,
+            system_history_len: self.manager.system_history.capacity(),
+            max_rows: self.max_rows,
+            smooth_cpu: self.smooth_cpu,
+            normalize_cpu: self.normalize_cpu,
+            state_glyphs: self.state_glyphs,
+            group_threads: self.manager.group_threads,
+            message_ttl: self.message_ttl,
+            error_message_ttl: self.error_message_ttl,
+            new_process_highlight_secs: self.new_process_highlight_secs,
+            memory_unit: self.memory_unit,
+            tree_show_cmdline: self.tree_show_cmdline,
+            tree_cmdline_width: self.tree_cmdline_width,
+            name_max_chars: self.name_max_chars,
+            favorites: self.favorites.clone(),
+            cpu_smoothing_alpha: self.manager.cpu_smoothing_alpha,
+            colors: self.colors.clone(),
+            protected_names: {
+                let mut names: Vec<String> = self.manager.protected_names
+                    .iter()
+                    .filter(|name| !self.launch_protected_names.contains(*name))
+                    .cloned()
+                    .collect();
+                names.sort();
+                names
+            },
+        }
+    }
+
+    /// Apply settings loaded from storage
+    fn apply_config(&mut self, config: AppConfig) {
+        self.visible_columns = config.visible_columns;
+        self.refresh_in_background = config.refresh_in_background;
+        self.manager.system_history.set_capacity(config.system_history_len);
+        self.max_rows = config.max_rows;
+        self.smooth_cpu = config.smooth_cpu;
+        self.normalize_cpu = config.normalize_cpu;
+        self.state_glyphs = config.state_glyphs;
+        self.manager.set_group_threads(config.group_threads);
+        self.message_ttl = config.message_ttl;
+        self.error_message_ttl = config.error_message_ttl;
+        self.new_process_highlight_secs = config.new_process_highlight_secs;
+        self.memory_unit = config.memory_unit;
+        self.tree_show_cmdline = config.tree_show_cmdline;
+        self.tree_cmdline_width = config.tree_cmdline_width.max(10);
+        self.name_max_chars = config.name_max_chars.max(4);
+        self.favorites = config.favorites;
+        self.colors = config.colors;
+        self.manager.protected_names = config.protected_names.into_iter().collect();
+        self.manager.cpu_smoothing_alpha = config.cpu_smoothing_alpha.clamp(0.05, 1.0);
+    }
+
+    /// Read pid's schedstat, computing the runqueue wait rate since the previous reading if that
+    /// was of the same process (otherwise the rate is unknown until the next reading)
+    fn sample_sched_stats(&mut self, pid: u32) {
+        let stats = Process::sched_stats(pid);
+        let now = Instant::now();
+        self.sched_wait_rate = match (&self.sched_sample, &stats) {
+            (Some((sampled_pid, Some(earlier), at)), Some(current)) if *sampled_pid == pid => {
+                Some(current.wait_ms_per_sec(earlier, now.duration_since(*at)))
+            }
+            _ => None,
+        };
+        self.sched_sample = Some((pid, stats, now));
+    }
+
+    /// Whether the process started recently enough to get the "new" tint
+    fn is_new(&self, process: &Process) -> bool {
+        self.new_process_highlight_secs > 0 && process.pcb_data.uptime_seconds < self.new_process_highlight_secs
+    }
+
+    /// CPU percentage to display and sort by (smoothed or raw, per core or of the whole machine, per the View menu)
+    fn cpu_percent(&self, process: &Process) -> f32 {
+        self.cpu_display().percent(process)
+    }
+
+    /// The CPU% settings as a value a comparator can own
+    fn cpu_display(&self) -> CpuDisplay {
+        CpuDisplay {
+            smooth: self.smooth_cpu,
+            per_machine: self.normalize_cpu.then_some(self.cpu_cores),
+        }
+    }
+
+    /// State letter as displayed, with its shape in front when state glyphs are on.
+    /// Render it monospace: only the monospace font has the shapes.
+    fn state_label(&self, state: char) -> String {
+        match state_glyph(state) {
+            Some(glyph) if self.state_glyphs => format!("{} {}", glyph, state),
+            _ => state.to_string(),
+        }
+    }
+
+    /// Show or hide a table column, keeping columns in their canonical order
+    fn set_column_visible(&mut self, column: Column, visible: bool) {
+        if visible {
+            if !self.visible_columns.contains(&column) {
+                self.visible_columns.push(column);
+            }
+        } else {
+            self.visible_columns.retain(|&c| c != column);
+        }
+        self.visible_columns
+            .sort_by_key(|c| Column::ALL.iter().position(|a| a == c));
+    }
+
+    /// Show an error banner, restarting its expiry timer
+    fn set_error(&mut self, error: String) {
+        self.error_message = Some(error);
+        self.error_message_time = Some(Instant::now());
     }
 
     /// Refresh the process list from /proc filesystem using Manager
     fn refresh_processes(&mut self) {
-        self.error_message = None;
-        // Note: Don't clear success_message here - let it persist so user can see it
+        // Note: Don't clear success_message or error_message here - they expire on their own (see message_ttl)
         
         // Use Manager's refresh method
         match self.manager.refresh() {
-            Ok(_) => {
+            Ok(delta) => {
+                // Remember renames for a while so the rows can be flagged
+                let now = Instant::now();
+                for (pid, old_name, _) in delta.renamed {
+                    self.recent_renames.insert(pid, (old_name, now));
+                }
+                self.recent_renames.retain(|pid, (_, at)| {
+                    now.duration_since(*at) < RENAME_HIGHLIGHT && self.manager.processes.contains_key(pid)
+                });
+                // UID changes may be privilege escalations, so they stay flagged longer
+                for (pid, old_uid, _) in delta.uid_changed {
+                    self.recent_uid_changes.insert(pid, (old_uid, now));
+                }
+                self.recent_uid_changes.retain(|pid, (_, at)| {
+                    now.duration_since(*at) < UID_CHANGE_HIGHLIGHT && self.manager.processes.contains_key(pid)
+                });
+                // Flash processes whose CPU% jumped since the last refresh
+                let scale = if self.normalize_cpu { self.cpu_cores.max(1.0) } else { 1.0 };
+                self.cpu_spikes = self.manager.top_cpu_delta(usize::MAX)
+                    .into_iter()
+                    .map(|p| (p.process_id, p.pcb_data.cpu_percent_delta / scale))
+                    .take_while(|&(_, rise)| rise > self.thresholds.cpu_spike)
+                    .map(|(pid, rise)| (pid, (rise, now)))
+                    .collect();
+
+                self.thread_cache = None;
+                self.namespace_cache = None;
+                self.mount_cache = None;
+                self.connection_cache = None;
+                self.numa_cache = None;
+                self.memory_cache = None;
+                self.rlimit_cache = None;
+                self.capability_cache = None;
+                if let Some(pid) = self.selected_pid {
+                    self.sample_sched_stats(pid);
+                }
+
+                // Keep following the pinned process, or report that it's gone
+                let mut pinned_exited = None;
+                if let Some(pid) = self.pinned_pid {
+                    if self.manager.processes.contains_key(&pid) {
+                        self.scroll_to_pinned = true;
+                    } else {
+                        self.follow_notice = Some(format!("Process {} has exited", pid));
+                        self.pinned_pid = None;
+                        pinned_exited = Some(pid);
+                    }
+                }
+
+                // Keep the selection across refreshes, dropping only processes that have exited
+                if let Some(pid) = self.selected_pid
+                    && !self.manager.processes.contains_key(&pid)
+                {
+                    // A followed process already got its own notice above
+                    if pinned_exited != Some(pid) {
+                        self.selection_notice = Some(format!("Selected process {} has exited", pid));
+                    }
+                    self.selected_pid = None;
+                }
+                self.selected_pids.retain(|pid| self.manager.processes.contains_key(pid));
+                // Forget collapsed nodes that exited, so a reused PID starts out expanded
+                self.collapsed_tree_nodes.retain(|pid| self.manager.processes.contains_key(pid));
+
                 // Update cached vector from manager
-                self.processes_vec = self.manager.processes().into_iter().cloned().collect();
+                self.processes_vec = self.manager.iter().cloned().collect();
                 self.apply_filters_and_sort();
                 self.last_refresh = Instant::now();
             }
             Err(e) => {
-                self.error_message = Some(format!("Failed to refresh processes: {}", e));
+                self.set_error(format!("Failed to refresh processes: {}", e));
             }
         }
     }
 
-    /// Apply search filter and sorting
+    /// Apply search filter and the current sort settings
     fn apply_filters_and_sort(&mut self) {
+        let compare = self.comparator();
+        self.apply_filters_and_sort_by(compare);
+    }
+
+    /// Apply search filter, then order the table with any comparator (fuzzy search keeps its own ranking)
+    fn apply_filters_and_sort_by(&mut self, compare: ProcessComparator) {
+        self.search_query = Query::parse(&self.search_filter);
+        self.foreground_pgrp = self
+            .search_query
+            .tty()
+            .and_then(|tty| Some((tty.to_string(), self.manager.foreground_pgrp(tty)?)));
+
+        let pattern = self.search_filter.trim();
+        if self.fuzzy_search && !pattern.is_empty() {
+            // Best matches first, capped so a short pattern can't flood the table
+            let mut scored: Vec<(u32, usize)> = self
+                .processes_vec
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| {
+                    (!self.show_only_zombies || p.pcb_data.state == 'Z')
+                        && (!self.show_only_stuck || p.pcb_data.state == 'D')
+                        && (!self.show_only_stale || p.exe_deleted)
+                })
+                .filter_map(|(idx, p)| query::fuzzy_score(pattern, &p.name).map(|score| (score, idx)))
+                .collect();
+            scored.sort_by_key(|&(score, idx)| (std::cmp::Reverse(score), self.processes_vec[idx].process_id));
+            scored.truncate(FUZZY_MAX_RESULTS);
+            self.filtered_processes = scored.into_iter().map(|(_, idx)| idx).collect();
+            return;
+        }
+
         // Filter processes
         self.filtered_processes = self
             .processes_vec
@@ -144,54 +617,93 @@ impl ProcessManagerApp {
                 if self.show_only_zombies && p.pcb_data.state != 'Z' {
                     return false;
                 }
-                
-                // Filter by search term
-                if self.search_filter.is_empty() {
-                    return true;
+                if self.show_only_stuck && p.pcb_data.state != 'D' {
+                    return false;
+                }
+                if self.show_only_stale && !p.exe_deleted {
+                    return false;
                 }
-                let filter_lower = self.search_filter.to_lowercase();
-                p.name.to_lowercase().contains(&filter_lower)
-                    || p.process_id.to_string().contains(&filter_lower)
-                    || p.user_id.to_string().contains(&filter_lower)
+                
+                // Filter by search query
+                self.search_query.matches(p)
             })
             .map(|(idx, _)| idx)
             .collect();
 
         // Sort filtered indices
-        self.filtered_processes.sort_by(|&a, &b| {
-            let cmp = match self.sort_column {
-                SortColumn::Pid => self.processes_vec[a].process_id.cmp(&self.processes_vec[b].process_id),
-                SortColumn::Name => self.processes_vec[a].name.cmp(&self.processes_vec[b].name),
-                SortColumn::Uid => self.processes_vec[a].user_id.cmp(&self.processes_vec[b].user_id),
-                SortColumn::State => self.processes_vec[a]
-                    .pcb_data
-                    .state
-                    .cmp(&self.processes_vec[b].pcb_data.state),
-                SortColumn::Cpu => self.processes_vec[a]
-                    .pcb_data
-                    .cpu_percent
-                    .partial_cmp(&self.processes_vec[b].pcb_data.cpu_percent)
-                    .unwrap_or(std::cmp::Ordering::Equal),
-                SortColumn::Memory => self.processes_vec[a]
-                    .pcb_data
-                    .memory_rss_mb
-                    .cmp(&self.processes_vec[b].pcb_data.memory_rss_mb),
-                SortColumn::Priority => self.processes_vec[a]
-                    .pcb_data
-                    .priority
-                    .cmp(&self.processes_vec[b].pcb_data.priority),
-                SortColumn::Timer => self.processes_vec[a]
-                    .pcb_data
-                    .uptime_seconds
-                    .cmp(&self.processes_vec[b].pcb_data.uptime_seconds),
-            };
+        let processes = &self.processes_vec;
+        self.filtered_processes.sort_by(|&a, &b| compare(&processes[a], &processes[b]));
+    }
 
-            if self.sort_ascending {
-                cmp
-            } else {
-                cmp.reverse()
+    /// Ordering chosen in the UI: the sort column and direction, with abnormal processes
+    /// first when that is enabled (also used for tree siblings)
+    fn comparator(&self) -> ProcessComparator {
+        let by_column = sorting::by_column(self.sort_column, self.sort_ascending, self.cpu_display());
+        if !self.abnormal_first {
+            return by_column;
+        }
+        let abnormal = self
+            .processes_vec
+            .iter()
+            .filter(|p| self.is_abnormal(p))
+            .map(|p| p.process_id)
+            .collect();
+        sorting::then(sorting::pids_first(abnormal), by_column)
+    }
+
+    /// Pair to compare side by side: the pinned one, else exactly two processes ticked in the table
+    fn comparison_pids(&self) -> Option<(u32, u32)> {
+        if self.compare_pair.is_some() {
+            return self.compare_pair;
+        }
+        let mut ticked = self.selected_pids.iter().copied();
+        match (ticked.next(), ticked.next(), ticked.next()) {
+            (Some(a), Some(b), None) => Some((a.min(b), a.max(b))),
+            _ => None,
+        }
+    }
+
+    /// Two-column comparison of CPU, RSS, threads, FDs and uptime, the larger value of each row highlighted
+    fn show_comparison(&mut self, ui: &mut egui::Ui, a_pid: u32, b_pid: u32) {
+        let pinned = self.compare_pair.is_some();
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("⚖ Comparison").strong());
+            if pinned {
+                if ui.small_button("Unpin").on_hover_text("Compare whichever two processes are ticked instead").clicked() {
+                    self.compare_pair = None;
+                }
+            } else if ui.small_button("📌 Pin Pair").on_hover_text("Keep comparing these two when the selection changes").clicked() {
+                self.compare_pair = Some((a_pid, b_pid));
             }
         });
+        let (Some(a), Some(b)) = (self.manager.find_by_pid(a_pid), self.manager.find_by_pid(b_pid)) else {
+            ui.colored_label(Color32::YELLOW, format!("PID {} or {} has exited", a_pid, b_pid));
+            return;
+        };
+        let larger = |value: String, is_larger: bool| {
+            if is_larger {
+                RichText::new(value).strong().color(Color32::from_rgb(255, 200, 100))
+            } else {
+                RichText::new(value)
+            }
+        };
+        egui::Grid::new("comparison_grid")
+            .num_columns(3)
+            .spacing([10.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("");
+                ui.label(RichText::new(format!("{} ({})", a.name, a.process_id)).strong());
+                ui.label(RichText::new(format!("{} ({})", b.name, b.process_id)).strong());
+                ui.end_row();
+
+                for (field, a_value, b_value) in compare(a, b) {
+                    ui.label(format!("{}:", field.label()));
+                    ui.label(larger(field.format(a_value), a_value > b_value));
+                    ui.label(larger(field.format(b_value), b_value > a_value));
+                    ui.end_row();
+                }
+            });
     }
 
     /// Get selected process details
@@ -200,6 +712,28 @@ impl ProcessManagerApp {
             .and_then(|pid| self.processes_vec.iter().find(|p| p.process_id == pid))
     }
 
+    /// Render a sortable column header; clicking the active column flips the direction
+    fn sort_header(&mut self, ui: &mut egui::Ui, column: SortColumn, label: &str) {
+        let is_active = self.sort_column == column;
+        if ui
+            .selectable_label(
+                is_active,
+                RichText::new(label)
+                    .strong()
+                    .color(if is_active { Color32::YELLOW } else { Color32::WHITE }),
+            )
+            .clicked()
+        {
+            if is_active {
+                self.sort_ascending = !self.sort_ascending;
+            } else {
+                self.sort_column = column;
+                self.sort_ascending = true;
+            }
+            self.apply_filters_and_sort();
+        }
+    }
+
     /// Toggle selection of a process for batch operations
     fn toggle_selection(&mut self, pid: u32) {
         if self.selected_pids.contains(&pid) {
@@ -214,26 +748,26 @@ impl ProcessManagerApp {
         self.selected_pids.clear();
     }
 
-    /// Check if process is abnormal (zombie, deadlock, or exceeds thresholds)
+    /// Check if process is abnormal (zombie, deadlock, leaking, or exceeds thresholds)
     fn is_abnormal(&self, process: &Process) -> bool {
         process.pcb_data.state == 'Z' // Zombie
             || self.is_deadlocked(process) // Deadlock detection
-            || process.pcb_data.cpu_percent > self.thresholds.cpu_percent
-            || process.pcb_data.memory_rss_mb > self.thresholds.memory_mb
+            || self.is_growing(process) // Memory/FD/thread leak
+            || self.cpu_percent(process) > self.thresholds.cpu_percent
+            || process.rss_mb() > self.thresholds.memory_mb as f64
+    }
+
+    /// Whether RSS, open FDs or threads are rising faster than the configured growth limits
+    fn is_growing(&self, process: &Process) -> bool {
+        !self.manager.growth.growing(process.process_id).is_empty()
     }
     
     /// Basic deadlock detection heuristic
-    /// Detects processes that are in uninterruptible sleep (D state) for extended periods
+    /// Detects processes that have stayed in uninterruptible sleep (D state) across several refreshes
     /// This is a simple heuristic - true deadlock detection would require resource dependency analysis
     fn is_deadlocked(&self, process: &Process) -> bool {
-        // Process in uninterruptible sleep (D state) for more than 30 seconds
-        // This is a heuristic - processes stuck in D state often indicate I/O deadlocks
-        if process.pcb_data.state == 'D' && process.pcb_data.uptime_seconds > 30 {
-            // Additional check: if process has been running for a while but is stuck in D state
-            // and hasn't made progress, it might be deadlocked
-            return true;
-        }
-        false
+        // Processes stuck in D state often indicate hung I/O (e.g. an unresponsive NFS server)
+        self.manager.is_stuck_in_d(process.process_id)
     }
 
     /// Get abnormality reason for display
@@ -243,21 +777,31 @@ impl ProcessManagerApp {
             reasons.push("Zombie process".to_string());
         }
         if self.is_deadlocked(process) {
+            let refreshes = self.manager.d_state_streaks.get(&process.process_id).copied().unwrap_or(0);
             reasons.push(format!(
-                "Possible deadlock (uninterruptible sleep for {}s)",
-                process.pcb_data.uptime_seconds
+                "Possible hung I/O (uninterruptible sleep for {} consecutive refreshes)",
+                refreshes
             ));
         }
-        if process.pcb_data.cpu_percent > self.thresholds.cpu_percent {
+        let growing = self.manager.growth.growing(process.process_id);
+        if !growing.is_empty() {
+            for (kind, rate) in self.manager.growth.rates(process.process_id).unwrap_or_default() {
+                if growing.contains(&kind) {
+                    let unit = if kind == GrowthKind::Memory { " MB" } else { "" };
+                    reasons.push(format!("Growing {}: +{:.0}{}/min", kind.label(), rate, unit));
+                }
+            }
+        }
+        if self.cpu_percent(process) > self.thresholds.cpu_percent {
             reasons.push(format!(
                 "CPU usage {:.1}% exceeds threshold {:.1}%",
-                process.pcb_data.cpu_percent, self.thresholds.cpu_percent
+                self.cpu_percent(process), self.thresholds.cpu_percent
             ));
         }
-        if process.pcb_data.memory_rss_mb > self.thresholds.memory_mb {
+        if process.rss_mb() > self.thresholds.memory_mb as f64 {
             reasons.push(format!(
-                "Memory usage {} MB exceeds threshold {} MB",
-                process.pcb_data.memory_rss_mb, self.thresholds.memory_mb
+                "Memory usage {:.1} MB exceeds threshold {} MB",
+                process.rss_mb(), self.thresholds.memory_mb
             ));
         }
         if reasons.is_empty() {
@@ -273,21 +817,20 @@ impl ProcessManagerApp {
         let tree = self.manager.build_process_tree()?;
         
         // If search filter is active, filter the tree
-        if !self.search_filter.is_empty() {
-            Some(self.filter_tree(&tree))
+        let mut tree = if !self.search_query.is_empty() {
+            self.filter_tree(&tree)
         } else {
-            Some(tree)
-        }
+            tree
+        };
+        sort_tree(&mut tree, &self.comparator());
+        Some(tree)
     }
-    
+
     /// Filter tree to only include processes matching search criteria
     fn filter_tree(&self, node: &ProcessNode) -> ProcessNode {
         use crate::process::tree::ProcessNode;
         let process = &node.process;
-        let filter_lower = self.search_filter.to_lowercase();
-        let matches = process.name.to_lowercase().contains(&filter_lower)
-            || process.process_id.to_string().contains(&filter_lower)
-            || process.user_id.to_string().contains(&filter_lower);
+        let matches = self.search_query.matches(process);
         
         // Filter children first
         let filtered_children: Vec<ProcessNode> = node.children
@@ -295,10 +838,7 @@ impl ProcessManagerApp {
             .map(|child| self.filter_tree(child))
             .filter(|child| {
                 // Keep if child matches or has matching descendants
-                let child_matches = child.process.name.to_lowercase().contains(&filter_lower)
-                    || child.process.process_id.to_string().contains(&filter_lower)
-                    || child.process.user_id.to_string().contains(&filter_lower);
-                child_matches || !child.children.is_empty()
+                self.search_query.matches(&child.process) || !child.children.is_empty()
             })
             .collect();
         
@@ -319,6 +859,7 @@ impl ProcessManagerApp {
         let is_abnormal = self.is_abnormal(process);
         let is_selected = self.selected_pids.contains(&process.process_id);
         let has_children = !node.children.is_empty();
+        let is_collapsed = has_children && self.collapsed_tree_nodes.contains(&process.process_id);
 
         // Build tree connector
         let connector = if depth == 0 {
@@ -338,7 +879,12 @@ impl ProcessManagerApp {
             format!("{}│  ", prefix) // Vertical line for non-last children
         };
 
+        let mut tree_action = None;
+        let is_current = self.selected_pid == Some(process.process_id);
         ui.horizontal(|ui| {
+            // Reserve a shape behind the row for the selection/hover highlight, filled in once the row's size is known
+            let highlight = ui.painter().add(egui::Shape::Noop);
+
             // Tree connector with styling
             ui.label(
                 RichText::new(&connector)
@@ -346,6 +892,23 @@ impl ProcessManagerApp {
                     .monospace()
             );
 
+            // Expand/collapse toggle (leaves get a blank of the same width so names stay aligned)
+            if has_children {
+                let toggle = ui.add(
+                    egui::Button::new(RichText::new(if is_collapsed { "▶" } else { "▼" }).monospace())
+                        .frame(false)
+                );
+                if toggle.on_hover_text(if is_collapsed { "Expand" } else { "Collapse" }).clicked() {
+                    if is_collapsed {
+                        self.collapsed_tree_nodes.remove(&process.process_id);
+                    } else {
+                        self.collapsed_tree_nodes.insert(process.process_id);
+                    }
+                }
+            } else {
+                ui.label(RichText::new(" ").monospace());
+            }
+
             // Checkbox for batch selection
             let mut checked = is_selected;
             if ui.checkbox(&mut checked, "").changed() {
@@ -353,8 +916,12 @@ impl ProcessManagerApp {
             }
 
             // Process info with better formatting
-            let name_color = if is_abnormal {
-                Color32::YELLOW
+            let name_color = if self.is_growing(process) {
+                self.colors.growing
+            } else if process.exe_deleted {
+                self.colors.stale_binary
+            } else if is_abnormal {
+                self.colors.abnormal
             } else if depth == 0 {
                 Color32::from_rgb(100, 200, 100) // Light green for root
             } else {
@@ -362,49 +929,46 @@ impl ProcessManagerApp {
             };
 
             // State color
-            let state_color = match process.pcb_data.state {
-                'R' => Color32::GREEN,
-                'S' => Color32::BLUE,
-                'D' => Color32::RED,
-                'Z' => Color32::YELLOW,
-                'T' => Color32::GRAY,
-                _ => Color32::WHITE,
-            };
+            let state_color = self.colors.state(process.pcb_data.state);
 
             // Build process display text
             let pid_text = RichText::new(format!("PID:{}", process.process_id))
                 .strong()
                 .color(Color32::from_rgb(100, 150, 255));
             
-            let name_text = RichText::new(&process.name)
+            let mut name_text = RichText::new(truncate_with_ellipsis(&process.name, self.name_max_chars))
                 .color(name_color)
                 .strong();
+            if self.is_new(process) {
+                name_text = name_text.background_color(self.colors.new_process);
+            }
             
-            let state_text = RichText::new(format!("[{}]", process.pcb_data.state))
+            let state_text = RichText::new(format!("[{}]", self.state_label(process.pcb_data.state)))
                 .color(state_color)
                 .monospace();
             
-            let mem_text = RichText::new(format!("{:.1}MB", process.pcb_data.memory_rss_mb))
+            let mem_text = RichText::new(format_memory(process.pcb_data.memory_rss_kb, self.memory_unit))
                 .color(Color32::from_rgb(255, 200, 100));
 
             // Display process info with colored components
             ui.horizontal(|ui| {
-                let pid_click = ui.selectable_label(
-                    self.selected_pid == Some(process.process_id),
-                    pid_text
-                );
-                if pid_click.clicked() {
-                    self.selected_pid = Some(process.process_id);
-                }
-                
+                ui.label(pid_text);
                 ui.label(" • ");
+                let name_label = ui.label(name_text);
+                if process.name.chars().count() > self.name_max_chars {
+                    name_label.on_hover_text(&process.name);
+                }
                 
-                let name_click = ui.selectable_label(
-                    self.selected_pid == Some(process.process_id),
-                    name_text
-                );
-                if name_click.clicked() {
-                    self.selected_pid = Some(process.process_id);
+                if self.tree_show_cmdline && !process.cmdline.is_empty() {
+                    let cmdline = process.cmdline_string();
+                    let label = ui.label(
+                        RichText::new(truncate_with_ellipsis(&cmdline, self.tree_cmdline_width))
+                            .color(Color32::from_rgb(180, 180, 180))
+                            .monospace()
+                    );
+                    if cmdline.chars().count() > self.tree_cmdline_width {
+                        label.on_hover_text(cmdline);
+                    }
                 }
                 
                 ui.label(" • ");
@@ -413,15 +977,42 @@ impl ProcessManagerApp {
                 ui.label(mem_text);
                 
                 if has_children {
+                    let hidden = if is_collapsed { ", collapsed" } else { "" };
                     ui.label(
-                        RichText::new(format!(" ({} children)", node.children.len()))
+                        RichText::new(format!(" ({} children{})", node.children.len(), hidden))
                             .color(Color32::from_rgb(150, 150, 150))
                             .small()
                     );
                 }
             });
+
+            // The whole row selects the node. Sensed behind the widgets, so the toggle and the
+            // checkbox keep their own clicks and only the rest of the row falls through to it.
+            let row = ui.interact_bg(egui::Sense::click());
+            let fill = if is_current {
+                Some(ui.visuals().selection.bg_fill)
+            } else if row.hovered() {
+                Some(ui.visuals().widgets.hovered.weak_bg_fill)
+            } else {
+                None
+            };
+            if let Some(fill) = fill {
+                ui.painter().set(highlight, egui::epaint::RectShape::filled(row.rect, 2.0, fill));
+            }
+            if row.clicked() {
+                self.selected_pid = Some(process.process_id);
+            }
+            row.context_menu(|ui| tree_context_menu(ui, process.process_id, has_children, self.colors.danger, &mut tree_action));
         });
 
+        if let Some(action) = tree_action {
+            self.run_tree_action(process.process_id, action);
+        }
+
+        if is_collapsed {
+            return;
+        }
+
         // Render children with proper tree structure
         let child_count = node.children.len();
         for (idx, child) in node.children.iter().enumerate() {
@@ -430,6 +1021,29 @@ impl ProcessManagerApp {
         }
     }
 
+    /// Run an action picked from a tree node's right-click menu and report the outcome in the banners
+    fn run_tree_action(&mut self, pid: u32, action: TreeAction) {
+        let result = match action {
+            TreeAction::Kill => self.kill_process(pid).map(|_| format!("Killed process {}", pid)),
+            TreeAction::Terminate => self.terminate_process(pid).map(|_| format!("Terminated process {}", pid)),
+            TreeAction::Pause => self.pause_process(pid).map(|_| format!("Paused process {}", pid)),
+            TreeAction::Resume => self.resume_process(pid).map(|_| format!("Resumed process {}", pid)),
+            TreeAction::KillSubtree => batch::kill_subtree(&self.manager, pid)
+                .map(|pids| format!("Killed {} processes in the subtree of {}", pids.len(), pid)),
+            TreeAction::PauseSubtree => batch::pause_subtree(&self.manager, pid)
+                .map(|pids| format!("Paused {} processes in the subtree of {}", pids.len(), pid)),
+        };
+        // Refresh either way: failed subtree actions may still have changed some processes
+        self.refresh_processes();
+        match result {
+            Ok(message) => {
+                self.success_message = Some(message);
+                self.success_message_time = Some(Instant::now());
+            }
+            Err(e) => self.set_error(e),
+        }
+    }
+
     // Real backend function calls using Ismail's implementation
     fn kill_process(&mut self, pid: u32) -> Result<(), String> {
         operations::kill_process(&self.manager, pid)
@@ -439,6 +1053,10 @@ impl ProcessManagerApp {
         operations::terminate_process(&self.manager, pid)
     }
 
+    fn force_terminate(&mut self, pid: u32) -> Result<operations::KillOutcome, String> {
+        operations::force_terminate(&self.manager, pid, operations::DEFAULT_FORCE_TERMINATE_GRACE)
+    }
+
     fn pause_process(&mut self, pid: u32) -> Result<(), String> {
         operations::pause_process(&self.manager, pid)
     }
@@ -447,15 +1065,46 @@ impl ProcessManagerApp {
         operations::resume_process(&self.manager, pid)
     }
 
-    fn set_priority(&mut self, pid: u32, nice: i32) -> Result<(), String> {
-        operations::set_priority(&self.manager, pid, nice)
+    fn set_priority(&mut self, pid: u32, nice: i32) -> Result<Vec<u32>, String> {
+        operations::set_priority_tree(&self.manager, pid, nice, self.priority_recursive)
+    }
+
+    /// Ask each zombie's parent to reap it and report the ones that remain
+    fn clean_zombies(&mut self) {
+        match batch::clean_zombies(&mut self.manager, Duration::from_millis(500)) {
+            Ok(reports) if reports.is_empty() => {
+                self.success_message = Some("No zombie processes found".to_string());
+                self.success_message_time = Some(Instant::now());
+            }
+            Ok(reports) => {
+                let total = reports.len();
+                self.zombie_reports = reports
+                    .into_iter()
+                    .filter(|r| r.outcome != ZombieOutcome::Reaped)
+                    .collect();
+                self.success_message = Some(format!(
+                    "Reaped {} of {} zombie process(es)",
+                    total - self.zombie_reports.len(),
+                    total
+                ));
+                self.success_message_time = Some(Instant::now());
+                self.show_zombie_cleanup = !self.zombie_reports.is_empty();
+            }
+            Err(e) => self.set_error(e),
+        }
+        self.refresh_processes();
     }
 
     fn batch_kill(&mut self, pids: Vec<u32>, force: bool) {
         let mut successful = 0;
         let mut failed = 0;
+        let mut killed_commands = Vec::new();
         
         for pid in &pids {
+            // Capture the command line first so "Undo Last Kill" can relaunch it
+            let command = self.manager.processes.get(pid)
+                .and_then(|p| p.cmdline.split_first())
+                .map(|(command, args)| (command.clone(), args.to_vec()));
             let result = if force {
                 operations::kill_process(&self.manager, *pid)
             } else {
@@ -463,10 +1112,13 @@ impl ProcessManagerApp {
             };
             
             match result {
-                Ok(_) => successful += 1,
+                Ok(_) => {
+                    successful += 1;
+                    killed_commands.extend(command);
+                }
                 Err(e) => {
                     failed += 1;
-                    eprintln!("Failed to {} process {}: {}", if force { "kill" } else { "terminate" }, pid, e);
+                    log::warn!("Failed to {} process {}: {}", if force { "kill" } else { "terminate" }, pid, e);
                 }
             }
         }
@@ -479,16 +1131,80 @@ impl ProcessManagerApp {
             ));
             self.success_message_time = Some(Instant::now());
         } else {
-            self.error_message = Some(format!(
+            self.set_error(format!(
                 "{} {} process(es), {} failed",
                 if force { "Killed" } else { "Terminated" },
                 successful,
                 failed
             ));
         }
+        // In dry-run mode nothing was killed, so there is nothing to undo
+        if !killed_commands.is_empty() && !self.manager.dry_run {
+            self.last_killed_batch = killed_commands;
+        }
         self.clear_selections();
     }
 
+    /// Nice value, CPUs and cgroup typed into the create-process dialog (empty fields are left unset)
+    fn spawn_options(&self) -> Result<SpawnOptions, String> {
+        let field = |text: &str| Some(text.trim()).filter(|t| !t.is_empty()).map(str::to_string);
+        let nice = match field(&self.create_process_nice) {
+            Some(nice) => Some(nice.parse::<i32>().map_err(|_| format!("Invalid nice value: {}", nice))?),
+            None => None,
+        };
+        let affinity = field(&self.create_process_affinity)
+            .map(|cpus| creation::parse_cpu_list(&cpus))
+            .transpose()?;
+        Ok(SpawnOptions { nice, affinity, cgroup: field(&self.create_process_cgroup) })
+    }
+
+    /// Relaunch the commands of the last batch kill in the background (a fresh start, not a restore)
+    fn undo_last_kill(&mut self) {
+        let batch = std::mem::take(&mut self.last_killed_batch);
+        let mut relaunched = 0;
+        let mut failures = Vec::new();
+
+        for (command, args) in &batch {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            match creation::create_process_background(&self.manager, command, &args, &SpawnOptions::default()) {
+                Ok(_) => relaunched += 1,
+                Err(e) => failures.push(format!("{}: {}", command, e)),
+            }
+        }
+
+        self.refresh_processes();
+        if failures.is_empty() {
+            self.success_message = Some(format!("Relaunched {} process(es)", relaunched));
+            self.success_message_time = Some(Instant::now());
+        } else {
+            self.set_error(format!(
+                "Relaunched {} of {} process(es); failed: {}",
+                relaunched,
+                batch.len(),
+                failures.join("; ")
+            ));
+        }
+    }
+
+    /// Replace the selection with the running processes named in select_pids_input
+    fn select_pid_list(&mut self) {
+        match batch::parse_pid_list(&self.select_pids_input) {
+            Ok(pids) => {
+                let (running, missing): (Vec<u32>, Vec<u32>) = pids
+                    .into_iter()
+                    .partition(|pid| self.manager.processes.contains_key(pid));
+                self.selected_pids = running.iter().copied().collect();
+                self.success_message = Some(if missing.is_empty() {
+                    format!("Selected {} process(es)", running.len())
+                } else {
+                    format!("Selected {} process(es); {} PID(s) not running", running.len(), missing.len())
+                });
+                self.success_message_time = Some(Instant::now());
+            }
+            Err(e) => self.set_error(e),
+        }
+    }
+
     fn batch_pause(&mut self, pids: Vec<u32>) {
         let mut successful = 0;
         let mut failed = 0;
@@ -498,7 +1214,7 @@ impl ProcessManagerApp {
                 Ok(_) => successful += 1,
                 Err(e) => {
                     failed += 1;
-                    eprintln!("Failed to pause process {}: {}", pid, e);
+                    log::warn!("Failed to pause process {}: {}", pid, e);
                 }
             }
         }
@@ -507,7 +1223,7 @@ impl ProcessManagerApp {
             self.success_message = Some(format!("Successfully paused {} process(es)", successful));
             self.success_message_time = Some(Instant::now());
         } else {
-            self.error_message = Some(format!("Paused {} process(es), {} failed", successful, failed));
+            self.set_error(format!("Paused {} process(es), {} failed", successful, failed));
         }
         self.clear_selections();
     }
@@ -521,7 +1237,7 @@ impl ProcessManagerApp {
                 Ok(_) => successful += 1,
                 Err(e) => {
                     failed += 1;
-                    eprintln!("Failed to resume process {}: {}", pid, e);
+                    log::warn!("Failed to resume process {}: {}", pid, e);
                 }
             }
         }
@@ -530,31 +1246,68 @@ impl ProcessManagerApp {
             self.success_message = Some(format!("Successfully resumed {} process(es)", successful));
             self.success_message_time = Some(Instant::now());
         } else {
-            self.error_message = Some(format!("Resumed {} process(es), {} failed", successful, failed));
+            self.set_error(format!("Resumed {} process(es), {} failed", successful, failed));
         }
         self.clear_selections();
     }
 }
 
 impl eframe::App for ProcessManagerApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, CONFIG_KEY, &self.config());
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Auto-refresh logic
-        if self.auto_refresh && self.last_refresh.elapsed() >= self.refresh_interval {
+        // Scanning /proc while nobody is looking wastes CPU, so pause when the window is
+        // unfocused or minimized (unless the user opted into background refreshes).
+        // Focus/restore events wake the app up again, so refreshing resumes on its own.
+        let is_visible = ctx.input(|i| i.focused && !i.viewport().minimized.unwrap_or(false));
+        let should_auto_refresh = self.auto_refresh && (is_visible || self.refresh_in_background);
+        if should_auto_refresh && self.last_refresh.elapsed() >= self.refresh_interval {
             self.refresh_processes();
         }
         
-        // Clear success message after 3 seconds
-        if let Some(msg_time) = self.success_message_time {
-            if msg_time.elapsed().as_secs() >= 3 {
-                self.success_message = None;
-                self.success_message_time = None;
-            }
+        // Surface audit-log write failures (reported once, operations still go through)
+        if let Some(e) = self.manager.take_audit_error() {
+            self.set_error(e);
+        }
+
+        if let Some(note) = self.manager.take_escalation_note() {
+            self.escalation_message = Some(note);
+        }
+
+        // Ctrl+P (Cmd+P on macOS) toggles the command palette
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P)) {
+            self.command_palette = match self.command_palette {
+                Some(_) => None,
+                None => Some(String::new()),
+            };
+        }
+
+        // Clear success and error messages once they have been shown for their TTL
+        if let Some(msg_time) = self.success_message_time
+            && msg_time.elapsed() >= self.message_ttl
+        {
+            self.success_message = None;
+            self.success_message_time = None;
+        }
+        if let (Some(msg_time), Some(ttl)) = (self.error_message_time, self.error_message_ttl)
+            && msg_time.elapsed() >= ttl
+        {
+            self.error_message = None;
+            self.error_message_time = None;
         }
 
         // Request repaint for auto-refresh
-        if self.auto_refresh {
+        if should_auto_refresh {
             ctx.request_repaint_after(self.refresh_interval);
         }
+        // Keep repainting while CPU spikes fade out
+        self.cpu_spikes.retain(|_, (_, at)| at.elapsed() < CPU_SPIKE_FLASH);
+        if !self.cpu_spikes.is_empty() {
+            ctx.request_repaint();
+        }
 
         // Top menu bar
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
@@ -567,6 +1320,12 @@ impl eframe::App for ProcessManagerApp {
                     if ui.button("Create Process...").clicked() {
                         self.show_create_process = true;
                     }
+                    if ui.button("Export Processes...").clicked() {
+                        self.export_processes_path = Some(format!("processes.{}", self.export_format.extension()));
+                    }
+                    if ui.button("Export Tree (DOT)...").clicked() {
+                        self.export_dot_path = Some("process_tree.dot".to_string());
+                    }
                     if ui.button("Exit").clicked() {
                         std::process::exit(0);
                     }
@@ -574,8 +1333,105 @@ impl eframe::App for ProcessManagerApp {
 
                 ui.menu_button("View", |ui| {
                     ui.checkbox(&mut self.auto_refresh, "Auto Refresh");
+                    ui.checkbox(&mut self.refresh_in_background, "Refresh While in Background");
                     ui.checkbox(&mut self.show_tree_view, "Process Tree View");
+                    ui.checkbox(&mut self.show_system_graphs, "System Graphs");
+                    ui.checkbox(&mut self.show_favorites, "Favorites Panel");
+                    ui.horizontal(|ui| {
+                        ui.label("Graph history:");
+                        let mut history_len = self.manager.system_history.capacity();
+                        if ui
+                            .add(egui::Slider::new(&mut history_len, 10..=1000).suffix(" samples"))
+                            .changed()
+                        {
+                            self.manager.system_history.set_capacity(history_len);
+                        }
+                    });
+                    if ui.checkbox(&mut self.smooth_cpu, "Smooth CPU %")
+                        .on_hover_text("Average CPU usage over recent refreshes to stop the list jumping around")
+                        .changed()
+                    {
+                        self.apply_filters_and_sort();
+                    }
+                    if self.smooth_cpu {
+                        ui.horizontal(|ui| {
+                            ui.label("Smoothing weight:");
+                            ui.add(egui::Slider::new(&mut self.manager.cpu_smoothing_alpha, 0.05..=1.0))
+                                .on_hover_text("Weight of the newest sample; lower is smoother, 1.0 disables smoothing");
+                        });
+                    }
+                    if ui.checkbox(&mut self.normalize_cpu, "CPU % of Whole Machine (0-100)")
+                        .on_hover_text(format!(
+                            "Off: 100% is one busy core, so a multithreaded process can reach {:.0}%. \
+                             On: divided by the {} cores. The CPU threshold compares against the value shown.",
+                            self.cpu_cores * 100.0, self.cpu_cores
+                        ))
+                        .changed()
+                    {
+                        self.apply_filters_and_sort();
+                    }
+                    let mut group_threads = self.manager.group_threads;
+                    if ui.checkbox(&mut group_threads, "Group Threads Into Processes")
+                        .on_hover_text("On: one row per process, its CPU % covering all of its threads. Off: every thread gets its own row, nested under its process in the tree")
+                        .changed()
+                    {
+                        self.manager.set_group_threads(group_threads);
+                        self.refresh_processes();
+                    }
+                    ui.checkbox(&mut self.state_glyphs, "State Shapes")
+                        .on_hover_text("Show a shape next to each state letter so states can be told apart without relying on color");
+                    ui.horizontal(|ui| {
+                        ui.label("Name width:");
+                        ui.add(egui::DragValue::new(&mut self.name_max_chars).clamp_range(4..=200).suffix(" chars"))
+                            .on_hover_text("Longer process names are cut short with \"…\"; hover one to see it in full");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Memory unit:");
+                        for unit in MemoryUnit::ALL {
+                            ui.radio_value(&mut self.memory_unit, unit, unit.label());
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Success messages:");
+                        let mut secs = self.message_ttl.as_secs();
+                        if ui.add(egui::DragValue::new(&mut secs).clamp_range(1..=600).suffix(" s")).changed() {
+                            self.message_ttl = Duration::from_secs(secs);
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        let mut auto_clear = self.error_message_ttl.is_some();
+                        if ui.checkbox(&mut auto_clear, "Clear errors after:")
+                            .on_hover_text("Otherwise errors stay until dismissed")
+                            .changed()
+                        {
+                            self.error_message_ttl = auto_clear.then_some(DEFAULT_ERROR_MESSAGE_TTL);
+                        }
+                        if let Some(ttl) = &mut self.error_message_ttl {
+                            let mut secs = ttl.as_secs();
+                            if ui.add(egui::DragValue::new(&mut secs).clamp_range(1..=3600).suffix(" s")).changed() {
+                                *ttl = Duration::from_secs(secs);
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Highlight new processes:");
+                        ui.add(egui::DragValue::new(&mut self.new_process_highlight_secs).clamp_range(0..=3600).suffix(" s"))
+                            .on_hover_text("Tint processes started less than this long ago (0 = off)");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Max table rows:");
+                        ui.add(egui::DragValue::new(&mut self.max_rows).speed(10.0).clamp_range(0..=100_000))
+                            .on_hover_text("Only render the top rows by the current sort (0 = unlimited)");
+                    });
                     ui.checkbox(&mut self.show_threshold_config, "Configure Thresholds");
+                    ui.menu_button("Columns", |ui| {
+                        for column in Column::ALL {
+                            let mut visible = self.visible_columns.contains(&column);
+                            if ui.checkbox(&mut visible, column.label()).changed() {
+                                self.set_column_visible(column, visible);
+                            }
+                        }
+                    });
                     ui.separator();
                     if ui.checkbox(&mut self.show_only_zombies, "Show Only Zombie Processes").changed() {
                         self.apply_filters_and_sort();
@@ -596,6 +1452,10 @@ impl eframe::App for ProcessManagerApp {
                         self.sort_column = SortColumn::Cpu;
                         self.apply_filters_and_sort();
                     }
+                    if ui.button("Sort by CPU Time").clicked() {
+                        self.sort_column = SortColumn::CpuTime;
+                        self.apply_filters_and_sort();
+                    }
                     if ui.button("Sort by Name").clicked() {
                         self.sort_column = SortColumn::Name;
                         self.apply_filters_and_sort();
@@ -604,47 +1464,170 @@ impl eframe::App for ProcessManagerApp {
                         self.sort_column = SortColumn::Memory;
                         self.apply_filters_and_sort();
                     }
+                    if ui.checkbox(&mut self.abnormal_first, "Abnormal First")
+                        .on_hover_text("Keep zombies, hung and leaking processes and threshold-breakers at the top, sorted among themselves by the column")
+                        .changed()
+                    {
+                        self.apply_filters_and_sort();
+                    }
                 });
 
                 ui.menu_button("Operations", |ui| {
-                    if ui.button("Kill Selected").clicked() {
-                        if !self.selected_pids.is_empty() {
-                            let pids: Vec<u32> = self.selected_pids.iter().copied().collect();
-                            self.batch_kill(pids, false);
-                            self.refresh_processes();
-                        }
+                    if ui.add(egui::Button::new("Command Palette...").shortcut_text("Ctrl+P")).clicked() {
+                        self.command_palette = Some(String::new());
+                        ui.close_menu();
                     }
-                    if ui.button("Force Kill Selected").clicked() {
-                        if !self.selected_pids.is_empty() {
-                            let pids: Vec<u32> = self.selected_pids.iter().copied().collect();
-                            self.batch_kill(pids, true);
-                            self.refresh_processes();
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.add(TextEdit::singleline(&mut self.select_pids_input)
+                            .hint_text("1001,1002,2000-2010")
+                            .desired_width(140.0));
+                        if ui.button("Select PIDs").on_hover_text("Select these PIDs for the batch operations below").clicked() {
+                            self.select_pid_list();
                         }
+                    });
+                    let selected = format!("the {} selected processes", self.selected_pids.len());
+                    let has_selection = !self.selected_pids.is_empty();
+                    if danger_button(ui, has_selection, "Kill Selected", self.colors.danger, &signal_hint(false, &selected)).clicked() {
+                        let pids: Vec<u32> = self.selected_pids.iter().copied().collect();
+                        self.batch_kill(pids, false);
+                        self.refresh_processes();
                     }
-                    if ui.button("Pause Selected").clicked() {
-                        if !self.selected_pids.is_empty() {
-                            let pids: Vec<u32> = self.selected_pids.iter().copied().collect();
-                            self.batch_pause(pids);
-                            self.refresh_processes();
-                        }
+                    if danger_button(ui, has_selection, "Force Kill Selected", self.colors.danger, &signal_hint(true, &selected)).clicked() {
+                        let pids: Vec<u32> = self.selected_pids.iter().copied().collect();
+                        self.batch_kill(pids, true);
+                        self.refresh_processes();
                     }
-                    if ui.button("Resume Selected").clicked() {
-                        if !self.selected_pids.is_empty() {
-                            let pids: Vec<u32> = self.selected_pids.iter().copied().collect();
-                            self.batch_resume(pids);
-                            self.refresh_processes();
-                        }
+                    if ui
+                        .add_enabled(
+                            !self.last_killed_batch.is_empty(),
+                            egui::Button::new(format!("Undo Last Kill ({})...", self.last_killed_batch.len())),
+                        )
+                        .on_hover_text("Relaunch the commands of the last batch kill")
+                        .clicked()
+                    {
+                        self.show_undo_kill = true;
+                    }
+                    if ui.button("Pause Selected").clicked() && !self.selected_pids.is_empty() {
+                        let pids: Vec<u32> = self.selected_pids.iter().copied().collect();
+                        self.batch_pause(pids);
+                        self.refresh_processes();
+                    }
+                    if ui.button("Resume Selected").clicked() && !self.selected_pids.is_empty() {
+                        let pids: Vec<u32> = self.selected_pids.iter().copied().collect();
+                        self.batch_resume(pids);
+                        self.refresh_processes();
+                    }
+                    ui.separator();
+                    if ui.button("Clean Zombies").clicked() {
+                        self.clean_zombies();
                     }
+                    ui.separator();
+                    ui.menu_button("On Permission Denied", |ui| {
+                        ui.label(
+                            RichText::new("Retry failed operations with elevated privileges")
+                                .small()
+                        );
+                        ui.radio_value(&mut self.manager.privilege_escalation, None, "Don't escalate");
+                        ui.radio_value(&mut self.manager.privilege_escalation, Some(EscalationMethod::Sudo), "Retry with sudo -n");
+                        ui.radio_value(&mut self.manager.privilege_escalation, Some(EscalationMethod::Pkexec), "Retry with pkexec");
+                    });
+                    ui.separator();
+                    if ui.button("Protected Processes...").clicked() {
+                        self.show_protection_config = true;
+                    }
+                    ui.checkbox(&mut self.manager.force_protected, "Allow Signaling Protected Processes")
+                        .on_hover_text("Let kill/terminate/pause act on protected PIDs and names");
+                    let limiter = self.manager.rate_limiter.get_mut();
+                    let limit_hint = format!(
+                        "Refuse kill/terminate/pause beyond a burst of {:.0}, then {:.0} per second",
+                        limiter.burst(),
+                        limiter.per_second()
+                    );
+                    ui.checkbox(&mut limiter.enabled, "Rate-Limit Signals").on_hover_text(limit_hint);
+                    ui.checkbox(&mut self.manager.dry_run, "Dry Run")
+                        .on_hover_text("Log what each operation would do without sending any signal");
                 });
 
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label(format!(
-                        "Processes: {} | Selected: {} | Last refresh: {:.1}s ago",
-                        self.filtered_processes.len(),
-                        self.selected_pids.len(),
-                        self.last_refresh.elapsed().as_secs_f32()
-                    ));
-                });
+                // Lock toggle: read-only mode refuses every operation, even as root
+                let (lock_text, lock_hint) = if self.manager.read_only {
+                    (RichText::new("🔒 Read-only").strong().color(Color32::BLACK).background_color(Color32::from_rgb(100, 180, 255)),
+                     "Every operation is disabled (monitor only). Click to unlock.")
+                } else {
+                    (RichText::new("🔓"), "Click to lock: disable every operation, for pure observation")
+                };
+                if ui.selectable_label(self.manager.read_only, lock_text).on_hover_text(lock_hint).clicked() {
+                    self.manager.read_only = !self.manager.read_only;
+                }
+
+                if self.manager.dry_run {
+                    ui.label(
+                        RichText::new(" DRY RUN ")
+                            .strong()
+                            .color(Color32::BLACK)
+                            .background_color(Color32::from_rgb(255, 165, 0))
+                    )
+                    .on_hover_text("Operations are logged but not performed (Operations → Dry Run)");
+                }
+
+                if let Some(restriction) = &self.manager.proc_restriction {
+                    ui.label(
+                        RichText::new(" PARTIAL /proc ")
+                            .strong()
+                            .color(Color32::BLACK)
+                            .background_color(Color32::YELLOW)
+                    )
+                    .on_hover_text(format!("{}. Only the processes that could be read are listed.", restriction));
+                }
+
+                // How much of the time tasks are stalled waiting for each resource (hidden without PSI)
+                if let Some(pressure) = self.manager.system_history.pressure {
+                    ui.separator();
+                    pressure_gauge(ui, "CPU", pressure.cpu, None);
+                    pressure_gauge(ui, "MEM", pressure.memory, Some(self.thresholds.pressure));
+                    pressure_gauge(ui, "IO", pressure.io, Some(self.thresholds.pressure));
+                }
+
+                // Live stats of the followed process, shown even when the table filters it out
+                if let Some(process) = self.pinned_pid.and_then(|pid| self.manager.processes.get(&pid)) {
+                    ui.separator();
+                    ui.label(
+                        RichText::new(format!(
+                            "📌 {} ({}): {} | CPU {:.1}% | MEM {}",
+                            process.name,
+                            process.process_id,
+                            process.pcb_data.state,
+                            self.cpu_percent(process),
+                            format_memory(process.pcb_data.memory_rss_kb, self.memory_unit)
+                        ))
+                        .color(Color32::LIGHT_GREEN)
+                    );
+                    if ui.small_button("✖").on_hover_text("Stop following").clicked() {
+                        self.pinned_pid = None;
+                    }
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(format!(
+                        "Processes: {} | Selected: {} | Last refresh: {:.1}s ago",
+                        self.filtered_processes.len(),
+                        self.selected_pids.len(),
+                        self.last_refresh.elapsed().as_secs_f32()
+                    ));
+                    ui.separator();
+
+                    // Process count by state (laid out right-to-left, so iterate in reverse)
+                    // Zombies and uninterruptible sleepers are red when present since they usually signal a problem
+                    let state_counts = self.manager.state_counts();
+                    for state in ['R', 'S', 'D', 'Z', 'T'].into_iter().rev() {
+                        let count = state_counts.get(&state).copied().unwrap_or(0);
+                        let mut text = RichText::new(format!("{}:{}", state, count)).monospace();
+                        if matches!(state, 'D' | 'Z') && count > 0 {
+                            text = text.color(Color32::RED).strong();
+                        }
+                        ui.label(text);
+                    }
+                });
             });
         });
 
@@ -674,12 +1657,390 @@ impl eframe::App for ProcessManagerApp {
                         ui.label(format!("{} MB", self.thresholds.memory_mb));
                     });
 
+                    ui.horizontal(|ui| {
+                        ui.label("Spawn storm (new processes per refresh):");
+                        ui.add(egui::Slider::new(&mut self.thresholds.spawn_storm, 2..=1000).logarithmic(true));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("CPU spike (rise in CPU % per refresh):");
+                        ui.add(egui::Slider::new(&mut self.thresholds.cpu_spike, 5.0..=400.0).suffix("%"))
+                            .on_hover_text("Flash the CPU % of processes whose usage jumps by more than this between two refreshes");
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Memory/I/O pressure (% of time stalled):");
+                        ui.add(egui::Slider::new(&mut self.thresholds.pressure, 1.0..=100.0).suffix("%"))
+                            .on_hover_text("Warn when tasks spent more than this share of the last 10 seconds stalled on memory or I/O (PSI)");
+                    });
+
+                    ui.separator();
+                    ui.label("Growth alerts (per minute, measured over the last minute):");
+                    let limits = &mut self.manager.growth.limits;
+                    ui.horizontal(|ui| {
+                        ui.label("Memory growth (MB/min):");
+                        ui.add(egui::Slider::new(&mut limits.rss_mb_per_min, 10.0..=5000.0).logarithmic(true));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("FD growth (per min):");
+                        ui.add(egui::Slider::new(&mut limits.fds_per_min, 1.0..=10000.0).logarithmic(true));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Thread growth (per min):");
+                        ui.add(egui::Slider::new(&mut limits.threads_per_min, 1.0..=5000.0).logarithmic(true));
+                    });
+
+                    egui::CollapsingHeader::new("Highlight Colors")
+                        .id_source("highlight_colors")
+                        .show(ui, |ui| {
+                            let colors = &mut self.colors;
+                            egui::Grid::new("highlight_color_grid")
+                                .num_columns(2)
+                                .spacing([10.0, 4.0])
+                                .show(ui, |ui| {
+                                    for (label, color) in [
+                                        ("Abnormal process", &mut colors.abnormal),
+                                        ("Growing RSS/FDs/threads", &mut colors.growing),
+                                        ("Needs restart (deleted binary)", &mut colors.stale_binary),
+                                        ("Newly started (background)", &mut colors.new_process),
+                                        ("CPU over threshold", &mut colors.cpu_over_threshold),
+                                        ("Memory over threshold", &mut colors.memory_over_threshold),
+                                        ("Running (R)", &mut colors.running),
+                                        ("Sleeping (S)", &mut colors.sleeping),
+                                        ("Disk sleep (D)", &mut colors.disk_sleep),
+                                        ("Zombie (Z)", &mut colors.zombie),
+                                        ("Stopped (T)", &mut colors.stopped),
+                                        ("Other states", &mut colors.other_state),
+                                        ("CPU spike (flash)", &mut colors.cpu_spike),
+                                        ("Destructive buttons", &mut colors.danger),
+                                    ] {
+                                        ui.label(label);
+                                        ui.color_edit_button_srgba(color);
+                                        ui.end_row();
+                                    }
+                                });
+                            if ui.button("Reset to Defaults").clicked() {
+                                *colors = HighlightColors::default();
+                            }
+                        });
+
                     if ui.button("Close").clicked() {
                         self.show_threshold_config = false;
                     }
                 });
         }
 
+        // Protected processes window
+        if self.show_protection_config {
+            let mut open = true;
+            egui::Window::new("Protected Processes")
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label("Kill, terminate and pause refuse to act on these unless\nOperations → Allow Signaling Protected Processes is checked.");
+                    ui.separator();
+
+                    let mut pids: Vec<u32> = self.manager.protected_pids.iter().copied().collect();
+                    pids.sort();
+                    let mut names: Vec<String> = self.manager.protected_names.iter().cloned().collect();
+                    names.sort();
+
+                    ui.label(RichText::new("PIDs (this session only)").strong());
+                    for pid in pids {
+                        ui.horizontal(|ui| {
+                            let name = self.manager.processes.get(&pid).map(|p| p.name.as_str()).unwrap_or("not running");
+                            ui.label(format!("{} ({})", pid, name));
+                            if ui.small_button("✖").clicked() {
+                                self.manager.protected_pids.remove(&pid);
+                            }
+                        });
+                    }
+
+                    ui.label(RichText::new("Names (remembered)").strong());
+                    for name in names {
+                        ui.horizontal(|ui| {
+                            if self.launch_protected_names.contains(&name) {
+                                ui.label(name.as_str()).on_hover_text("From --protect: this session only");
+                                ui.label(RichText::new("(--protect)").small().color(Color32::GRAY));
+                            } else {
+                                ui.label(name.as_str());
+                            }
+                            if ui.small_button("✖").clicked() {
+                                self.manager.protected_names.remove(&name);
+                                self.launch_protected_names.remove(&name);
+                            }
+                        });
+                    }
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.add(TextEdit::singleline(&mut self.protect_input).hint_text("PID or process name").desired_width(150.0));
+                        if ui.button("Add").clicked() {
+                            let input = self.protect_input.trim();
+                            if let Ok(pid) = input.parse::<u32>() {
+                                self.manager.protected_pids.insert(pid);
+                            } else if !input.is_empty() {
+                                self.manager.protected_names.insert(input.to_string());
+                                // Added here, so remember it even if --protect named it too
+                                self.launch_protected_names.remove(input);
+                            }
+                            self.protect_input.clear();
+                        }
+                    });
+                });
+            if !open {
+                self.show_protection_config = false;
+            }
+        }
+
+        // Undo last batch kill window
+        if self.show_undo_kill {
+            let mut open = true;
+            let mut relaunch = false;
+            egui::Window::new("Undo Last Kill")
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.colored_label(
+                        Color32::from_rgb(255, 165, 0),
+                        "⚠ This only starts the same commands again in the background.
+The killed processes' state (memory, open files, PIDs, terminal) is gone for good.",
+                    );
+                    ui.separator();
+                    ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        for (command, args) in &self.last_killed_batch {
+                            ui.monospace(format!("{} {}", command, args.join(" ")));
+                        }
+                    });
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Relaunch").clicked() {
+                            relaunch = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.show_undo_kill = false;
+                        }
+                    });
+                });
+            if relaunch {
+                self.undo_last_kill();
+                self.show_undo_kill = false;
+            }
+            if !open {
+                self.show_undo_kill = false;
+            }
+        }
+
+        // Zombie cleanup results window
+        if self.show_zombie_cleanup {
+            let mut parent_to_kill = None;
+            egui::Window::new("Zombie Cleanup")
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label("These zombies could not be cleared:");
+                    ui.separator();
+
+                    for report in &self.zombie_reports {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(Color32::YELLOW, report.describe());
+                            if let ZombieOutcome::ParentIgnored(ppid) = report.outcome
+                                && danger_button(ui, true, &format!("Kill Parent {}", ppid), self.colors.danger, &signal_hint(true, &format!("PID {}", ppid))).clicked()
+                            {
+                                parent_to_kill = Some(ppid);
+                            }
+                        });
+                    }
+
+                    ui.separator();
+                    if ui.button("Close").clicked() {
+                        self.show_zombie_cleanup = false;
+                    }
+                });
+
+            if let Some(ppid) = parent_to_kill {
+                match self.kill_process(ppid) {
+                    Ok(_) => {
+                        self.success_message = Some(format!(
+                            "Killed parent {}; init will reap its zombie children",
+                            ppid
+                        ));
+                        self.success_message_time = Some(Instant::now());
+                        self.zombie_reports
+                            .retain(|r| r.outcome != ZombieOutcome::ParentIgnored(ppid));
+                        self.show_zombie_cleanup = !self.zombie_reports.is_empty();
+                        self.refresh_processes();
+                    }
+                    Err(e) => self.set_error(e),
+                }
+            }
+        }
+
+        // Full process report window
+        if let Some((pid, report)) = &self.full_report {
+            let mut open = true;
+            egui::Window::new(format!("Full Report: PID {}", pid))
+                .open(&mut open)
+                .default_width(520.0)
+                .show(ctx, |ui| {
+                    if ui.button("📋 Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = report.clone());
+                    }
+                    ui.separator();
+                    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                        ui.add(egui::Label::new(RichText::new(report).monospace()).wrap(false));
+                    });
+                });
+            if !open {
+                self.full_report = None;
+            }
+        }
+
+        // Export process tree window
+        if let Some(path) = &mut self.export_dot_path {
+            let mut close = false;
+            let mut result = None;
+            egui::Window::new("Export Tree (DOT)")
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label("Write the process hierarchy as a Graphviz graph (render with `dot -Tsvg`).");
+                    ui.horizontal(|ui| {
+                        ui.label("File:");
+                        ui.text_edit_singleline(path);
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button("Export").clicked() {
+                            let dot = self.manager.tree_to_dot();
+                            result = Some(
+                                std::fs::write(path.as_str(), dot)
+                                    .map(|_| format!("Exported process tree to {}", path))
+                                    .map_err(|e| format!("Failed to write {}: {}", path, e)),
+                            );
+                        }
+                        close = ui.button("Cancel").clicked();
+                    });
+                });
+            match result {
+                Some(Ok(message)) => {
+                    self.success_message = Some(message);
+                    self.success_message_time = Some(Instant::now());
+                    close = true;
+                }
+                Some(Err(e)) => self.set_error(e),
+                None => {}
+            }
+            if close {
+                self.export_dot_path = None;
+            }
+        }
+
+        // Command palette: type "kill 1234", "nice 1234 5", ... and press Enter
+        if let Some(input) = &mut self.command_palette {
+            let mut close = false;
+            let mut result = None;
+            egui::Window::new("Command Palette")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+                .show(ctx, |ui| {
+                    let response = ui.add(
+                        TextEdit::singleline(input)
+                            .hint_text("kill 1234")
+                            .desired_width(400.0),
+                    );
+                    response.request_focus();
+                    ui.label(RichText::new(COMMAND_HELP).small().color(Color32::GRAY));
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        result = Some(command::parse_command(input)
+                            .and_then(|cmd| command::execute(&self.manager, &cmd)));
+                    }
+                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        close = true;
+                    }
+                });
+            match result {
+                Some(Ok(message)) => {
+                    self.success_message = Some(message);
+                    self.success_message_time = Some(Instant::now());
+                    self.refresh_processes();
+                    close = true;
+                }
+                // Keep the palette open with the text so a typo can be fixed
+                Some(Err(e)) => self.set_error(e),
+                None => {}
+            }
+            if close {
+                self.command_palette = None;
+            }
+        }
+
+        // Export process list window
+        if let Some(path) = &mut self.export_processes_path {
+            let mut close = false;
+            let mut result = None;
+            egui::Window::new("Export Processes")
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Format:");
+                        for format in ExportFormat::ALL {
+                            if ui.radio_value(&mut self.export_format, format, format.label()).changed() {
+                                *path = std::path::Path::new(path.as_str())
+                                    .with_extension(format.extension())
+                                    .to_string_lossy()
+                                    .into_owned();
+                            }
+                        }
+                    });
+                    ui.radio_value(
+                        &mut self.export_filtered_only,
+                        true,
+                        format!("Filtered view ({} processes, in the current sort order)", self.filtered_processes.len()),
+                    );
+                    ui.radio_value(
+                        &mut self.export_filtered_only,
+                        false,
+                        format!("All processes ({}, by PID)", self.processes_vec.len()),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.label("File:");
+                        ui.text_edit_singleline(path);
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button("Export").clicked() {
+                            let processes: Vec<&Process> = if self.export_filtered_only {
+                                self.filtered_processes.iter().map(|&idx| &self.processes_vec[idx]).collect()
+                            } else {
+                                let mut all: Vec<&Process> = self.processes_vec.iter().collect();
+                                all.sort_by_key(|p| p.process_id);
+                                all
+                            };
+                            let count = processes.len();
+                            result = Some(
+                                export::export_processes(processes, self.export_format)
+                                    .and_then(|text| {
+                                        std::fs::write(path.as_str(), text)
+                                            .map_err(|e| format!("Failed to write {}: {}", path, e))
+                                    })
+                                    .map(|_| format!("Exported {} processes to {}", count, path)),
+                            );
+                        }
+                        close = ui.button("Cancel").clicked();
+                    });
+                });
+            match result {
+                Some(Ok(message)) => {
+                    self.success_message = Some(message);
+                    self.success_message_time = Some(Instant::now());
+                    close = true;
+                }
+                Some(Err(e)) => self.set_error(e),
+                None => {}
+            }
+            if close {
+                self.export_processes_path = None;
+            }
+        }
+
         // Create Process window
         if self.show_create_process {
             egui::Window::new("Create Process")
@@ -699,6 +2060,30 @@ impl eframe::App for ProcessManagerApp {
                     });
 
                     ui.checkbox(&mut self.create_process_background, "Run in background");
+                    if self.create_process_background {
+                        ui.horizontal(|ui| {
+                            ui.label("Log to file:");
+                            ui.add(TextEdit::singleline(&mut self.create_process_log_path)
+                                .hint_text("optional, e.g. /tmp/job.log"));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Nice:");
+                            ui.add(TextEdit::singleline(&mut self.create_process_nice)
+                                .hint_text("-20..19")
+                                .desired_width(50.0));
+                            ui.label("CPUs:");
+                            ui.add(TextEdit::singleline(&mut self.create_process_affinity)
+                                .hint_text("e.g. 0-3,6")
+                                .desired_width(80.0))
+                                .on_hover_text("Cores the process may run on, applied before it starts");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Cgroup:");
+                            ui.add(TextEdit::singleline(&mut self.create_process_cgroup)
+                                .hint_text("optional, e.g. /user.slice/batch"))
+                                .on_hover_text("cgroup v2 path to move the process into right after it starts");
+                        });
+                    }
 
                     ui.separator();
 
@@ -711,13 +2096,19 @@ impl eframe::App for ProcessManagerApp {
                                     self.create_process_args.split_whitespace().collect()
                                 };
                                 
-                                let result = if self.create_process_background {
-                                    creation::create_process_background(&self.manager, &self.create_process_command, &args)
-                                } else {
-                                    match creation::create_process_foreground(&self.manager, &self.create_process_command, &args) {
+                                let log_path = self.create_process_log_path.trim();
+                                let result = match self.spawn_options() {
+                                    Err(e) => Err(e),
+                                    Ok(options) if self.create_process_background && !log_path.is_empty() => {
+                                        creation::create_process_background_logged(&self.manager, &self.create_process_command, &args, std::path::Path::new(log_path), &options)
+                                    }
+                                    Ok(options) if self.create_process_background => {
+                                        creation::create_process_background(&self.manager, &self.create_process_command, &args, &options)
+                                    }
+                                    Ok(_) => match creation::create_process_foreground(&self.manager, &self.create_process_command, &args) {
                                         Ok(_) => Ok(0),
                                         Err(e) => Err(e),
-                                    }
+                                    },
                                 };
                                 
                                 match result {
@@ -730,15 +2121,19 @@ impl eframe::App for ProcessManagerApp {
                                         self.success_message_time = Some(Instant::now());
                                         self.create_process_command.clear();
                                         self.create_process_args.clear();
+                                        self.create_process_log_path.clear();
+                                        self.create_process_nice.clear();
+                                        self.create_process_affinity.clear();
+                                        self.create_process_cgroup.clear();
                                         self.show_create_process = false;
                                         self.refresh_processes();
                                     }
                                     Err(e) => {
-                                        self.error_message = Some(format!("Failed to create process: {}", e));
+                                        self.set_error(format!("Failed to create process: {}", e));
                                     }
                                 }
                             } else {
-                                self.error_message = Some("Command cannot be empty".to_string());
+                                self.set_error("Command cannot be empty".to_string());
                             }
                         }
                         
@@ -746,29 +2141,234 @@ impl eframe::App for ProcessManagerApp {
                             self.show_create_process = false;
                             self.create_process_command.clear();
                             self.create_process_args.clear();
+                            self.create_process_log_path.clear();
+                            self.create_process_nice.clear();
+                            self.create_process_affinity.clear();
+                            self.create_process_cgroup.clear();
                         }
                     });
                 });
         }
 
+        // System CPU/memory history graphs
+        if self.show_system_graphs {
+            egui::TopBottomPanel::bottom("system_graphs")
+                .resizable(true)
+                .show(ctx, |ui| {
+                    egui::CollapsingHeader::new(RichText::new("📈 System Monitor").strong())
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            let interval = self.refresh_interval.as_secs_f64();
+                            let history = &self.manager.system_history;
+                            ui.columns(2, |columns| {
+                                history_plot(&mut columns[0], "system_cpu_plot", "CPU %", &history.cpu_percent, interval, Color32::from_rgb(100, 150, 255));
+                                history_plot(&mut columns[1], "system_memory_plot", "Memory %", &history.memory_percent, interval, Color32::from_rgb(255, 200, 100));
+                            });
+                        });
+                });
+        }
+
+        // Summary of the processes currently shown, so large lists can be read at a glance
+        egui::TopBottomPanel::bottom("process_summary").show(ctx, |ui| {
+            let summary = monitoring::summarize(
+                self.filtered_processes.iter().map(|&idx| &self.processes_vec[idx]),
+                3,
+            );
+            let mut states: Vec<_> = summary.state_counts.iter().collect();
+            states.sort();
+            let states: Vec<String> = states.iter().map(|(state, count)| format!("{}:{}", state, count)).collect();
+            let top: Vec<String> = summary
+                .top_memory
+                .iter()
+                .map(|(pid, name, rss_kb)| format!("{} ({}) {}", name, pid, format_memory(*rss_kb, self.memory_unit)))
+                .collect();
+            ui.horizontal_wrapped(|ui| {
+                ui.label(format!(
+                    "Shown: {} | Total RSS: {} | Avg CPU: {:.1}% | States: {}",
+                    summary.total,
+                    format_memory(summary.total_rss_kb, self.memory_unit),
+                    summary.average_cpu_percent,
+                    states.join(" ")
+                ));
+                if !top.is_empty() {
+                    ui.separator();
+                    ui.label(format!("Top memory: {}", top.join(", ")));
+                }
+            });
+        });
+
+        // Starred process names, for jumping straight to frequently watched services
+        if self.show_favorites {
+            egui::SidePanel::left("favorites_panel")
+                .resizable(true)
+                .default_width(160.0)
+                .show(ctx, |ui| {
+                    ui.label(RichText::new("★ Favorites").strong());
+                    ui.separator();
+                    if self.favorites.is_empty() {
+                        ui.label(
+                            RichText::new("Star a process in the details panel to add it here")
+                                .color(Color32::GRAY)
+                                .small()
+                        );
+                    }
+                    let mut remove = None;
+                    for name in &self.favorites {
+                        // The longest-running instance is the stable choice when several share the name
+                        let running = self.manager.oldest_by_name(name).map(|p| p.process_id);
+                        ui.horizontal(|ui| {
+                            let label = match running {
+                                Some(_) => RichText::new(name),
+                                None => RichText::new(format!("{} (not running)", name)).color(Color32::GRAY),
+                            };
+                            let is_selected = running.is_some() && self.selected_pid == running;
+                            if ui.selectable_label(is_selected, label).clicked() {
+                                match running {
+                                    Some(pid) => self.selected_pid = Some(pid),
+                                    None => self.selection_notice = Some(format!("{} is not running", name)),
+                                }
+                            }
+                            if ui.small_button("✖").on_hover_text("Remove from favorites").clicked() {
+                                remove = Some(name.clone());
+                            }
+                        });
+                    }
+                    if let Some(name) = remove {
+                        self.favorites.retain(|n| *n != name);
+                    }
+                });
+        }
+
         // Main content area
         egui::CentralPanel::default().show(ctx, |ui| {
+            let mut dismiss_error = false;
             if let Some(error) = &self.error_message {
-                ui.colored_label(Color32::RED, format!("Error: {}", error));
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::RED, format!("Error: {}", error));
+                    dismiss_error = ui.small_button("✖").clicked();
+                });
+            }
+            if dismiss_error {
+                self.error_message = None;
+                self.error_message_time = None;
             }
             if let Some(success) = &self.success_message {
-                ui.colored_label(Color32::GREEN, format!("Success: {}", success));
+                if self.manager.dry_run {
+                    ui.colored_label(Color32::GREEN, format!("Dry run (nothing was sent): {}", success));
+                } else {
+                    ui.colored_label(Color32::GREEN, format!("Success: {}", success));
+                }
+            }
+            let mut dismiss_escalation = false;
+            if let Some(note) = &self.escalation_message {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::from_rgb(255, 165, 0), format!("🔐 {}", note));
+                    dismiss_escalation = ui.small_button("✖").clicked();
+                });
+            }
+            if dismiss_escalation {
+                self.escalation_message = None;
+            }
+            let mut dismiss_follow = false;
+            if let Some(notice) = &self.follow_notice {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::YELLOW, format!("📌 {}", notice));
+                    dismiss_follow = ui.small_button("✖").clicked();
+                });
+            }
+            if dismiss_follow {
+                self.follow_notice = None;
+            }
+            let mut dismiss_selection = false;
+            if let Some(notice) = &self.selection_notice {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::YELLOW, format!("ℹ {}", notice));
+                    dismiss_selection = ui.small_button("✖").clicked();
+                });
+            }
+            if dismiss_selection {
+                self.selection_notice = None;
+            }
+            // The system as a whole struggling: tasks stalled on memory (reclaim, swap) or I/O
+            if let Some(pressure) = self.manager.system_history.pressure {
+                for (resource, averages) in [("Memory", pressure.memory), ("I/O", pressure.io)] {
+                    if averages.avg10 > self.thresholds.pressure {
+                        ui.label(
+                            RichText::new(format!(
+                                "⚠ {} pressure: tasks stalled {:.1}% of the last 10 s ({:.1}% over 60 s)",
+                                resource, averages.avg10, averages.avg60
+                            ))
+                            .strong()
+                            .color(Color32::BLACK)
+                            .background_color(Color32::from_rgb(255, 165, 0)),
+                        );
+                    }
+                }
+            }
+            // Fork bombs and crash loops: one name spawning many processes at once
+            let mut kill_storm = None;
+            for (name, count) in self.manager.spawn_storms(self.thresholds.spawn_storm) {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(format!("⚠ {} spawned {} processes since the last refresh", name, count))
+                            .strong()
+                            .color(Color32::WHITE)
+                            .background_color(Color32::from_rgb(180, 0, 0)),
+                    );
+                    let hint = signal_hint(false, &format!("every running process named \"{}\"", name));
+                    if danger_button(ui, true, &format!("Kill All \"{}\"", name), self.colors.danger, &hint).clicked()
+                    {
+                        kill_storm = Some(name.clone());
+                    }
+                });
+            }
+            if let Some(name) = kill_storm {
+                let pids: Vec<u32> = self.manager.iter().filter(|p| p.name == name).map(|p| p.process_id).collect();
+                self.batch_kill(pids, false);
+                self.refresh_processes();
             }
 
             ui.vertical(|ui| {
                 // Search bar and controls
                 ui.horizontal(|ui| {
                     ui.label("Search:");
-                    let response = ui.text_edit_singleline(&mut self.search_filter);
+                    let response = ui.add(
+                        TextEdit::singleline(&mut self.search_filter)
+                            .hint_text("e.g. chrome, user:root state:Z"),
+                    )
+                    .on_hover_text("Bare words match name/command line. Fields: name:, user:, uid:, pid:, state:, cmdline:");
                     if response.changed() {
                         self.apply_filters_and_sort();
                     }
+                    if ui.checkbox(&mut self.fuzzy_search, "Fuzzy")
+                        .on_hover_text("Tolerate typos in process names and show the best matches first")
+                        .changed()
+                    {
+                        self.apply_filters_and_sort();
+                    }
                     
+                    // Stuck (D) filter toggle with the current count
+                    let d_count = self.manager.iter().filter(|p| p.pcb_data.state == 'D').count();
+                    if ui
+                        .selectable_label(self.show_only_stuck, format!("⏳ Stuck (D): {}", d_count))
+                        .on_hover_text("Show only processes in uninterruptible sleep")
+                        .clicked()
+                    {
+                        self.show_only_stuck = !self.show_only_stuck;
+                        self.apply_filters_and_sort();
+                    }
+
+                    // Processes running a deleted binary, with the current count
+                    let stale_count = self.manager.stale_binaries().len();
+                    if ui
+                        .selectable_label(self.show_only_stale, format!("♻ Needs Restart: {}", stale_count))
+                        .on_hover_text("Show only processes whose executable was deleted or replaced since they started (e.g. by a package upgrade), so they still run the old code")
+                        .clicked()
+                    {
+                        self.show_only_stale = !self.show_only_stale;
+                        self.apply_filters_and_sort();
+                    }
+
                     // Show zombie filter indicator
                     if self.show_only_zombies {
                         ui.label(
@@ -789,6 +2389,26 @@ impl eframe::App for ProcessManagerApp {
                     });
                 });
 
+                // Gentle hints for query parts that were ignored or can't match
+                for hint in &self.search_query.hints {
+                    ui.label(
+                        RichText::new(format!("ℹ {}", hint))
+                            .color(Color32::from_rgb(150, 150, 150))
+                            .small()
+                    );
+                }
+                if let Some((tty, pgrp)) = &self.foreground_pgrp {
+                    let leader = match self.manager.find_by_pid(*pgrp) {
+                        Some(leader) => format!("{} ({})", leader.name, pgrp),
+                        None => pgrp.to_string(),
+                    };
+                    ui.label(
+                        RichText::new(format!("Running in the foreground of {}: process group {} (marked FG)", tty, leader))
+                            .color(FOREGROUND_BADGE)
+                            .small()
+                    );
+                }
+
                 ui.separator();
 
                 // Process tree view or table view
@@ -808,18 +2428,33 @@ impl eframe::App for ProcessManagerApp {
                                     .small()
                             );
                         }
+                        ui.separator();
+                        ui.checkbox(&mut self.tree_show_cmdline, "Show command lines")
+                            .on_hover_text("Tell apart identical names (e.g. many cc1plus) by their arguments");
+                        if self.tree_show_cmdline {
+                            ui.add(egui::DragValue::new(&mut self.tree_cmdline_width).clamp_range(10..=500).suffix(" chars"));
+                        }
+                        ui.separator();
+                        if ui.button("Collapse All").clicked() {
+                            // Collapsing leaves has no effect, so only parents are recorded
+                            self.collapsed_tree_nodes = self.manager.iter()
+                                .filter_map(|p| p.parent_id)
+                                .collect();
+                        }
+                        if ui.button("Expand All").clicked() {
+                            self.collapsed_tree_nodes.clear();
+                        }
                     });
                     ui.separator();
+                    // Explicit id: banners appearing above would otherwise change the auto id and reset the scroll offset
                     ScrollArea::vertical()
+                        .id_source("process_tree_scroll")
                         .auto_shrink([false, false])
                         .show(ui, |ui| {
                             if let Some(root) = self.build_process_tree() {
                                 // Check if tree has any content after filtering
-                                if !self.search_filter.is_empty() {
-                                    let filter_lower = self.search_filter.to_lowercase();
-                                    let root_matches = root.process.name.to_lowercase().contains(&filter_lower)
-                                        || root.process.process_id.to_string().contains(&filter_lower)
-                                        || root.process.user_id.to_string().contains(&filter_lower);
+                                if !self.search_query.is_empty() {
+                                    let root_matches = self.search_query.matches(&root.process);
                                     if !root_matches && root.children.is_empty() {
                                         ui.label("No processes match the search filter");
                                     } else {
@@ -834,198 +2469,20 @@ impl eframe::App for ProcessManagerApp {
                         });
                 } else {
                     // Table view
-                    ScrollArea::vertical().show(ui, |ui| {
+                    let columns = self.visible_columns.clone();
+                    // Explicit id so the scroll offset survives refreshes (see the tree view above)
+                    ScrollArea::vertical().id_source("process_table_scroll").show(ui, |ui| {
                         egui::Grid::new("process_table")
-                            .num_columns(8)
+                            .num_columns(columns.len() + 1)
                             .spacing([10.0, 4.0])
                             .striped(true)
                             .show(ui, |ui| {
                                 // Header row
                                 // Select column
                                 ui.label(RichText::new("Select").strong());
-                                
-                                // PID column
-                                if ui
-                                    .selectable_label(
-                                        self.sort_column == SortColumn::Pid,
-                                        RichText::new("PID")
-                                            .strong()
-                                            .color(if self.sort_column == SortColumn::Pid {
-                                                Color32::YELLOW
-                                            } else {
-                                                Color32::WHITE
-                                            }),
-                                    )
-                                    .clicked()
-                                {
-                                    if self.sort_column == SortColumn::Pid {
-                                        self.sort_ascending = !self.sort_ascending;
-                                    } else {
-                                        self.sort_column = SortColumn::Pid;
-                                        self.sort_ascending = true;
-                                    }
-                                    self.apply_filters_and_sort();
-                                }
-
-                                // Name column
-                                if ui
-                                    .selectable_label(
-                                        self.sort_column == SortColumn::Name,
-                                        RichText::new("Name")
-                                            .strong()
-                                            .color(if self.sort_column == SortColumn::Name {
-                                                Color32::YELLOW
-                                            } else {
-                                                Color32::WHITE
-                                            }),
-                                    )
-                                    .clicked()
-                                {
-                                    if self.sort_column == SortColumn::Name {
-                                        self.sort_ascending = !self.sort_ascending;
-                                    } else {
-                                        self.sort_column = SortColumn::Name;
-                                        self.sort_ascending = true;
-                                    }
-                                    self.apply_filters_and_sort();
-                                }
-
-                                // UID column
-                                if ui
-                                    .selectable_label(
-                                        self.sort_column == SortColumn::Uid,
-                                        RichText::new("UID")
-                                            .strong()
-                                            .color(if self.sort_column == SortColumn::Uid {
-                                                Color32::YELLOW
-                                            } else {
-                                                Color32::WHITE
-                                            }),
-                                    )
-                                    .clicked()
-                                {
-                                    if self.sort_column == SortColumn::Uid {
-                                        self.sort_ascending = !self.sort_ascending;
-                                    } else {
-                                        self.sort_column = SortColumn::Uid;
-                                        self.sort_ascending = true;
-                                    }
-                                    self.apply_filters_and_sort();
-                                }
-
-                                // State column
-                                if ui
-                                    .selectable_label(
-                                        self.sort_column == SortColumn::State,
-                                        RichText::new("State")
-                                            .strong()
-                                            .color(if self.sort_column == SortColumn::State {
-                                                Color32::YELLOW
-                                            } else {
-                                                Color32::WHITE
-                                            }),
-                                    )
-                                    .clicked()
-                                {
-                                    if self.sort_column == SortColumn::State {
-                                        self.sort_ascending = !self.sort_ascending;
-                                    } else {
-                                        self.sort_column = SortColumn::State;
-                                        self.sort_ascending = true;
-                                    }
-                                    self.apply_filters_and_sort();
-                                }
-
-                                // CPU column
-                                if ui
-                                    .selectable_label(
-                                        self.sort_column == SortColumn::Cpu,
-                                        RichText::new("CPU %")
-                                            .strong()
-                                            .color(if self.sort_column == SortColumn::Cpu {
-                                                Color32::YELLOW
-                                            } else {
-                                                Color32::WHITE
-                                            }),
-                                    )
-                                    .clicked()
-                                {
-                                    if self.sort_column == SortColumn::Cpu {
-                                        self.sort_ascending = !self.sort_ascending;
-                                    } else {
-                                        self.sort_column = SortColumn::Cpu;
-                                        self.sort_ascending = true;
-                                    }
-                                    self.apply_filters_and_sort();
-                                }
-
-                                // Memory column
-                                if ui
-                                    .selectable_label(
-                                        self.sort_column == SortColumn::Memory,
-                                        RichText::new("Memory (MB)")
-                                            .strong()
-                                            .color(if self.sort_column == SortColumn::Memory {
-                                                Color32::YELLOW
-                                            } else {
-                                                Color32::WHITE
-                                            }),
-                                    )
-                                    .clicked()
-                                {
-                                    if self.sort_column == SortColumn::Memory {
-                                        self.sort_ascending = !self.sort_ascending;
-                                    } else {
-                                        self.sort_column = SortColumn::Memory;
-                                        self.sort_ascending = true;
-                                    }
-                                    self.apply_filters_and_sort();
-                                }
-
-                                // Priority column
-                                if ui
-                                    .selectable_label(
-                                        self.sort_column == SortColumn::Priority,
-                                        RichText::new("Priority")
-                                            .strong()
-                                            .color(if self.sort_column == SortColumn::Priority {
-                                                Color32::YELLOW
-                                            } else {
-                                                Color32::WHITE
-                                            }),
-                                    )
-                                    .clicked()
-                                {
-                                    if self.sort_column == SortColumn::Priority {
-                                        self.sort_ascending = !self.sort_ascending;
-                                    } else {
-                                        self.sort_column = SortColumn::Priority;
-                                        self.sort_ascending = true;
-                                    }
-                                    self.apply_filters_and_sort();
-                                }
 
-                                // Timer/Uptime column
-                                if ui
-                                    .selectable_label(
-                                        self.sort_column == SortColumn::Timer,
-                                        RichText::new("Timer")
-                                            .strong()
-                                            .color(if self.sort_column == SortColumn::Timer {
-                                                Color32::YELLOW
-                                            } else {
-                                                Color32::WHITE
-                                            }),
-                                    )
-                                    .clicked()
-                                {
-                                    if self.sort_column == SortColumn::Timer {
-                                        self.sort_ascending = !self.sort_ascending;
-                                    } else {
-                                        self.sort_column = SortColumn::Timer;
-                                        self.sort_ascending = true;
-                                    }
-                                    self.apply_filters_and_sort();
+                                for &column in &columns {
+                                    self.sort_header(ui, column.sort_column(), column.label());
                                 }
 
                                 ui.end_row();
@@ -1034,76 +2491,195 @@ impl eframe::App for ProcessManagerApp {
                                 // Collect selection changes to avoid borrowing conflicts
                                 let mut selection_changes: Vec<u32> = Vec::new();
                                 
-                                for &idx in &self.filtered_processes {
+                                // Large process lists make the grid sluggish, so only the top rows are rendered
+                                let shown = match self.max_rows {
+                                    0 => self.filtered_processes.len(),
+                                    max => max.min(self.filtered_processes.len()),
+                                };
+
+                                for &idx in &self.filtered_processes[..shown] {
                                     let process = &self.processes_vec[idx];
                                     let is_selected = self.selected_pids.contains(&process.process_id);
                                     let is_abnormal = self.is_abnormal(process);
 
                                     // Selection checkbox
                                     let mut checked = is_selected;
-                                    if ui.checkbox(&mut checked, "").changed() {
+                                    let checkbox = ui.checkbox(&mut checked, "");
+                                    if checkbox.changed() {
                                         selection_changes.push(process.process_id);
-                                    }
-
-                                    // PID column
-                                    let pid_response = ui.selectable_label(
-                                        self.selected_pid == Some(process.process_id),
-                                        process.process_id.to_string(),
-                                    );
-                                    if pid_response.clicked() {
-                                        self.selected_pid = Some(process.process_id);
-                                    }
-
-                                    // Name column (highlight if abnormal)
-                                    let name_color = if is_abnormal {
-                                        Color32::YELLOW
-                                    } else {
-                                        Color32::WHITE
-                                    };
-                                    let name_response = ui.selectable_label(
-                                        self.selected_pid == Some(process.process_id),
-                                        RichText::new(process.name.as_str()).color(name_color),
-                                    );
-                                    if name_response.clicked() {
-                                        self.selected_pid = Some(process.process_id);
-                                    }
-
-                                    // UID column
-                                    ui.label(process.user_id.to_string());
-
-                                    // State column (color-coded)
-                                    let state_color = match process.pcb_data.state {
-                                        'R' => Color32::GREEN,  // Running
-                                        'S' => Color32::BLUE,   // Sleeping
-                                        'D' => Color32::RED,    // Disk sleep
-                                        'Z' => Color32::YELLOW, // Zombie
-                                        'T' => Color32::GRAY,   // Stopped
-                                        _ => Color32::WHITE,
-                                    };
-                                    ui.colored_label(state_color, process.pcb_data.state.to_string());
-
-                                    // CPU column (highlight if exceeds threshold)
-                                    let cpu_color = if process.pcb_data.cpu_percent > self.thresholds.cpu_percent {
-                                        Color32::RED
-                                    } else {
-                                        Color32::WHITE
-                                    };
-                                    ui.colored_label(cpu_color, format!("{:.1}", process.pcb_data.cpu_percent));
-
-                                    // Memory column (highlight if exceeds threshold)
-                                    let mem_color = if process.pcb_data.memory_rss_mb > self.thresholds.memory_mb {
-                                        Color32::RED
-                                    } else {
-                                        Color32::WHITE
-                                    };
-                                    ui.colored_label(mem_color, format!("{:.1}", process.pcb_data.memory_rss_mb));
+                                    }
+                                    let is_pinned = self.pinned_pid == Some(process.process_id);
+                                    if is_pinned && self.scroll_to_pinned {
+                                        checkbox.scroll_to_me(Some(egui::Align::Center));
+                                        self.scroll_to_pinned = false;
+                                    }
 
-                                    // Priority column
-                                    ui.label(process.pcb_data.priority.to_string());
+                                    for &column in &columns {
+                                        match column {
+                                            Column::Pid => {
+                                                let pid_text = if is_pinned {
+                                                    RichText::new(format!("📌 {}", process.process_id)).color(Color32::LIGHT_GREEN)
+                                                } else {
+                                                    RichText::new(process.process_id.to_string())
+                                                };
+                                                let pid_response = ui.selectable_label(
+                                                    self.selected_pid == Some(process.process_id),
+                                                    pid_text,
+                                                );
+                                                if pid_response.clicked() {
+                                                    self.selected_pid = Some(process.process_id);
+                                                }
+                                            }
+                                            Column::Name => {
+                                                // Highlight if abnormal or recently renamed
+                                                let renamed_from = self.recent_renames.get(&process.process_id);
+                                                let name_color = if self.is_growing(process) {
+                                                    self.colors.growing
+                                                } else if process.exe_deleted {
+                                                    self.colors.stale_binary
+                                                } else if is_abnormal {
+                                                    self.colors.abnormal
+                                                } else if renamed_from.is_some() {
+                                                    Color32::LIGHT_BLUE
+                                                } else {
+                                                    Color32::WHITE
+                                                };
+                                                let name_truncated = process.name.chars().count() > self.name_max_chars;
+                                                let mut name_text = RichText::new(truncate_with_ellipsis(&process.name, self.name_max_chars)).color(name_color);
+                                                let is_new = self.is_new(process);
+                                                if is_new {
+                                                    name_text = name_text.background_color(self.colors.new_process);
+                                                }
+                                                let is_name_selected = self.selected_pid == Some(process.process_id);
+                                                let badge = sched_badge(process);
+                                                let foreground_of = self.foreground_pgrp.as_ref()
+                                                    .filter(|&&(_, pgrp)| process.pcb_data.pgrp == pgrp as i32)
+                                                    .map(|(tty, _)| tty);
+                                                let mut name_response = ui.horizontal(|ui| {
+                                                    let response = ui.selectable_label(is_name_selected, name_text);
+                                                    if let Some((text, color, hover)) = badge {
+                                                        ui.label(RichText::new(text).small().strong().color(Color32::BLACK).background_color(color))
+                                                            .on_hover_text(hover);
+                                                    }
+                                                    if let Some(tty) = foreground_of {
+                                                        ui.label(RichText::new("FG").small().strong().color(Color32::BLACK).background_color(FOREGROUND_BADGE))
+                                                            .on_hover_text(format!("In the foreground process group of {}: gets its input and Ctrl+C", tty));
+                                                    }
+                                                    response
+                                                }).inner;
+                                                if name_truncated {
+                                                    name_response = name_response.on_hover_text(&process.name);
+                                                }
+                                                if is_new {
+                                                    name_response = name_response.on_hover_text(format!(
+                                                        "Started {}s ago", process.pcb_data.uptime_seconds
+                                                    ));
+                                                }
+                                                if let Some((old_name, _)) = renamed_from {
+                                                    name_response = name_response.on_hover_text(format!("Renamed from \"{}\"", old_name));
+                                                }
+                                                if process.exe_deleted {
+                                                    name_response = name_response.on_hover_text("Needs restart: its executable was deleted or replaced");
+                                                }
+                                                if process.is_thread() {
+                                                    name_response = name_response.on_hover_text(format!(
+                                                        "Thread of PID {} (signals sent to it reach the whole process)", process.tgid.unwrap_or_default()
+                                                    ));
+                                                }
+                                                if name_response.clicked() {
+                                                    self.selected_pid = Some(process.process_id);
+                                                }
+                                            }
+                                            Column::Uid => {
+                                                match self.recent_uid_changes.get(&process.process_id) {
+                                                    Some((old_uid, _)) => {
+                                                        ui.colored_label(Color32::from_rgb(255, 165, 0), format!("⚠ {}", process.user_id))
+                                                            .on_hover_text(format!("Real UID changed from {} to {}", old_uid, process.user_id));
+                                                    }
+                                                    None => {
+                                                        ui.label(process.user_id.to_string());
+                                                    }
+                                                }
+                                            }
+                                            Column::State => {
+                                                // Color-coded
+                                                let state_color = self.colors.state(process.pcb_data.state);
+                                                if process.pcb_data.state == 'D' {
+                                                    // Uninterruptible sleep gets a warning badge, stronger once it persists
+                                                    let stuck = self.manager.is_stuck_in_d(process.process_id);
+                                                    let (text, background) = if stuck {
+                                                        ("⏳ D".to_string(), Color32::from_rgb(200, 0, 0))
+                                                    } else {
+                                                        (format!(" {} ", self.state_label('D')), Color32::from_rgb(150, 90, 0))
+                                                    };
+                                                    ui.label(RichText::new(text).strong().monospace().color(Color32::WHITE).background_color(background))
+                                                        .on_hover_text(if stuck {
+                                                            "Stuck in uninterruptible sleep across several refreshes (hung I/O?)"
+                                                        } else {
+                                                            "Uninterruptible sleep (usually waiting on disk or network I/O)"
+                                                        });
+                                                } else {
+                                                    ui.label(RichText::new(self.state_label(process.pcb_data.state)).color(state_color).monospace());
+                                                }
+                                            }
+                                            Column::Cpu => {
+                                                // Highlight if exceeds threshold
+                                                let cpu_color = if self.cpu_percent(process) > self.thresholds.cpu_percent {
+                                                    self.colors.cpu_over_threshold
+                                                } else {
+                                                    Color32::WHITE
+                                                };
+                                                let mut cpu_text = RichText::new(format!("{:.1}", self.cpu_percent(process))).color(cpu_color);
+                                                match self.cpu_spikes.get(&process.process_id) {
+                                                    Some(&(rise, at)) => {
+                                                        let fade = 1.0 - at.elapsed().as_secs_f32() / CPU_SPIKE_FLASH.as_secs_f32();
+                                                        cpu_text = cpu_text.background_color(self.colors.cpu_spike.gamma_multiply(fade.max(0.0)));
+                                                        ui.label(cpu_text).on_hover_text(format!("CPU % jumped by {:.1} since the last refresh", rise));
+                                                    }
+                                                    None => {
+                                                        ui.label(cpu_text);
+                                                    }
+                                                }
+                                            }
+                                            Column::CpuTime => {
+                                                ui.label(process.format_cpu_time());
+                                            }
+                                            Column::Memory => {
+                                                // Highlight if exceeds threshold
+                                                let mem_color = if process.rss_mb() > self.thresholds.memory_mb as f64 {
+                                                    self.colors.memory_over_threshold
+                                                } else {
+                                                    Color32::WHITE
+                                                };
+                                                ui.colored_label(mem_color, format_memory(process.pcb_data.memory_rss_kb, self.memory_unit));
+                                            }
+                                            Column::Priority => {
+                                                if process.is_realtime() {
+                                                    ui.label(format!("RT {}", process.pcb_data.rt_priority));
+                                                } else {
+                                                    ui.label(process.pcb_data.priority.to_string());
+                                                }
+                                            }
+                                            Column::Timer => {
+                                                ui.label(process.format_uptime());
+                                            }
+                                            Column::LastCpu => {
+                                                ui.label(process.pcb_data.last_cpu.to_string());
+                                            }
+                                        }
+                                    }
 
-                                    // Timer/Uptime column
-                                    ui.label(process.format_uptime());
+                                    ui.end_row();
+                                }
 
+                                let hidden = self.filtered_processes.len() - shown;
+                                if hidden > 0 {
+                                    ui.label("");
+                                    ui.label(
+                                        RichText::new(format!("+{} more (narrow your filter)", hidden))
+                                            .italics()
+                                            .color(Color32::GRAY)
+                                    );
                                     ui.end_row();
                                 }
                                 
@@ -1127,29 +2703,44 @@ impl eframe::App for ProcessManagerApp {
             .default_width(350.0)
             .show(ctx, |ui| {
                 // Process details and actions panel
-                // Copy the selected PID and process data to avoid borrowing conflicts
-                let selected_pid = self.selected_pid;
-                let process_data = selected_pid.and_then(|pid| {
-                    self.processes_vec.iter().find(|p| p.process_id == pid).map(|p| {
-                        (
-                            p.process_id,
-                            p.name.clone(),
-                            p.user_id,
-                            p.parent_id,
-                            p.pcb_data.state,
-                            p.pcb_data.memory_rss_mb,
-                            p.pcb_data.priority,
-                            p.pcb_data.cpu_percent,
-                            p.pcb_data.uptime_seconds,
-                            self.get_abnormality_reason(p),
-                        )
-                    })
-                });
+                // Copy the selected process data to avoid borrowing conflicts
+                let process_data = self
+                    .get_selected_process()
+                    .map(|p| (p.clone(), self.get_abnormality_reason(p)));
                 
                 ui.heading("Process Details & Actions");
                 ui.separator();
+
+                if let Some((a_pid, b_pid)) = self.comparison_pids() {
+                    self.show_comparison(ui, a_pid, b_pid);
+                    ui.separator();
+                }
                 
-                if let Some((process_pid, process_name, user_id, parent_id, state, memory, priority, cpu, uptime, abnormality_reason)) = process_data {
+                if let Some((process, abnormality_reason)) = process_data {
+                    let process_pid = process.process_id;
+                    // numa_maps is long, so it's only parsed for the selected process
+                    if self.numa_cache.map(|(pid, _)| pid) != Some(process_pid) {
+                        self.numa_cache = Some((process_pid, Process::numa_node(process_pid)));
+                    }
+                    let numa_node = self.numa_cache.and_then(|(_, node)| node);
+                    // Likewise smaps_rollup, which makes the kernel walk every mapping
+                    if self.memory_cache.map(|(pid, _)| pid) != Some(process_pid) {
+                        self.memory_cache = Some((process_pid, Process::memory_breakdown(process_pid)));
+                    }
+                    let memory_breakdown = self.memory_cache.and_then(|(_, breakdown)| breakdown);
+                    // schedstat is then re-read on every refresh for the wait rate
+                    if self.sched_sample.map(|(pid, _, _)| pid) != Some(process_pid) {
+                        self.sample_sched_stats(process_pid);
+                    }
+                    let sched_stats = self.sched_sample.and_then(|(_, stats, _)| stats);
+                    // Capabilities are decoded for the selected process only, so the warning below is always current
+                    if self.capability_cache.as_ref().map(|(pid, _)| *pid) != Some(process_pid) {
+                        self.capability_cache = Some((process_pid, Process::capabilities(process_pid)));
+                    }
+                    let dangerous_caps: Vec<String> = match &self.capability_cache {
+                        Some((_, Ok(caps))) => caps.dangerous().into_iter().map(str::to_string).collect(),
+                        _ => Vec::new(),
+                    };
                     // Details section
                     ui.label(
                         RichText::new("Details")
@@ -1165,68 +2756,592 @@ impl eframe::App for ProcessManagerApp {
                             ui.end_row();
 
                             ui.label("Name:");
-                            ui.label(&process_name);
+                            ui.horizontal(|ui| {
+                                ui.label(&process.name);
+                                let is_favorite = self.favorites.contains(&process.name);
+                                let star = ui.add(
+                                    egui::Button::new(if is_favorite { "★" } else { "☆" }).frame(false)
+                                );
+                                if star.on_hover_text(if is_favorite { "Remove from favorites" } else { "Add to favorites" }).clicked() {
+                                    if is_favorite {
+                                        self.favorites.retain(|n| *n != process.name);
+                                    } else {
+                                        self.favorites.push(process.name.clone());
+                                    }
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label("Command:");
+                            if process.cmdline.is_empty() {
+                                ui.label("N/A");
+                            } else {
+                                ui.add(egui::Label::new(process.cmdline_string()).wrap(true));
+                            }
                             ui.end_row();
 
+                            if process.exe_deleted {
+                                ui.label("Executable:");
+                                ui.colored_label(self.colors.stale_binary, "♻ Deleted since start (needs restart)")
+                                    .on_hover_text("The binary was removed or replaced (e.g. by a package upgrade); the process keeps running the old code, including any unpatched vulnerabilities, until it is restarted");
+                                ui.end_row();
+                            }
+
                             ui.label("User ID:");
-                            ui.label(user_id.to_string());
+                            ui.label(process.user_id.to_string());
                             ui.end_row();
 
-                            ui.label("Parent PID:");
-                            ui.label(
-                                parent_id
-                                    .map(|p| p.to_string())
-                                    .unwrap_or_else(|| "N/A".to_string()),
-                            );
+                            ui.label("Parent:");
+                            match process.parent_id {
+                                // PID 0 is the kernel (parent of init and kthreadd), which has no /proc entry
+                                Some(0) => ui.label("kernel (0)"),
+                                Some(ppid) => match self.manager.find_by_pid(ppid) {
+                                    Some(parent) => ui.label(format!("{} ({})", parent.name, ppid)),
+                                    None => ui.label(format!("{} (gone)", ppid))
+                                        .on_hover_text("The parent is not in the current process list (it has probably exited)"),
+                                },
+                                None => ui.label("N/A"),
+                            };
                             ui.end_row();
 
-                            ui.label("State:");
-                            let state_color = match state {
-                                'R' => Color32::GREEN,
-                                'S' => Color32::BLUE,
-                                'D' => Color32::RED,
-                                'Z' => Color32::YELLOW,
-                                'T' => Color32::GRAY,
-                                _ => Color32::WHITE,
+                            ui.label("Terminal:");
+                            match process.tty() {
+                                Some(tty) if process.is_tty_foreground() => ui.label(format!("{} (foreground, group {})", tty, process.pcb_data.pgrp)),
+                                Some(tty) => ui.label(format!("{} (background, group {})", tty, process.pcb_data.pgrp))
+                                    .on_hover_text(format!("Process group {} is in the foreground", process.pcb_data.tpgid)),
+                                None => ui.label("None"),
                             };
-                            ui.colored_label(state_color, state.to_string());
                             ui.end_row();
 
+                            ui.label("State:");
+                            let state_color = self.colors.state(process.pcb_data.state);
+                            ui.label(RichText::new(self.state_label(process.pcb_data.state)).color(state_color).monospace());
+                            ui.end_row();
+
+                            // How a zombie died or what stopped a stopped process, when that can be found out
+                            match (process.state_cause(), process.pcb_data.state) {
+                                (Some(cause), _) => {
+                                    let (label, value) = cause.describe();
+                                    ui.label(format!("{}:", label));
+                                    ui.label(value);
+                                    ui.end_row();
+                                }
+                                (None, 't') => {
+                                    ui.label("Stopped by:");
+                                    ui.label("debugger (ptrace)");
+                                    ui.end_row();
+                                }
+                                (None, 'T') => {
+                                    ui.label("Stopped by:");
+                                    ui.label("unknown").on_hover_text(
+                                        "A stop signal (SIGSTOP, SIGTSTP, SIGTTIN or SIGTTOU). Which one is only known for processes started from here",
+                                    );
+                                    ui.end_row();
+                                }
+                                (None, 'Z') => {
+                                    ui.label("Exit status:");
+                                    ui.label("unknown").on_hover_text("The kernel only shows it with permission to trace the process");
+                                    ui.end_row();
+                                }
+                                _ => {}
+                            }
+
                             ui.label("Memory (RSS):");
-                            ui.label(format!("{:.2} MB", memory));
+                            ui.label(format_memory(process.pcb_data.memory_rss_kb, self.memory_unit));
+                            ui.end_row();
+
+                            let unit = self.memory_unit;
+                            let size = |kb: u64| format_memory(kb, unit);
+                            match memory_breakdown {
+                                Some(memory) => {
+                                    ui.label("  Anonymous / file:");
+                                    ui.label(format!("{} / {}", size(memory.anonymous_kb), size(memory.file_backed_kb())))
+                                        .on_hover_text("Heap/stack vs. memory-mapped files and libraries");
+                                    ui.end_row();
+
+                                    ui.label("  Private (clean/dirty):");
+                                    ui.label(format!("{} / {}", size(memory.private_clean_kb), size(memory.private_dirty_kb)));
+                                    ui.end_row();
+
+                                    ui.label("  Shared (clean/dirty):");
+                                    ui.label(format!("{} / {}", size(memory.shared_clean_kb), size(memory.shared_dirty_kb)));
+                                    ui.end_row();
+
+                                    ui.label("  PSS:");
+                                    ui.label(size(memory.pss_kb))
+                                        .on_hover_text("Proportional set size: shared pages split among the processes using them");
+                                    ui.end_row();
+                                }
+                                None => {
+                                    ui.label("  Breakdown:");
+                                    ui.label("N/A").on_hover_text("smaps_rollup is unreadable (another user's process?)");
+                                    ui.end_row();
+                                }
+                            }
+
+                            ui.label("Session I/O:");
+                            match self.manager.session_io(process_pid) {
+                                Some((read, written)) => ui.label(format!(
+                                    "{} read / {} written",
+                                    format_memory(read / 1024, MemoryUnit::Auto),
+                                    format_memory(written / 1024, MemoryUnit::Auto)
+                                ))
+                                .on_hover_text("Storage I/O since this tool first saw the process"),
+                                None => ui.label("N/A")
+                                    .on_hover_text("/proc/[pid]/io is unreadable (another user's process?)"),
+                            };
                             ui.end_row();
 
-                            ui.label("Priority (Nice):");
-                            ui.label(priority.to_string());
+                            ui.label("Runqueue wait:");
+                            match (sched_stats, self.sched_wait_rate) {
+                                (None, _) => ui.label("unavailable")
+                                    .on_hover_text("/proc/[pid]/schedstat is missing (kernel built without CONFIG_SCHED_INFO)"),
+                                (Some(_), None) => ui.label("measuring…")
+                                    .on_hover_text("Needs a second reading, taken at the next refresh"),
+                                (Some(stats), Some(rate)) => ui.label(format!("{:.1} ms/s", rate))
+                                    .on_hover_text(format!(
+                                        "Time spent runnable but waiting for a CPU, per second; high values mean CPU starvation.\n\
+                                         Total: {:.2}s running, {:.2}s waiting over {} timeslices",
+                                        stats.sched_run_ns as f64 / 1e9,
+                                        stats.sched_wait_ns as f64 / 1e9,
+                                        stats.timeslices
+                                    )),
+                            };
+                            ui.end_row();
+
+                            if process.is_realtime() {
+                                ui.label("RT priority:");
+                                ui.label(format!(
+                                    "{} ({})",
+                                    process.pcb_data.rt_priority,
+                                    sched_policy_name(process.pcb_data.sched_policy)
+                                ))
+                                .on_hover_text("Real-time task: the nice value has no effect");
+                            } else {
+                                ui.label("Priority (Nice):");
+                                ui.label(process.pcb_data.priority.to_string());
+                            }
                             ui.end_row();
 
                             ui.label("CPU %:");
-                            ui.label(format!("{:.2}%", cpu));
+                            ui.label(format!(
+                                "{:.1}% (of {:.0}% max), smoothed {:.1}%",
+                                process.pcb_data.cpu_percent,
+                                self.cpu_cores * 100.0,
+                                process.cpu_percent_smoothed()
+                            ))
+                            .on_hover_text(format!(
+                                "100% is one fully busy core; with {} cores a multithreaded process can reach {:.0}%",
+                                self.cpu_cores, self.cpu_cores * 100.0
+                            ));
                             ui.end_row();
 
-                            ui.label("Uptime:");
-                            // Format uptime nicely
-                            let hours = uptime / 3600;
-                            let minutes = (uptime % 3600) / 60;
-                            let secs = uptime % 60;
-                            let uptime_str = if hours > 0 {
-                                format!("{}h {}m {}s", hours, minutes, secs)
-                            } else if minutes > 0 {
-                                format!("{}m {}s", minutes, secs)
+                            ui.label("CPU % of Machine:");
+                            ui.label(format!("{:.1}%", process.cpu_percent_normalized(self.cpu_cores)));
+                            ui.end_row();
+
+                            ui.label("Avg CPU % (watched):");
+                            match (process.avg_cpu_percent_since_seen(), self.manager.watched_for(process_pid)) {
+                                (Some(average), Some(watched)) => {
+                                    ui.label(format!("{:.1}% over {}", average, format_elapsed(watched.as_secs())))
+                                        .on_hover_text("CPU time used since this session first saw the process, divided by the time since then");
+                                }
+                                _ => {
+                                    ui.label("measuring…");
+                                }
+                            }
+                            ui.end_row();
+
+                            // A mostly-system split hints at a syscall/IO-bound process, mostly-user at a compute-bound one
+                            ui.label("CPU Time:");
+                            let utime = process.pcb_data.utime_seconds;
+                            let stime = process.pcb_data.stime_seconds;
+                            let total = utime + stime;
+                            if total > 0.0 {
+                                ui.label(format!(
+                                    "user {:.2}s / system {:.2}s ({:.0}% user)",
+                                    utime, stime, utime / total * 100.0
+                                ));
                             } else {
-                                format!("{}s", secs)
-                            };
-                            ui.label(uptime_str);
+                                ui.label("user 0.00s / system 0.00s");
+                            }
+                            ui.end_row();
+
+                            ui.label("I/O wait:");
+                            ui.label(format!("{:.0} ms/s", process.pcb_data.blkio_wait_ms_per_sec))
+                                .on_hover_text("Time spent waiting on block I/O per second (needs delay accounting)");
+                            ui.end_row();
+
+                            ui.label("Last CPU core:");
+                            ui.label(if process.pcb_data.last_cpu >= 0 {
+                                process.pcb_data.last_cpu.to_string()
+                            } else {
+                                "N/A".to_string()
+                            });
+                            ui.end_row();
+
+                            ui.label("NUMA node:");
+                            ui.label(match numa_node {
+                                Some(node) => node.to_string(),
+                                None => "N/A".to_string(),
+                            })
+                            .on_hover_text("Node holding most of the process's resident memory");
+                            ui.end_row();
+
+                            ui.label("Uptime:");
+                            ui.label(process.format_uptime());
+                            ui.end_row();
+
+                            ui.label("Started:");
+                            ui.label(process.age_relative());
                             ui.end_row();
 
                             // Show abnormality reason if any
                             if let Some(reason) = abnormality_reason {
                                 ui.label("⚠️ Warning:");
-                                ui.colored_label(Color32::YELLOW, reason);
+                                ui.colored_label(self.colors.abnormal, reason);
                                 ui.end_row();
                             }
+                            if !dangerous_caps.is_empty() {
+                                ui.label("⚠️ Privileged:");
+                                ui.colored_label(self.colors.abnormal, format!("Holds {}", dangerous_caps.join(", ")))
+                                    .on_hover_text("These capabilities are close to full root (load kernel modules, mount, trace any process). \
+                                                    Expected for system daemons; suspicious for anything else.");
+                                ui.end_row();
+                            }
+                        });
+
+                    ui.separator();
+
+                    // Identity section (resolves names only while expanded)
+                    egui::CollapsingHeader::new("Identity / Capabilities")
+                        .id_source("identity_section")
+                        .show(ui, |ui| {
+                            let uid_label = |uid: u32| match user::name_for_uid(uid) {
+                                Some(name) => format!("{} ({})", name, uid),
+                                None => uid.to_string(),
+                            };
+                            egui::Grid::new("identity_details")
+                                .num_columns(2)
+                                .spacing([10.0, 4.0])
+                                .show(ui, |ui| {
+                                    ui.label("Real UID:");
+                                    ui.label(uid_label(process.user_id));
+                                    ui.end_row();
+
+                                    ui.label("Effective UID:");
+                                    let euid_text = uid_label(process.effective_uid);
+                                    if process.effective_uid != process.user_id {
+                                        // setuid binaries or privilege drops show up here
+                                        ui.colored_label(Color32::from_rgb(255, 165, 0), euid_text)
+                                            .on_hover_text("Differs from the real UID");
+                                    } else {
+                                        ui.label(euid_text);
+                                    }
+                                    ui.end_row();
+
+                                    ui.label("Saved UID:");
+                                    ui.label(uid_label(process.saved_uid));
+                                    ui.end_row();
+
+                                    ui.label("Groups:");
+                                    if process.groups.is_empty() {
+                                        ui.label("None");
+                                    } else {
+                                        let names = user::group_names();
+                                        let groups: Vec<String> = process
+                                            .groups
+                                            .iter()
+                                            .map(|gid| match names.get(gid) {
+                                                Some(name) => format!("{} ({})", name, gid),
+                                                None => gid.to_string(),
+                                            })
+                                            .collect();
+                                        ui.add(egui::Label::new(groups.join(", ")).wrap(true));
+                                    }
+                                    ui.end_row();
+
+                                    match self.capability_cache.as_ref().map(|(_, caps)| caps) {
+                                        Some(Ok(caps)) => {
+                                            let dangerous = caps.dangerous();
+                                            capability_row(ui, "Effective caps:", &caps.capabilities, &dangerous, self.colors.abnormal);
+                                            capability_row(ui, "Permitted caps:", &caps.permitted, &dangerous, self.colors.abnormal);
+                                            if let Some(bounding) = &caps.bounding {
+                                                capability_row(ui, "Bounding set:", bounding, &[], self.colors.abnormal);
+                                            }
+                                        }
+                                        Some(Err(e)) => {
+                                            ui.label("Capabilities:");
+                                            ui.label(format!("N/A ({})", e));
+                                            ui.end_row();
+                                        }
+                                        None => {}
+                                    }
+                                });
+                        });
+
+                    // Namespaces section (read from /proc only while expanded, then cached until the next refresh)
+                    egui::CollapsingHeader::new("Namespaces")
+                        .id_source("namespaces_section")
+                        .show(ui, |ui| {
+                            if self.namespace_cache.as_ref().map(|(pid, _)| *pid) != Some(process_pid) {
+                                self.namespace_cache = Some((process_pid, Process::namespaces(process_pid)));
+                            }
+                            let Some((_, namespaces)) = &self.namespace_cache else {
+                                return;
+                            };
+                            if namespaces.likely_containerized() {
+                                ui.colored_label(Color32::from_rgb(100, 180, 255), "📦 Likely containerized or chrooted");
+                            }
+                            egui::Grid::new("namespace_details")
+                                .num_columns(2)
+                                .spacing([10.0, 4.0])
+                                .show(ui, |ui| {
+                                    ui.label("Root directory:");
+                                    ui.label(match &namespaces.root_path {
+                                        Some(root) => root.display().to_string(),
+                                        None => "N/A (permission denied?)".to_string(),
+                                    });
+                                    ui.end_row();
+
+                                    ui.label("Mount namespace:");
+                                    let mnt = namespaces.mnt_namespace.as_deref().unwrap_or("N/A");
+                                    match namespaces.same_mnt_namespace_as_us {
+                                        Some(false) => {
+                                            ui.colored_label(Color32::from_rgb(255, 165, 0), mnt)
+                                                .on_hover_text("Differs from this process manager's mount namespace");
+                                        }
+                                        _ => {
+                                            ui.label(mnt);
+                                        }
+                                    }
+                                    ui.end_row();
+                                });
+                        });
+
+                    // Mounts section (read from /proc only while expanded, then cached until the next refresh)
+                    egui::CollapsingHeader::new("Mounts")
+                        .id_source("mounts_section")
+                        .show(ui, |ui| {
+                            if self.mount_cache.as_ref().map(|(pid, _)| *pid) != Some(process_pid) {
+                                self.mount_cache = Some((process_pid, Process::mounts(process_pid)));
+                            }
+                            match self.mount_cache.as_ref().map(|(_, mounts)| mounts) {
+                                Some(Ok(mounts)) => {
+                                    if mounts.len() > MAX_MOUNT_ROWS {
+                                        ui.label(format!("Showing {} of {} mounts", MAX_MOUNT_ROWS, mounts.len()));
+                                    } else {
+                                        ui.label(format!("{} mount(s)", mounts.len()));
+                                    }
+                                    ScrollArea::vertical()
+                                        .id_source("mounts_scroll")
+                                        .max_height(200.0)
+                                        .show(ui, |ui| {
+                                            egui::Grid::new("mount_list")
+                                                .num_columns(4)
+                                                .spacing([10.0, 2.0])
+                                                .striped(true)
+                                                .show(ui, |ui| {
+                                                    ui.label(RichText::new("Target").strong());
+                                                    ui.label(RichText::new("Source").strong());
+                                                    ui.label(RichText::new("Type").strong());
+                                                    ui.label(RichText::new("Options").strong());
+                                                    ui.end_row();
+
+                                                    for mount in mounts.iter().take(MAX_MOUNT_ROWS) {
+                                                        ui.monospace(&mount.target);
+                                                        ui.monospace(&mount.source);
+                                                        ui.label(&mount.fstype);
+                                                        ui.label(RichText::new(&mount.options).small());
+                                                        ui.end_row();
+                                                    }
+                                                });
+                                        });
+                                }
+                                Some(Err(e)) => {
+                                    ui.colored_label(Color32::RED, e);
+                                }
+                                None => {}
+                            }
+                        });
+
+                    // Network section (read from /proc only while expanded, then cached until the next refresh)
+                    egui::CollapsingHeader::new("Network")
+                        .id_source("network_section")
+                        .show(ui, |ui| {
+                            if self.connection_cache.as_ref().map(|(pid, _)| *pid) != Some(process_pid) {
+                                self.connection_cache = Some((process_pid, Process::connections(process_pid)));
+                            }
+                            match self.connection_cache.as_ref().map(|(_, connections)| connections) {
+                                Some(Ok(connections)) if connections.is_empty() => {
+                                    ui.label("No TCP/UDP sockets");
+                                }
+                                Some(Ok(connections)) => {
+                                    ui.label(format!("{} socket(s)", connections.len()));
+                                    ScrollArea::vertical()
+                                        .id_source("network_scroll")
+                                        .max_height(200.0)
+                                        .show(ui, |ui| {
+                                            egui::Grid::new("connection_list")
+                                                .num_columns(4)
+                                                .spacing([10.0, 2.0])
+                                                .striped(true)
+                                                .show(ui, |ui| {
+                                                    ui.label(RichText::new("Proto").strong());
+                                                    ui.label(RichText::new("Local").strong());
+                                                    ui.label(RichText::new("Remote").strong());
+                                                    ui.label(RichText::new("State").strong());
+                                                    ui.end_row();
+
+                                                    for connection in connections {
+                                                        ui.label(connection.protocol);
+                                                        ui.monospace(connection.local.to_string());
+                                                        ui.monospace(connection.remote.to_string());
+                                                        ui.label(connection.state);
+                                                        ui.end_row();
+                                                    }
+                                                });
+                                        });
+                                }
+                                Some(Err(e)) => {
+                                    ui.colored_label(Color32::RED, e);
+                                }
+                                None => {}
+                            }
+                        });
+
+                    // Threads section (read from /proc only while expanded, then cached until the next refresh)
+                    egui::CollapsingHeader::new("Threads")
+                        .id_source("threads_section")
+                        .show(ui, |ui| {
+                            if self.thread_cache.as_ref().map(|(pid, _)| *pid) != Some(process_pid) {
+                                self.thread_cache = Some((process_pid, Process::threads(process_pid)));
+                            }
+                            match self.thread_cache.as_ref().map(|(_, threads)| threads) {
+                                Some(Ok(threads)) => {
+                                    ui.label(format!("{} thread(s), busiest first", threads.len()));
+                                    ScrollArea::vertical()
+                                        .id_source("threads_scroll")
+                                        .max_height(200.0)
+                                        .show(ui, |ui| {
+                                            egui::Grid::new("thread_list")
+                                                .num_columns(4)
+                                                .spacing([10.0, 2.0])
+                                                .striped(true)
+                                                .show(ui, |ui| {
+                                                    ui.label(RichText::new("TID").strong());
+                                                    ui.label(RichText::new("Name").strong());
+                                                    ui.label(RichText::new("State").strong());
+                                                    ui.label(RichText::new("CPU Time").strong());
+                                                    ui.end_row();
+
+                                                    for thread in threads {
+                                                        ui.label(thread.tid.to_string());
+                                                        ui.label(thread.name.as_str());
+                                                        ui.label(thread.state.to_string());
+                                                        ui.label(format!("{:.2}s", thread.cpu_time_secs()));
+                                                        ui.end_row();
+                                                    }
+                                                });
+                                        });
+                                }
+                                Some(Err(e)) => {
+                                    ui.colored_label(Color32::RED, e);
+                                }
+                                None => {}
+                            }
+                        });
+
+                    // Resource limits section (read from /proc only while expanded, then cached until the next refresh)
+                    egui::CollapsingHeader::new("Resource Limits")
+                        .id_source("rlimits_section")
+                        .show(ui, |ui| {
+                            if self.rlimit_cache.as_ref().map(|(pid, _)| *pid) != Some(process_pid) {
+                                self.rlimit_cache = Some((process_pid, Process::rlimits(process_pid)));
+                            }
+                            match self.rlimit_cache.as_ref().map(|(_, limits)| limits) {
+                                Some(Ok(limits)) => {
+                                    egui::Grid::new("rlimit_list")
+                                        .num_columns(3)
+                                        .spacing([10.0, 2.0])
+                                        .striped(true)
+                                        .show(ui, |ui| {
+                                            ui.label(RichText::new("Resource").strong());
+                                            ui.label(RichText::new("Soft").strong());
+                                            ui.label(RichText::new("Hard").strong());
+                                            ui.end_row();
+
+                                            for limit in limits {
+                                                ui.label(limit.resource.label());
+                                                ui.label(limit.resource.format_value(limit.soft));
+                                                ui.label(limit.resource.format_value(limit.hard));
+                                                ui.end_row();
+                                            }
+                                        });
+                                }
+                                Some(Err(e)) => {
+                                    ui.colored_label(Color32::RED, e);
+                                }
+                                None => {}
+                            }
+
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                for resource in RlimitResource::ALL {
+                                    ui.radio_value(&mut self.rlimit_resource, resource, resource.label());
+                                }
+                            });
+                            let hint = format!("{} or \"unlimited\"", self.rlimit_resource.unit());
+                            ui.horizontal(|ui| {
+                                ui.label("Soft:");
+                                ui.add(TextEdit::singleline(&mut self.rlimit_soft_input).desired_width(100.0).hint_text(&hint));
+                                ui.label("Hard:");
+                                ui.add(TextEdit::singleline(&mut self.rlimit_hard_input).desired_width(100.0).hint_text(&hint));
+                                if ui.button("Set Limit")
+                                    .on_hover_text("Lowering is allowed for your own processes; raising the hard limit needs root")
+                                    .clicked()
+                                {
+                                    let resource = self.rlimit_resource;
+                                    let result = parse_limit(&self.rlimit_soft_input)
+                                        .and_then(|soft| parse_limit(&self.rlimit_hard_input).map(|hard| (soft, hard)))
+                                        .and_then(|(soft, hard)| operations::set_rlimit(&self.manager, process_pid, resource, soft, hard));
+                                    match result {
+                                        Ok(()) => {
+                                            self.success_message = Some(format!("Set {} limit of process {}", resource.label().to_lowercase(), process_pid));
+                                            self.success_message_time = Some(Instant::now());
+                                            self.rlimit_cache = None;
+                                        }
+                                        Err(e) => self.set_error(e),
+                                    }
+                                }
+                            });
                         });
 
+                    ui.horizontal(|ui| {
+                        if ui.button("📄 Full Report").on_hover_text("Everything readable from /proc for this process").clicked() {
+                            self.full_report = Some((process_pid, process.full_report()));
+                        }
+                        if self.manager.protected_pids.contains(&process_pid) {
+                            if ui.button("🛡 Unprotect").on_hover_text("Allow kill/terminate/pause on this PID").clicked() {
+                                self.manager.protected_pids.remove(&process_pid);
+                            }
+                        } else if ui.button("🛡 Protect").on_hover_text("Refuse kill/terminate/pause on this PID").clicked() {
+                            self.manager.protected_pids.insert(process_pid);
+                        }
+                        if self.pinned_pid == Some(process_pid) {
+                            if ui.button("📌 Unfollow").clicked() {
+                                self.pinned_pid = None;
+                            }
+                        } else if ui.button("📌 Follow")
+                            .on_hover_text("Keep this process highlighted, scrolled into view, and its stats in the top bar")
+                            .clicked()
+                        {
+                            self.pinned_pid = Some(process_pid);
+                            self.follow_notice = None;
+                            self.scroll_to_pinned = true;
+                        }
+                    });
+
                     ui.separator();
                     
                     // Actions section
@@ -1235,84 +3350,218 @@ impl eframe::App for ProcessManagerApp {
                             .strong()
                             .size(14.0)
                     );
+                    // Only offer actions that make sense for the current state
+                    let is_zombie = process.pcb_data.state == 'Z';
+                    let is_stopped = matches!(process.pcb_data.state, 'T' | 't');
+                    let zombie_hint = "Zombies have already exited and can't be signaled";
+
+                    if is_zombie {
+                        ui.colored_label(
+                            Color32::YELLOW,
+                            "🧟 This process is a zombie: it has already exited and only its parent can remove it by reaping it.",
+                        );
+                        match process.parent_id.filter(|&ppid| ppid > 1) {
+                            Some(ppid) => {
+                                if ui.button(format!("Ask Parent {} to Reap", ppid)).clicked() {
+                                    match operations::notify_child_exit(&self.manager, ppid) {
+                                        Ok(_) => {
+                                            self.success_message = Some(format!("Sent SIGCHLD to parent {}", ppid));
+                                            self.success_message_time = Some(Instant::now());
+                                            self.refresh_processes();
+                                        }
+                                        Err(e) => self.set_error(e),
+                                    }
+                                }
+                            }
+                            None => {
+                                ui.label("Its parent is init, which reaps zombies on its own.");
+                            }
+                        }
+                        ui.label(
+                            RichText::new("If the parent ignores the request, use Operations → Clean Zombies to kill the parent.")
+                                .small()
+                        );
+                        ui.separator();
+                    }
+
                     ui.vertical(|ui| {
-                        if ui.button("Kill").clicked() {
+                        if danger_button(ui, !is_zombie, "Kill", self.colors.danger, &signal_hint(true, &format!("PID {}", process_pid)))
+                            .on_disabled_hover_text(zombie_hint)
+                            .clicked()
+                        {
                             match self.kill_process(process_pid) {
                                 Ok(_) => {
                                     self.success_message = Some(format!("Killed process {}", process_pid));
                                     self.success_message_time = Some(Instant::now());
                                     self.refresh_processes();
                                 }
-                                Err(e) => self.error_message = Some(e),
+                                Err(e) => self.set_error(e),
                             }
                         }
 
-                        if ui.button("Force Kill").clicked() {
+                        if danger_button(ui, !is_zombie, "Force Kill", self.colors.danger, &signal_hint(true, &format!("PID {}", process_pid)))
+                            .on_disabled_hover_text(zombie_hint)
+                            .clicked()
+                        {
                             match self.kill_process(process_pid) {
                                 Ok(_) => {
                                     self.success_message = Some(format!("Force killed process {}", process_pid));
                                     self.success_message_time = Some(Instant::now());
                                     self.refresh_processes();
                                 }
-                                Err(e) => self.error_message = Some(e),
+                                Err(e) => self.set_error(e),
                             }
                         }
                         
-                        if ui.button("Terminate").clicked() {
+                        if danger_button(ui, !is_zombie, "Terminate", self.colors.danger, &signal_hint(false, &format!("PID {}", process_pid)))
+                            .on_disabled_hover_text(zombie_hint)
+                            .clicked()
+                        {
                             match self.terminate_process(process_pid) {
                                 Ok(_) => {
                                     self.success_message = Some(format!("Terminated process {}", process_pid));
                                     self.success_message_time = Some(Instant::now());
                                     self.refresh_processes();
                                 }
-                                Err(e) => self.error_message = Some(e),
+                                Err(e) => self.set_error(e),
+                            }
+                        }
+
+                        let hard_hint = format!(
+                            "Sends SIGTERM to PID {} twice, then SIGKILL if it is still running after {}s. Freezes the window until it is done.",
+                            process_pid,
+                            operations::DEFAULT_FORCE_TERMINATE_GRACE.as_secs()
+                        );
+                        if danger_button(ui, !is_zombie, "Terminate Hard", self.colors.danger, &hard_hint)
+                            .on_disabled_hover_text(zombie_hint)
+                            .clicked()
+                        {
+                            match self.force_terminate(process_pid) {
+                                Ok(outcome @ operations::KillOutcome::Unkillable { .. }) => self.set_error(outcome.describe(process_pid)),
+                                Ok(outcome) => {
+                                    self.success_message = Some(outcome.describe(process_pid));
+                                    self.success_message_time = Some(Instant::now());
+                                }
+                                Err(e) => self.set_error(e),
                             }
+                            self.refresh_processes();
                         }
 
-                        if ui.button("Pause").clicked() {
+                        if ui.add_enabled(!is_zombie && !is_stopped, egui::Button::new("Pause"))
+                            .on_disabled_hover_text(if is_zombie { zombie_hint } else { "Process is already stopped" })
+                            .clicked()
+                        {
                             match self.pause_process(process_pid) {
                                 Ok(_) => {
                                     self.success_message = Some(format!("Paused process {}", process_pid));
                                     self.success_message_time = Some(Instant::now());
                                     self.refresh_processes();
                                 }
-                                Err(e) => self.error_message = Some(e),
+                                Err(e) => self.set_error(e),
                             }
                         }
 
-                        if ui.button("Resume").clicked() {
+                        if ui.add_enabled(is_stopped, egui::Button::new("Resume"))
+                            .on_disabled_hover_text("Process isn't stopped")
+                            .clicked()
+                        {
                             match self.resume_process(process_pid) {
                                 Ok(_) => {
                                     self.success_message = Some(format!("Resumed process {}", process_pid));
                                     self.success_message_time = Some(Instant::now());
                                     self.refresh_processes();
                                 }
-                                Err(e) => self.error_message = Some(e),
+                                Err(e) => self.set_error(e),
                             }
                         }
 
                         ui.separator();
                         ui.label("Set Priority (Nice):");
-                        ui.horizontal(|ui| {
+                        ui.add_enabled_ui(!is_zombie, |ui| ui.horizontal(|ui| {
                             ui.add(TextEdit::singleline(&mut self.priority_input)
                                 .desired_width(60.0)
                                 .hint_text("-20 to 19"));
                             if ui.button("Apply").clicked() {
                                 if let Ok(nice) = self.priority_input.parse::<i32>() {
-                                    match self.set_priority(process_pid, nice) {
-                                        Ok(_) => {
-                                            self.success_message = Some(format!("Set priority {} for process {}", nice, process_pid));
+                                    let result = self.set_priority(process_pid, nice);
+                                    // Refresh either way: a partial subtree renice still changed some priorities
+                                    self.refresh_processes();
+                                    match result {
+                                        Ok(pids) => {
+                                            self.success_message = Some(if pids.len() > 1 {
+                                                format!("Set priority {} for process {} and {} descendants", nice, process_pid, pids.len() - 1)
+                                            } else {
+                                                format!("Set priority {} for process {}", nice, process_pid)
+                                            });
                                             self.success_message_time = Some(Instant::now());
                                             self.priority_input.clear();
-                                            self.refresh_processes();
                                         }
-                                        Err(e) => self.error_message = Some(e),
+                                        Err(e) => self.set_error(e),
                                     }
                                 } else {
-                                    self.error_message = Some("Invalid priority value".to_string());
+                                    self.set_error("Invalid priority value".to_string());
                                 }
                             }
-                        });
+                            ui.checkbox(&mut self.priority_recursive, "Apply to subtree")
+                                .on_hover_text("Also set the nice value of every descendant");
+                        }));
+                        let owner = process.user_id;
+                        if ui.button(format!("Apply to All of UID {}", owner))
+                            .on_hover_text("Renice every process owned by this user")
+                            .clicked()
+                        {
+                            match self.priority_input.parse::<i32>() {
+                                Ok(nice) => {
+                                    let result = operations::renice_user(&self.manager, owner, nice);
+                                    // Refresh either way: partial failures still changed some priorities
+                                    self.refresh_processes();
+                                    match result {
+                                        Ok(pids) => {
+                                            self.success_message = Some(format!(
+                                                "Set priority {} for {} processes of UID {}",
+                                                nice, pids.len(), owner
+                                            ));
+                                            self.success_message_time = Some(Instant::now());
+                                            self.priority_input.clear();
+                                        }
+                                        Err(e) => self.set_error(e),
+                                    }
+                                }
+                                Err(_) => self.set_error("Invalid priority value".to_string()),
+                            }
+                        }
+
+                        // When several processes share this name, offer to kill just the oldest or newest one
+                        let same_name = self.manager.iter().filter(|p| p.name == process.name).count();
+                        if same_name > 1 {
+                            ui.separator();
+                            ui.label(format!("{} processes named \"{}\":", same_name, process.name));
+                            ui.horizontal(|ui| {
+                                let mut target = None;
+                                let oldest = self.manager.oldest_by_name(&process.name).map(|p| p.process_id);
+                                let newest = self.manager.newest_by_name(&process.name).map(|p| p.process_id);
+                                if let (Some(oldest), Some(newest)) = (oldest, newest) {
+                                    let hint = signal_hint(true, &format!("PID {}, the longest-running one", oldest));
+                                    if danger_button(ui, true, "Kill Oldest", self.colors.danger, &hint).clicked() {
+                                        target = Some(oldest);
+                                    }
+                                    let hint = signal_hint(true, &format!("PID {}, the most recently started one", newest));
+                                    if danger_button(ui, true, "Kill Newest", self.colors.danger, &hint).clicked() {
+                                        target = Some(newest);
+                                    }
+                                }
+                                if let Some(pid) = target {
+                                    match self.kill_process(pid) {
+                                        Ok(_) => {
+                                            self.success_message = Some(format!("Killed {} (PID {})", process.name, pid));
+                                            self.success_message_time = Some(Instant::now());
+                                            self.refresh_processes();
+                                        }
+                                        Err(e) => self.set_error(e),
+                                    }
+                                }
+                            });
+                        }
                     });
                 } else {
                     ui.label("Select a process to view details and perform actions");
@@ -1320,3 +3569,177 @@ impl eframe::App for ProcessManagerApp {
             });
     }
 }
+
+/// Small badge flagging unusual scheduling: "RT" for real-time policies, "IDLE" for SCHED_IDLE,
+/// otherwise the nice value when it isn't 0. Returns (text, background, hover text).
+fn sched_badge(process: &Process) -> Option<(String, Color32, String)> {
+    let policy = process.pcb_data.sched_policy;
+    let nice = process.pcb_data.priority;
+    if process.is_realtime() || policy == 6 {
+        Some((
+            "RT".to_string(),
+            Color32::from_rgb(255, 90, 90),
+            format!("{} (RT priority {})", sched_policy_name(policy), process.pcb_data.rt_priority),
+        ))
+    } else if policy == 5 {
+        Some(("IDLE".to_string(), Color32::GRAY, "SCHED_IDLE: runs only when nothing else wants the CPU".to_string()))
+    } else if nice < 0 {
+        Some((nice.to_string(), Color32::from_rgb(255, 180, 80), format!("Nice {}: favored by the scheduler", nice)))
+    } else if nice > 0 {
+        Some((format!("+{}", nice), Color32::from_rgb(130, 180, 255), format!("Nice +{}: yields the CPU to others", nice)))
+    } else {
+        None
+    }
+}
+
+/// Shape shown before a state letter, so states differ by more than color
+fn state_glyph(state: char) -> Option<&'static str> {
+    match state {
+        'R' => Some("●"),
+        'S' => Some("◐"),
+        'D' => Some("◆"),
+        'Z' => Some("☠"),
+        'T' | 't' => Some("⏸"),
+        _ => None,
+    }
+}
+
+/// Parse a resource limit typed in the details panel: a number, or "unlimited" (RLIM_INFINITY)
+fn parse_limit(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("unlimited") || input.eq_ignore_ascii_case("infinity") {
+        return Ok(libc::RLIM_INFINITY);
+    }
+    input
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid limit: \"{}\" (enter a number or \"unlimited\")", input))
+}
+
+/// Shorten text to at most max_chars characters, ending in "…" when something was cut
+fn truncate_with_ellipsis(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Right-click menu of a tree node; records the chosen action
+fn tree_context_menu(ui: &mut egui::Ui, pid: u32, has_children: bool, danger: Color32, action: &mut Option<TreeAction>) {
+    let mut pick = |ui: &mut egui::Ui, clicked: bool, choice: TreeAction| {
+        if clicked {
+            *action = Some(choice);
+            ui.close_menu();
+        }
+    };
+    let target = format!("PID {}", pid);
+    let clicked = danger_button(ui, true, "Kill", danger, &signal_hint(true, &target)).clicked();
+    pick(ui, clicked, TreeAction::Kill);
+    let clicked = danger_button(ui, true, "Terminate", danger, &signal_hint(false, &target)).clicked();
+    pick(ui, clicked, TreeAction::Terminate);
+    let clicked = ui.button("Pause").clicked();
+    pick(ui, clicked, TreeAction::Pause);
+    let clicked = ui.button("Resume").clicked();
+    pick(ui, clicked, TreeAction::Resume);
+    if has_children {
+        ui.separator();
+        let hint = signal_hint(true, &format!("PID {} and all its descendants", pid));
+        let clicked = danger_button(ui, true, "Kill Subtree", danger, &hint).clicked();
+        pick(ui, clicked, TreeAction::KillSubtree);
+        let clicked = ui.button("Pause Subtree").clicked();
+        pick(ui, clicked, TreeAction::PauseSubtree);
+    }
+}
+
+/// Button for an action that ends processes, tinted with the danger color so it stands apart
+/// from benign ones. The hover text (see signal_hint) says exactly what will be sent where.
+fn danger_button(ui: &mut egui::Ui, enabled: bool, label: &str, danger: Color32, hint: &str) -> egui::Response {
+    ui.add_enabled(enabled, egui::Button::new(RichText::new(label).color(Color32::WHITE)).fill(danger))
+        .on_hover_text(hint)
+}
+
+/// Hover text of a destructive button: SIGKILL (force) or SIGTERM, sent to target (e.g. "PID 1234")
+fn signal_hint(force: bool, target: &str) -> String {
+    if force {
+        format!("Sends SIGKILL to {} — cannot be caught; it stops at once without cleaning up", target)
+    } else {
+        format!("Sends SIGTERM to {} — it may catch this to shut down cleanly, or ignore it", target)
+    }
+}
+
+/// Order every node's children like the table (hottest branches first when sorted by CPU),
+/// siblings that compare equal staying in PID order
+fn sort_tree(node: &mut ProcessNode, compare: &ProcessComparator) {
+    // The manager builds children in PID order and sort_by is stable
+    node.children.sort_by(|a, b| compare(&a.process, &b.process));
+    for child in &mut node.children {
+        sort_tree(child, compare);
+    }
+}
+
+/// One capability set as a grid row: "None", "All (41)" for a full set, or the names with the
+/// dangerous ones in warning color
+fn capability_row(ui: &mut egui::Ui, label: &str, set: &[String], dangerous: &[&str], warning: Color32) {
+    ui.label(label);
+    if set.is_empty() {
+        ui.label("None");
+    } else if Capabilities::is_full(set) {
+        let text = RichText::new(format!("All ({})", set.len()));
+        ui.label(if dangerous.is_empty() { text } else { text.color(warning) })
+            .on_hover_text(set.join("\n"));
+    } else {
+        ui.horizontal_wrapped(|ui| {
+            for cap in set {
+                if dangerous.contains(&cap.as_str()) {
+                    ui.colored_label(warning, cap);
+                } else {
+                    ui.label(cap);
+                }
+            }
+        });
+    }
+    ui.end_row();
+}
+
+/// Small PSI bar for the top bar showing the 10 s average, red once it passes alert_at
+fn pressure_gauge(ui: &mut egui::Ui, label: &str, averages: PressureAverages, alert_at: Option<f32>) {
+    let alerting = alert_at.is_some_and(|limit| averages.avg10 > limit);
+    let fill = if alerting { Color32::from_rgb(200, 40, 40) } else { Color32::from_rgb(60, 140, 90) };
+    ui.add(
+        egui::ProgressBar::new(averages.avg10 / 100.0)
+            .desired_width(60.0)
+            .fill(fill)
+            .text(RichText::new(format!("{} {:.1}%", label, averages.avg10)).small()),
+    )
+    .on_hover_text(format!(
+        "{} pressure: some task was stalled waiting {:.1}% of the last 10 s, {:.1}% of 60 s, {:.1}% of 5 min",
+        label, averages.avg10, averages.avg60, averages.avg300
+    ));
+}
+
+/// Plot a history of percentages, newest sample at x = 0 and older ones to the left
+fn history_plot(ui: &mut egui::Ui, id: &str, label: &str, samples: &VecDeque<f32>, interval_secs: f64, color: Color32) {
+    let newest = samples.len().saturating_sub(1);
+    let points: PlotPoints = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| [-((newest - i) as f64) * interval_secs, value as f64])
+        .collect();
+
+    ui.label(RichText::new(format!(
+        "{}: {}",
+        label,
+        samples.back().map(|v| format!("{:.1}", v)).unwrap_or_else(|| "-".to_string())
+    )).strong());
+    Plot::new(id)
+        .height(120.0)
+        .include_y(0.0)
+        .include_y(100.0)
+        .x_axis_label("Seconds ago")
+        .y_axis_label(label)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .show(ui, |plot_ui| plot_ui.line(Line::new(points).color(color).name(label)));
+}
@@ -0,0 +1,118 @@
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+
+use std::time::Duration;
+
+use super::app::{Column, DEFAULT_MESSAGE_TTL, DEFAULT_NAME_MAX_CHARS, DEFAULT_TREE_CMDLINE_WIDTH};
+use crate::manager::{monitoring, system};
+use crate::process::MemoryUnit;
+
+/// Storage key for the persisted GUI settings
+pub const CONFIG_KEY: &str = "lpm_config";
+
+/// GUI settings that persist between runs (stored by eframe)
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)] // Missing fields fall back to defaults so older configs still load
+pub struct AppConfig {
+    pub visible_columns: Vec<Column>,
+    pub refresh_in_background: bool,
+    pub system_history_len: usize,
+    pub max_rows: usize, // 0 = unlimited
+    pub smooth_cpu: bool,
+    pub normalize_cpu: bool,
+    pub state_glyphs: bool,
+    pub group_threads: bool,
+    pub message_ttl: Duration,
+    pub error_message_ttl: Option<Duration>, // None = errors stay until dismissed
+    pub cpu_smoothing_alpha: f32,
+    pub colors: HighlightColors,
+    pub protected_names: Vec<String>,
+    pub new_process_highlight_secs: u64, // 0 = off
+    pub memory_unit: MemoryUnit,
+    pub tree_show_cmdline: bool,
+    pub tree_cmdline_width: usize,
+    pub name_max_chars: usize,
+    pub favorites: Vec<String>, // Process names, in the order they were starred
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            visible_columns: Column::defaults(),
+            refresh_in_background: false,
+            system_history_len: system::DEFAULT_HISTORY_LEN,
+            max_rows: 0,
+            smooth_cpu: true,
+            normalize_cpu: false,
+            state_glyphs: true,
+            group_threads: true,
+            message_ttl: DEFAULT_MESSAGE_TTL,
+            error_message_ttl: None,
+            cpu_smoothing_alpha: monitoring::DEFAULT_CPU_SMOOTHING_ALPHA,
+            colors: HighlightColors::default(),
+            protected_names: Vec::new(),
+            new_process_highlight_secs: 5,
+            memory_unit: MemoryUnit::default(),
+            tree_show_cmdline: false,
+            tree_cmdline_width: DEFAULT_TREE_CMDLINE_WIDTH,
+            name_max_chars: DEFAULT_NAME_MAX_CHARS,
+            favorites: Vec::new(),
+        }
+    }
+}
+
+/// Colors used to highlight abnormal processes and process states in the table and tree view,
+/// plus the tint of buttons that kill or terminate processes
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighlightColors {
+    pub abnormal: Color32,
+    pub growing: Color32,
+    pub stale_binary: Color32,
+    pub new_process: Color32,
+    pub cpu_over_threshold: Color32,
+    pub memory_over_threshold: Color32,
+    pub running: Color32,
+    pub sleeping: Color32,
+    pub disk_sleep: Color32,
+    pub zombie: Color32,
+    pub stopped: Color32,
+    pub other_state: Color32,
+    pub cpu_spike: Color32,
+    pub danger: Color32,
+}
+
+impl HighlightColors {
+    /// Color for a process state character (R, S, D, Z, T, ...)
+    pub fn state(&self, state: char) -> Color32 {
+        match state {
+            'R' => self.running,
+            'S' => self.sleeping,
+            'D' => self.disk_sleep,
+            'Z' => self.zombie,
+            'T' => self.stopped,
+            _ => self.other_state,
+        }
+    }
+}
+
+impl Default for HighlightColors {
+    fn default() -> Self {
+        Self {
+            abnormal: Color32::YELLOW,
+            growing: Color32::from_rgb(255, 105, 180),
+            stale_binary: Color32::from_rgb(255, 140, 0),
+            new_process: Color32::from_rgb(0, 90, 40),
+            cpu_over_threshold: Color32::RED,
+            memory_over_threshold: Color32::RED,
+            running: Color32::GREEN,
+            sleeping: Color32::BLUE,
+            disk_sleep: Color32::RED,
+            zombie: Color32::YELLOW,
+            stopped: Color32::GRAY,
+            other_state: Color32::WHITE,
+            cpu_spike: Color32::from_rgb(255, 120, 0),
+            danger: Color32::from_rgb(150, 30, 30),
+        }
+    }
+}
@@ -1,4 +1,9 @@
 mod app;
+mod config;
+mod options;
+pub mod sorting;
+mod startup;
 
 pub use app::ProcessManagerApp;
-
+pub use startup::GuiApp;
+pub use options::{LaunchOptions, USAGE};
@@ -0,0 +1,242 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use super::sorting::SortColumn;
+use crate::manager::batch;
+use crate::manager::command::{self, Command};
+
+/// Usage text printed for `--help` or when the arguments can't be parsed
+pub const USAGE: &str = "\
+Usage: lpm_backend [OPTIONS]
+
+Options:
+  --audit <file>     Append a record of every kill/signal/renice to <file>
+  --dry-run          Log operations instead of performing them
+  --read-only        Disable every operation (kill, renice, create, ...), even for root
+  --serve <port>     Serve a read-only JSON API on <port> instead of opening the GUI
+  --serve-addr <ip>  Address for --serve to listen on (default 127.0.0.1; 0.0.0.0 for every interface)
+  --protect <name>   Refuse to kill/terminate/pause processes named <name> (repeatable)
+  --format compact   Print one line per process (PID NAME STATE CPU% RSS_MB) and exit
+  --new <secs>       Like --format compact, but only processes started in the last <secs> seconds
+  --sort <column>    Order --format compact/--new by cputime, cpu or mem (largest first) or pid
+  --fuzzy <pattern>  Like --format compact, but only names fuzzily matching <pattern>, best first
+  --snapshot <file>  Save the current process list to <file> as JSON and exit
+  --dot <file>       Write the process tree to <file> as Graphviz DOT and exit
+  --diff <a> <b>     Print what changed between two snapshot files and exit
+  --monitor <pid>    Print CPU%, RSS and state of <pid> every 2 seconds until it exits
+  --describe <pid>   Print everything readable from /proc about <pid> and exit
+  --lsof <path>      Print the PIDs of processes with <path> open and exit
+  --port <n>         Print the PIDs of processes listening on TCP/UDP port <n> and exit
+  --kill <pids>      Kill the listed processes (e.g. 1200,1300-1310) and exit
+  --terminate <pids> Send SIGTERM to the listed processes and exit
+  --pause <pids>     Stop (SIGSTOP) the listed processes and exit
+  --resume <pids>    Continue (SIGCONT) the listed processes and exit
+  --kill-oldest <name>
+                     Kill the longest-running process named <name> and exit
+  --kill-newest <name>
+                     Kill the most recently started process named <name> and exit
+  --nice-user <uid> <n>
+                     Set the nice value of every process of <uid> to <n> (-20..19) and exit
+  --rules <file>     Apply the idle rules in <file> every 2 seconds until interrupted
+                     (with --dry-run, only report what would be done)
+  -v, --verbose      Log debug messages to stderr (otherwise only warnings; RUST_LOG overrides)
+  -h, --help         Print this help";
+
+/// Settings passed on the command line when launching the GUI (or the --serve API)
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub audit_log: Option<PathBuf>,
+    pub dry_run: bool,
+    pub read_only: bool,
+    pub serve_port: Option<u16>,
+    pub serve_addr: Option<IpAddr>, // None: api::DEFAULT_SERVE_ADDR
+    pub protect_names: Vec<String>,
+    pub print_compact: bool,
+    pub new_within_secs: Option<u64>,
+    pub sort: Option<SortColumn>, // Order of --format compact/--new instead of their default
+    pub fuzzy_pattern: Option<String>,
+    pub snapshot_path: Option<PathBuf>,
+    pub dot_path: Option<PathBuf>,
+    pub diff_paths: Option<(PathBuf, PathBuf)>,
+    pub monitor_pid: Option<u32>,
+    pub describe_pid: Option<u32>,
+    pub lsof_path: Option<String>,
+    pub listening_port: Option<u16>,
+    pub rules_path: Option<PathBuf>,
+    pub command: Option<Command>, // A single operation to run instead of opening the GUI
+    pub verbose: bool,
+    pub show_help: bool,
+}
+
+impl LaunchOptions {
+    /// Parse command-line arguments (excluding the program name)
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = LaunchOptions::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--audit" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| "--audit requires a file path".to_string())?;
+                    options.audit_log = Some(PathBuf::from(path));
+                }
+                "--dry-run" => options.dry_run = true,
+                "--read-only" => options.read_only = true,
+                "--serve" => {
+                    let port = args
+                        .next()
+                        .ok_or_else(|| "--serve requires a port".to_string())?;
+                    let port = port
+                        .parse::<u16>()
+                        .map_err(|_| format!("Invalid port: {}", port))?;
+                    options.serve_port = Some(port);
+                }
+                "--serve-addr" => {
+                    let addr = args
+                        .next()
+                        .ok_or_else(|| "--serve-addr requires an IP address".to_string())?;
+                    let addr = addr
+                        .parse::<IpAddr>()
+                        .map_err(|_| format!("Invalid IP address: {}", addr))?;
+                    options.serve_addr = Some(addr);
+                }
+                "--protect" => {
+                    let name = args
+                        .next()
+                        .ok_or_else(|| "--protect requires a process name".to_string())?;
+                    options.protect_names.push(name);
+                }
+                "--format" => {
+                    let format = args
+                        .next()
+                        .ok_or_else(|| "--format requires a format name".to_string())?;
+                    if format != "compact" {
+                        return Err(format!("Unknown format: {} (supported: compact)", format));
+                    }
+                    options.print_compact = true;
+                }
+                "--new" => {
+                    let secs = args
+                        .next()
+                        .ok_or_else(|| "--new requires a number of seconds".to_string())?;
+                    let secs = secs
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid number of seconds: {}", secs))?;
+                    options.new_within_secs = Some(secs);
+                }
+                "--sort" => {
+                    let column = args
+                        .next()
+                        .ok_or_else(|| "--sort requires a column".to_string())?;
+                    options.sort = Some(match column.as_str() {
+                        "cputime" => SortColumn::CpuTime,
+                        "cpu" => SortColumn::Cpu,
+                        "mem" => SortColumn::Memory,
+                        "pid" => SortColumn::Pid,
+                        _ => return Err(format!("Unknown sort column: {} (supported: cputime, cpu, mem, pid)", column)),
+                    });
+                }
+                "--fuzzy" => {
+                    let pattern = args
+                        .next()
+                        .ok_or_else(|| "--fuzzy requires a pattern".to_string())?;
+                    options.fuzzy_pattern = Some(pattern);
+                }
+                "--snapshot" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| "--snapshot requires a file path".to_string())?;
+                    options.snapshot_path = Some(PathBuf::from(path));
+                }
+                "--dot" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| "--dot requires a file path".to_string())?;
+                    options.dot_path = Some(PathBuf::from(path));
+                }
+                "--diff" => {
+                    let (Some(a), Some(b)) = (args.next(), args.next()) else {
+                        return Err("--diff requires two snapshot files".to_string());
+                    };
+                    options.diff_paths = Some((PathBuf::from(a), PathBuf::from(b)));
+                }
+                "--monitor" => {
+                    let pid = args
+                        .next()
+                        .ok_or_else(|| "--monitor requires a PID".to_string())?;
+                    match batch::parse_pid_list(&pid)?.as_slice() {
+                        &[pid] => options.monitor_pid = Some(pid),
+                        _ => return Err(format!("--monitor takes a single PID, not {}", pid)),
+                    }
+                }
+                "--describe" => {
+                    let pid = args
+                        .next()
+                        .ok_or_else(|| "--describe requires a PID".to_string())?;
+                    let pid = pid
+                        .parse::<u32>()
+                        .map_err(|_| format!("Invalid PID: {}", pid))?;
+                    options.describe_pid = Some(pid);
+                }
+                "--lsof" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| "--lsof requires a file path".to_string())?;
+                    options.lsof_path = Some(path);
+                }
+                "--port" => {
+                    let port = args
+                        .next()
+                        .ok_or_else(|| "--port requires a port".to_string())?;
+                    let port = port
+                        .parse::<u16>()
+                        .map_err(|_| format!("Invalid port: {}", port))?;
+                    options.listening_port = Some(port);
+                }
+                "--kill" | "--terminate" | "--pause" | "--resume" => {
+                    let list = args
+                        .next()
+                        .ok_or_else(|| format!("{} requires a list of PIDs", arg))?;
+                    let pids = batch::parse_pid_list(&list)?;
+                    options.command = Some(match arg.as_str() {
+                        "--kill" => Command::Kill(pids),
+                        "--terminate" => Command::Terminate(pids),
+                        "--pause" => Command::Pause(pids),
+                        _ => Command::Resume(pids),
+                    });
+                }
+                "--kill-oldest" | "--kill-newest" => {
+                    let name = args
+                        .next()
+                        .ok_or_else(|| format!("{} requires a process name", arg))?;
+                    options.command = Some(match arg.as_str() {
+                        "--kill-oldest" => Command::KillOldest(name),
+                        _ => Command::KillNewest(name),
+                    });
+                }
+                "--nice-user" => {
+                    let (Some(uid), Some(value)) = (args.next(), args.next()) else {
+                        return Err("--nice-user requires a UID and a nice value".to_string());
+                    };
+                    let uid = uid
+                        .parse::<u32>()
+                        .map_err(|_| format!("Invalid UID: {}", uid))?;
+                    options.command = Some(Command::NiceUser { uid, value: command::parse_nice(&value)? });
+                }
+                "--rules" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| "--rules requires a file path".to_string())?;
+                    options.rules_path = Some(PathBuf::from(path));
+                }
+                "-v" | "--verbose" => options.verbose = true,
+                "-h" | "--help" => options.show_help = true,
+                other => return Err(format!("Unknown argument: {}", other)),
+            }
+        }
+
+        Ok(options)
+    }
+}
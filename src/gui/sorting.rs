@@ -0,0 +1,79 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::process::Process;
+
+/// Orders two processes for the table and tree. Boxed so orderings can be built from settings
+/// and combined at runtime instead of being limited to the columns.
+pub type ProcessComparator = Box<dyn Fn(&Process, &Process) -> Ordering>;
+
+/// Column the table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortColumn {
+    Pid,
+    Name,
+    Uid,
+    State,
+    Cpu,
+    CpuTime,
+    Memory,
+    Priority,
+    Timer,
+    LastCpu,
+}
+
+/// Which CPU% figure is shown (and therefore sorted on)
+#[derive(Clone, Copy)]
+pub struct CpuDisplay {
+    pub smooth: bool,              // Moving average instead of the raw per-refresh value
+    pub per_machine: Option<f32>,  // Core count to divide by for a 0-100 whole-machine figure
+}
+
+impl CpuDisplay {
+    /// The raw per-refresh figure, per core, as in the compact listing
+    pub const RAW: CpuDisplay = CpuDisplay { smooth: false, per_machine: None };
+
+    pub fn percent(self, process: &Process) -> f32 {
+        let percent = if self.smooth {
+            process.cpu_percent_smoothed()
+        } else {
+            process.pcb_data.cpu_percent
+        };
+        match self.per_machine {
+            Some(cores) => percent / cores,
+            None => percent,
+        }
+    }
+}
+
+/// Built-in ordering by a table column
+pub fn by_column(column: SortColumn, ascending: bool, cpu: CpuDisplay) -> ProcessComparator {
+    Box::new(move |a, b| {
+        let cmp = match column {
+            SortColumn::Pid => a.process_id.cmp(&b.process_id),
+            SortColumn::Name => a.name.cmp(&b.name),
+            SortColumn::Uid => a.user_id.cmp(&b.user_id),
+            SortColumn::State => a.pcb_data.state.cmp(&b.pcb_data.state),
+            SortColumn::Cpu => cpu.percent(a).partial_cmp(&cpu.percent(b)).unwrap_or(Ordering::Equal),
+            SortColumn::CpuTime => a
+                .cpu_time_seconds()
+                .partial_cmp(&b.cpu_time_seconds())
+                .unwrap_or(Ordering::Equal),
+            SortColumn::Memory => a.pcb_data.memory_rss_kb.cmp(&b.pcb_data.memory_rss_kb),
+            SortColumn::Priority => a.pcb_data.priority.cmp(&b.pcb_data.priority),
+            SortColumn::Timer => a.pcb_data.uptime_seconds.cmp(&b.pcb_data.uptime_seconds),
+            SortColumn::LastCpu => a.pcb_data.last_cpu.cmp(&b.pcb_data.last_cpu),
+        };
+        if ascending { cmp } else { cmp.reverse() }
+    })
+}
+
+/// Built-in that puts the given PIDs (zombies, threshold-breakers, ...) first, whatever the direction
+pub fn pids_first(pids: HashSet<u32>) -> ProcessComparator {
+    Box::new(move |a, b| pids.contains(&b.process_id).cmp(&pids.contains(&a.process_id)))
+}
+
+/// Composite ordering: first decides, then breaks its ties (e.g. abnormal first, then by CPU)
+pub fn then(first: ProcessComparator, then: ProcessComparator) -> ProcessComparator {
+    Box::new(move |a, b| first(a, b).then_with(|| then(a, b)))
+}
@@ -0,0 +1,79 @@
+use egui::{Color32, RichText};
+
+use crate::manager::ManagerError;
+
+use super::app::ProcessManagerApp;
+use super::config::{AppConfig, CONFIG_KEY};
+use super::options::LaunchOptions;
+
+/// Top-level GUI state: the process manager once the process list could be read,
+/// or an error screen with a Retry button until it can
+pub enum GuiApp {
+    Running(Box<ProcessManagerApp>),
+    Failed(Box<StartupFailure>),
+}
+
+/// Why the manager couldn't start, plus what is needed to try again
+pub struct StartupFailure {
+    error: ManagerError,
+    // Kept so a successful retry starts exactly as the first attempt would have
+    config: Option<AppConfig>,
+    options: LaunchOptions,
+}
+
+impl GuiApp {
+    pub fn new(cc: &eframe::CreationContext<'_>, options: LaunchOptions) -> Self {
+        // Restore settings saved by a previous run
+        let config = cc.storage.and_then(|storage| eframe::get_value::<AppConfig>(storage, CONFIG_KEY));
+        Self::start(config, options)
+    }
+
+    fn start(config: Option<AppConfig>, options: LaunchOptions) -> Self {
+        match ProcessManagerApp::new(config.clone(), options.clone()) {
+            Ok(app) => GuiApp::Running(Box::new(app)),
+            Err(error) => {
+                log::error!("Failed to initialize manager: {}", error);
+                GuiApp::Failed(Box::new(StartupFailure { error, config, options }))
+            }
+        }
+    }
+}
+
+impl eframe::App for GuiApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        // Nothing was changed while the error screen was up, so the stored settings stay as they are
+        if let GuiApp::Running(app) = self {
+            app.save(storage);
+        }
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let failure = match self {
+            GuiApp::Running(app) => return app.update(ctx, frame),
+            GuiApp::Failed(failure) => failure,
+        };
+
+        let mut retry = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 3.0);
+                ui.heading(RichText::new("⚠ Failed to read /proc").color(Color32::RED));
+                ui.add_space(8.0);
+                ui.label(failure.error.to_string());
+                ui.add_space(4.0);
+                ui.add(egui::Label::new(RichText::new(failure.error.guidance()).color(Color32::GRAY)).wrap(true));
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    retry = ui.button("Retry").clicked();
+                    if ui.button("Quit").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+            });
+        });
+
+        if retry {
+            *self = GuiApp::start(failure.config.take(), failure.options.clone());
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod process;
+pub mod user;
+pub mod manager;
+pub mod gui;
+pub mod api;
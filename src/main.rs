@@ -1,11 +1,153 @@
-mod process; 
-mod user;   
-mod manager;
-mod gui;
+use std::collections::HashSet;
+use std::time::Duration;
 
-use gui::ProcessManagerApp;
+use lpm_backend::api;
+use lpm_backend::gui::sorting::{self, CpuDisplay, SortColumn};
+use lpm_backend::gui::{GuiApp, LaunchOptions, USAGE};
+use lpm_backend::manager::snapshot::Snapshot;
+use lpm_backend::manager::{command, export, monitoring, query, rules, Manager};
+use lpm_backend::user::{self, Privilege, User};
 
 fn main() -> eframe::Result<()> {
+    // Parse command-line options before starting the GUI
+    let launch_options = match LaunchOptions::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    init_logging(launch_options.verbose);
+    if launch_options.show_help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    // Compare two saved snapshots
+    if let Some((a, b)) = &launch_options.diff_paths {
+        let diff = Snapshot::load(a)
+            .and_then(|a| Snapshot::load(b).map(|b| Manager::diff_snapshots(&a, &b)));
+        match diff {
+            Ok(diff) => print!("{}", diff.report()),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Save a snapshot for a later --diff
+    if let Some(path) = &launch_options.snapshot_path {
+        if let Err(e) = sampled_manager().and_then(|manager| manager.snapshot().save(path)) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Write the process tree as Graphviz DOT (render with `dot -Tsvg`)
+    if let Some(path) = &launch_options.dot_path {
+        let result = sampled_manager().and_then(|manager| {
+            std::fs::write(path, manager.tree_to_dot())
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+        });
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Single-shot mode: print the process list for scripts and exit
+    if launch_options.print_compact || launch_options.new_within_secs.is_some() {
+        let within = launch_options.new_within_secs.map(Duration::from_secs);
+        if let Err(e) = print_compact(within, launch_options.sort) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Same listing, filtered and ranked by how well names match a possibly mistyped pattern
+    if let Some(pattern) = &launch_options.fuzzy_pattern {
+        if let Err(e) = print_fuzzy(pattern) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Watch a single process from the terminal
+    if let Some(pid) = launch_options.monitor_pid {
+        if let Err(e) = monitor_pid(pid, Duration::from_secs(2)) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Full report of a single process
+    if let Some(pid) = launch_options.describe_pid {
+        let report = sampled_manager().and_then(|manager| {
+            manager
+                .find_by_pid(pid)
+                .map(|process| process.full_report())
+                .ok_or_else(|| format!("No process with PID {}", pid))
+        });
+        match report {
+            Ok(report) => print!("{}", report),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Reverse lookups: which processes have a file open, or listen on a port
+    if let Some(path) = &launch_options.lsof_path {
+        print_pids(current_manager().map(|manager| manager.processes_with_open_file(path)));
+        return Ok(());
+    }
+    if let Some(port) = launch_options.listening_port {
+        print_pids(current_manager().map(|manager| manager.processes_listening_on(port)));
+        return Ok(());
+    }
+
+    // Run one operation (e.g. --kill-oldest) and exit
+    if let Some(command) = &launch_options.command {
+        match operating_manager(&launch_options).and_then(|manager| command::execute(&manager, command)) {
+            Ok(message) if launch_options.dry_run => println!("[dry run] {}", message),
+            Ok(message) => println!("{}", message),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Apply idle rules from a file until interrupted
+    if let Some(path) = &launch_options.rules_path {
+        if let Err(e) = run_rules(path, &launch_options, Duration::from_secs(2)) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Headless mode: serve the JSON API instead of opening a window
+    if let Some(port) = launch_options.serve_port {
+        let addr = launch_options.serve_addr.unwrap_or(api::DEFAULT_SERVE_ADDR);
+        println!("Serving process API on http://{}/ (GET /processes, /processes/{{pid}}, /system)", std::net::SocketAddr::new(addr, port));
+        if let Err(e) = api::serve(addr, port, Duration::from_secs(2)) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Configure native options for the GUI
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -18,6 +160,173 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Linux Process Manager",
         options,
-        Box::new(|cc| Box::new(ProcessManagerApp::new(cc))),
+        Box::new(|cc| Box::new(GuiApp::new(cc, launch_options))),
     )
 }
+
+/// Print one line per process (only those started within `within`, newest first, if given),
+/// with CPU% measured over a short sampling interval. `sort` replaces the default order: PID
+/// ascending, anything else largest first, ties by PID. Totals go to stderr, so pipes only see
+/// the process lines.
+fn print_compact(within: Option<Duration>, sort: Option<SortColumn>) -> Result<(), String> {
+    let manager = sampled_manager()?;
+    let mut processes = match within {
+        Some(within) => manager.recently_started(within),
+        None => {
+            let mut processes: Vec<_> = manager.iter().collect();
+            processes.sort_by_key(|p| p.process_id);
+            processes
+        }
+    };
+    if let Some(column) = sort {
+        let order = sorting::then(
+            sorting::by_column(column, column == SortColumn::Pid, CpuDisplay::RAW),
+            sorting::by_column(SortColumn::Pid, true, CpuDisplay::RAW),
+        );
+        processes.sort_by(|a, b| order(a, b));
+    }
+    print!("{}", export::processes_to_compact(processes.iter().copied()));
+    let summary = monitoring::summarize(processes.into_iter(), 0);
+    eprintln!(
+        "{} processes, {:.1} MB RSS in total, {:.1}% CPU on average",
+        summary.total,
+        summary.total_rss_kb as f64 / 1024.0,
+        summary.average_cpu_percent
+    );
+    Ok(())
+}
+
+/// Print the compact line of every process whose name fuzzily matches pattern, best match first
+/// (ties by PID)
+fn print_fuzzy(pattern: &str) -> Result<(), String> {
+    let manager = sampled_manager()?;
+    let mut scored: Vec<_> = manager
+        .iter()
+        .filter_map(|p| query::fuzzy_score(pattern, &p.name).map(|score| (score, p)))
+        .collect();
+    scored.sort_by_key(|&(score, p)| (std::cmp::Reverse(score), p.process_id));
+    print!("{}", export::processes_to_compact(scored.into_iter().map(|(_, p)| p)));
+    Ok(())
+}
+
+/// Print one PID per line, or the error (exiting with status 1)
+fn print_pids(pids: Result<Vec<u32>, String>) {
+    match pids {
+        Ok(pids) => pids.iter().for_each(|pid| println!("{}", pid)),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Print one line per interval with pid's CPU%, RSS and state, re-reading only that process,
+/// and stop when it exits (saying why, if it's still a zombie whose exit code can be read)
+fn monitor_pid(pid: u32, interval: Duration) -> Result<(), String> {
+    let mut manager = current_manager()?;
+    let name = manager
+        .find_by_pid(pid)
+        .map(|p| p.name.clone())
+        .ok_or_else(|| format!("No process with PID {}", pid))?;
+    println!("Monitoring {} (PID {}), Ctrl+C to stop", name, pid);
+
+    loop {
+        std::thread::sleep(interval);
+        let Some(process) = manager.refresh_pid(pid)? else {
+            println!("PID {} exited (already reaped, exit status unknown)", pid);
+            return Ok(());
+        };
+        // A zombie has finished running; its parent just hasn't collected it yet
+        if process.pcb_data.state == 'Z' {
+            let reason = monitoring::exit_reason(pid).unwrap_or_else(|| "exit status unknown".to_string());
+            println!("PID {} exited ({})", pid, reason);
+            return Ok(());
+        }
+        println!(
+            "CPU {:5.1}%  RSS {:8.1} MB  state {}",
+            process.pcb_data.cpu_percent,
+            process.rss_mb(),
+            process.pcb_data.state
+        );
+    }
+}
+
+/// A manager that may perform operations from the command line. It is an admin manager so the
+/// operations layer allows signals (the kernel still decides which processes we may signal);
+/// --read-only, --dry-run, --protect and --audit apply as in the GUI.
+fn operating_manager(options: &LaunchOptions) -> Result<Manager, String> {
+    let uid = nix::unistd::getuid().as_raw();
+    let name = user::name_for_uid(uid).unwrap_or_else(|| uid.to_string());
+    let mut manager = Manager::new(User::new(uid, &name, Privilege::Admin))?;
+    manager.dry_run = options.dry_run;
+    manager.read_only = options.read_only;
+    manager.protected_names.extend(options.protect_names.iter().cloned());
+    if let Some(audit_log) = &options.audit_log {
+        manager.enable_audit(audit_log.clone())?;
+    }
+    Ok(manager)
+}
+
+/// Every interval, refresh and apply whatever the rules in path call for, printing each action
+fn run_rules(path: &std::path::Path, options: &LaunchOptions, interval: Duration) -> Result<(), String> {
+    let rules = rules::load_rules(path)?;
+    if rules.is_empty() {
+        return Err(format!("No rules in {}", path.display()));
+    }
+    let mut manager = operating_manager(options)?;
+    let mode = if options.read_only {
+        " (read-only: every action will be refused)"
+    } else if options.dry_run {
+        " (dry run)"
+    } else {
+        ""
+    };
+    println!("Applying {} rule(s) from {}{}, Ctrl+C to stop", rules.len(), path.display(), mode);
+
+    // Each process is acted on once: a paused process stays idle, and a terminated one may take
+    // a few refreshes to exit
+    let mut acted: HashSet<u32> = HashSet::new();
+    loop {
+        std::thread::sleep(interval);
+        manager.refresh()?;
+        acted.retain(|pid| manager.processes.contains_key(pid));
+        for (pid, action) in manager.evaluate_rules(&rules) {
+            if !acted.insert(pid) {
+                continue;
+            }
+            let name = manager.find_by_pid(pid).map(|p| p.name.clone()).unwrap_or_default();
+            match action.apply(&manager, pid) {
+                // The operations layer only logs dry runs; report them like real actions
+                Ok(()) if manager.dry_run => println!("[dry run] would {} PID {} ({})", action.label(), pid, name),
+                Ok(()) => println!("{} PID {} ({})", action.past_tense(), pid, name),
+                Err(e) => eprintln!("Rule not applied to PID {} ({}): {}", pid, name, e),
+            }
+        }
+        if let Some(e) = manager.take_audit_error() {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// Send log records to stderr: warnings by default, this crate's debug messages with --verbose.
+/// RUST_LOG, when set, decides the level instead.
+fn init_logging(verbose: bool) {
+    let default_filter = if verbose { "warn,lpm_backend=debug" } else { "warn" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter)).init();
+}
+
+/// A read-only manager for the invoking user, with a single sample of the processes
+fn current_manager() -> Result<Manager, String> {
+    let uid = nix::unistd::getuid().as_raw();
+    let name = user::name_for_uid(uid).unwrap_or_else(|| uid.to_string());
+    Ok(Manager::new(User::new(uid, &name, Privilege::Normal))?)
+}
+
+/// A read-only manager for the invoking user, refreshed twice so CPU% is meaningful
+fn sampled_manager() -> Result<Manager, String> {
+    let mut manager = current_manager()?;
+    // CPU% is a delta between two samples, so take a second one after a moment
+    std::thread::sleep(Duration::from_millis(500));
+    manager.refresh()?;
+    Ok(manager)
+}
@@ -1,14 +1,71 @@
-use std::collections::HashMap;
-use std::time::Instant;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::{Child, ExitStatus};
+use std::time::{Duration, Instant};
 use crate::process::Process;
 use crate::user::User;
+use system::SystemHistory;
+use escalation::EscalationMethod;
+use monitoring::RefreshDelta;
 
+pub mod audit;
 pub mod batch;
 pub mod monitoring;
 pub mod operations;
 pub mod permissions;
+pub mod query;
+pub mod system;
 pub mod creation;
+pub mod escalation;
+pub mod export;
+pub mod growth;
+pub mod snapshot;
+pub mod rate_limit;
+pub mod command;
+pub mod lookup;
+pub mod rules;
 
+//Time spent suspended between two refreshes beyond which the CPU baselines are thrown away
+pub const SUSPEND_GAP_THRESHOLD: Duration = Duration::from_secs(2);
+
+//How often wait_for_exit checks /proc by default
+pub const DEFAULT_EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+//Why a Manager could not be created
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManagerError {
+    //No usable procfs: /proc missing, not mounted, unreadable, or no process in it could be read
+    ProcUnavailable(String),
+}
+
+impl ManagerError {
+    //What the user can do about it, shown under the error by both frontends
+    pub fn guidance(&self) -> &'static str {
+        match self {
+            ManagerError::ProcUnavailable(_) => {
+                "This tool reads every process from the proc filesystem and only runs on Linux. \
+                 If /proc is not mounted, mount it with \"mount -t proc proc /proc\"; inside a container \
+                 or sandbox, start it with /proc mounted and readable."
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ManagerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManagerError::ProcUnavailable(reason) => write!(f, "/proc is unavailable: {}", reason),
+        }
+    }
+}
+
+//The headless modes report errors as strings; keep the guidance with them
+impl From<ManagerError> for String {
+    fn from(error: ManagerError) -> Self {
+        format!("{}\n{}", error, error.guidance())
+    }
+}
 
 #[derive(Debug)] //Allows an instance of the Manager struct to be formatted for debugging output in a human-readable way.
 
@@ -17,39 +74,413 @@ pub struct Manager {
     pub processes: HashMap<u32, Process>, 
     pub active_user: User, 
     pub root_pid: u32,
-    // Track previous CPU times for CPU percentage calculation
-    // HashMap<pid, (cpu_time_jiffies, timestamp)>
-    pub(crate) previous_cpu_times: HashMap<u32, (u64, Instant)>,
+    // Track previous CPU times (and other counters) for CPU percentage and rate calculation
+    pub(crate) previous_cpu_times: HashMap<u32, monitoring::PreviousSample>,
+    pub(crate) previous_total_jiffies: Option<u64>, // System-wide CPU jiffies at the last refresh
+    // Time spent suspended (boot clock minus monotonic clock) at the last refresh, to notice a suspend/resume
+    pub(crate) suspended_at_last_refresh: Option<Duration>,
+    // Optional file that records every successful privileged operation
+    pub audit_log: Option<PathBuf>,
+    // Whether the last audit write failed, and the failure waiting to be reported
+    pub(crate) audit_failing: Cell<bool>,
+    pub(crate) audit_error: RefCell<Option<String>>,
+    // Recent system-wide CPU% and memory% samples (one per refresh)
+    pub system_history: SystemHistory,
+    // Opt-in: retry operations that fail with EPERM through sudo/pkexec
+    pub privilege_escalation: Option<EscalationMethod>,
+    // Description of the last escalation attempt, waiting to be shown to the user
+    pub(crate) escalation_note: RefCell<Option<String>>,
+    // When set, operations only log what they would do instead of sending signals
+    pub dry_run: bool,
+    // When set, every operation is refused whatever the active user's privilege ("monitor only")
+    pub read_only: bool,
+    // Weight of the newest sample in the smoothed CPU% (1.0 = no smoothing)
+    pub cpu_smoothing_alpha: f32,
+    // Processes that kill/terminate/pause refuse to touch unless force_protected is set
+    pub protected_pids: HashSet<u32>,
+    pub protected_names: HashSet<String>,
+    pub force_protected: bool,
+    // Children we spawned in the background, kept so their exit status can be collected
+    pub(crate) spawned: RefCell<HashMap<u32, Child>>,
+    // Exit statuses of those children, collected at refresh so they don't linger as zombies
+    pub(crate) spawned_exits: HashMap<u32, ExitStatus>,
+    // Consecutive refreshes each PID has spent in uninterruptible sleep (D), for spotting hung I/O
+    pub d_state_streaks: HashMap<u32, u32>,
+    // Storage bytes read/written per PID since this tool first saw it
+    pub(crate) session_io: HashMap<u32, monitoring::SessionIo>,
+    // Recent RSS/FD/thread counts per PID, for leak detection
+    pub growth: growth::GrowthTracker,
+    // How long each PID has stayed under any CPU%, for the idle rules (see evaluate_rules)
+    pub idle: rules::IdleTracker,
+    // Processes first seen in the last refresh, counted per name (for spotting fork bombs and crash loops)
+    pub last_spawn_counts: HashMap<String, usize>,
+    // One entry per process (threads folded into it, as the kernel already sums their CPU time),
+    // or every thread as its own entry; change with set_group_threads
+    pub group_threads: bool,
+    // Caps kill/terminate/pause/signal calls per second (set enabled = false to turn off)
+    pub rate_limiter: RefCell<rate_limit::RateLimiter>,
+    // Why some processes may be missing from the list (e.g. /proc mounted with hidepid), checked once at startup
+    pub proc_restriction: Option<String>,
+}
+
+/// How a process waited on with `Manager::wait_spawned` ended
+#[derive(Debug)]
+pub enum SpawnedExit {
+    // We spawned it, so the real exit status is known
+    Status(ExitStatus),
+    // Not our child: we only saw it disappear from /proc, the exit code is unknown
+    ExitedUnknown,
 }
 
 impl Manager {
-    pub fn new(active_user: User) -> Result<Self, String> {  //Constructor
+    pub fn new(active_user: User) -> Result<Self, ManagerError> {  //Constructor
+        monitoring::check_proc_available().map_err(ManagerError::ProcUnavailable)?;
+
         //Initialize the struct with default state
         let mut manager = Manager {
             processes: HashMap::new(), // Start with an empty map
             active_user,
             root_pid: 1,
             previous_cpu_times: HashMap::new(),
+            previous_total_jiffies: None,
+            suspended_at_last_refresh: monitoring::time_suspended(),
+            audit_log: None,
+            audit_failing: Cell::new(false),
+            audit_error: RefCell::new(None),
+            system_history: SystemHistory::new(system::DEFAULT_HISTORY_LEN),
+            privilege_escalation: None,
+            escalation_note: RefCell::new(None),
+            dry_run: false,
+            read_only: false,
+            cpu_smoothing_alpha: monitoring::DEFAULT_CPU_SMOOTHING_ALPHA,
+            protected_pids: permissions::default_protected_pids(),
+            protected_names: HashSet::new(),
+            force_protected: false,
+            spawned: RefCell::new(HashMap::new()),
+            spawned_exits: HashMap::new(),
+            d_state_streaks: HashMap::new(),
+            session_io: HashMap::new(),
+            growth: growth::GrowthTracker::new(growth::DEFAULT_GROWTH_WINDOW),
+            idle: rules::IdleTracker::default(),
+            last_spawn_counts: HashMap::new(),
+            group_threads: true,
+            rate_limiter: RefCell::new(rate_limit::RateLimiter::default()),
+            proc_restriction: monitoring::proc_restriction(),
         };
         
+        //Baseline for system CPU%; a failure here just leaves the graphs empty
+        let _ = manager.system_history.sample();
+        
         //Initial snapshot at initialization
-        match monitoring::refresh_processes(&mut manager.processes, &mut manager.previous_cpu_times) {
-            Ok(_) => Ok(manager),
-            Err(e) => Err(format!("Failed initial process load: {}", e)),
+        match monitoring::refresh_processes(&mut manager.processes, &mut manager.previous_cpu_times, &mut manager.previous_total_jiffies, manager.cpu_smoothing_alpha) {
+            //Listing worked but every read failed: nothing useful to show
+            Ok(_) if manager.processes.is_empty() => Err(ManagerError::ProcUnavailable(
+                "no process in /proc could be read".to_string(),
+            )),
+            Ok(_) => {
+                if let Some(restriction) = &manager.proc_restriction {
+                    log::warn!("{}", restriction);
+                }
+                monitoring::update_d_state_streaks(&manager.processes, &mut manager.d_state_streaks);
+                monitoring::update_session_io(&manager.processes, &mut manager.session_io);
+                manager.growth.sample(&manager.processes);
+                manager.idle.sample(&manager.processes, Instant::now());
+                Ok(manager)
+            }
+            Err(e) => Err(ManagerError::ProcUnavailable(format!("failed initial process load: {}", e))),
         }
     }
 
-    //Deals with live data from Linux system
-   pub fn refresh(&mut self) -> Result<(), String> {
-    monitoring::refresh_processes(&mut self.processes, &mut self.previous_cpu_times).map(|_| ())
+    //Deals with live data from Linux system; returns what changed since the last refresh
+   pub fn refresh(&mut self) -> Result<RefreshDelta, String> {
+    self.reap_spawned();
+    //Counters from before a suspend or VM pause give one refresh of nonsense rates; start over instead
+    let suspended = monitoring::time_suspended();
+    if let (Some(now), Some(before)) = (suspended, self.suspended_at_last_refresh)
+        && now.saturating_sub(before) > SUSPEND_GAP_THRESHOLD
+    {
+        log::info!("System was suspended for {:.0}s since the last refresh; resetting CPU baselines", (now - before).as_secs_f64());
+        self.reset_cpu_baseline();
+    }
+    self.suspended_at_last_refresh = suspended;
+    let source = monitoring::ProcSource { include_threads: !self.group_threads };
+    let delta = monitoring::refresh_processes_from(&source, &mut self.processes, &mut self.previous_cpu_times, &mut self.previous_total_jiffies, self.cpu_smoothing_alpha)?;
+    monitoring::update_d_state_streaks(&self.processes, &mut self.d_state_streaks);
+    monitoring::update_session_io(&self.processes, &mut self.session_io);
+    self.growth.sample(&self.processes);
+    self.idle.sample(&self.processes, Instant::now());
+    self.last_spawn_counts.clear();
+    for (_, name) in &delta.spawned {
+        *self.last_spawn_counts.entry(name.clone()).or_insert(0) += 1;
+    }
+    self.system_history.sample()?;
+    Ok(delta)
 }
 
+    //Forgets every process's previous counters, so the next refresh takes fresh baselines (every
+    //process shows 0% CPU for one refresh) instead of rates measured across a suspend
+    pub fn reset_cpu_baseline(&mut self) {
+        self.previous_cpu_times.clear();
+        self.previous_total_jiffies = None;
+    }
+
+    //Re-reads just pid instead of all of /proc, updating its CPU% like a full refresh would.
+    //Returns None once it has exited. Only for watching a single process (see monitoring::refresh_pid_from).
+    pub fn refresh_pid(&mut self, pid: u32) -> Result<Option<&Process>, String> {
+        let alive = monitoring::refresh_pid_from(&monitoring::ProcSource { include_threads: !self.group_threads }, pid, &mut self.processes, &mut self.previous_cpu_times, &mut self.previous_total_jiffies, self.cpu_smoothing_alpha)?;
+        Ok(if alive { self.processes.get(&pid) } else { None })
+    }
+
+    //Turns on audit logging of kill/signal/renice operations to the given file
+    pub fn enable_audit(&mut self, path: PathBuf) -> Result<(), String> {
+        audit::open_log(&path)?;
+        self.audit_log = Some(path);
+        Ok(())
+    }
+
+    //Returns the pending audit-log write failure, if any (each failure is reported once)
+    pub fn take_audit_error(&self) -> Option<String> {
+        self.audit_error.borrow_mut().take()
+    }
+
+    //Returns the outcome of the last privilege-escalation attempt, if there is one to report
+    pub fn take_escalation_note(&self) -> Option<String> {
+        self.escalation_note.borrow_mut().take()
+    }
+
+    //Blocks until the process exits or the timeout elapses; returns whether it exited
+    pub fn wait_for_exit(&self, pid: u32, timeout: Duration) -> Result<bool, String> {
+        self.wait_for_exit_polling(pid, timeout, DEFAULT_EXIT_POLL_INTERVAL)
+    }
+
+    //Same as wait_for_exit, checking /proc/[pid] every poll_interval
+    pub fn wait_for_exit_polling(&self, pid: u32, timeout: Duration, poll_interval: Duration) -> Result<bool, String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if monitoring::has_exited(pid)? {
+                return Ok(true);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(false);
+            }
+            std::thread::sleep(poll_interval.min(deadline - now));
+        }
+    }
+
     pub fn build_process_tree(&self) -> Option<crate::process::tree::ProcessNode> {
         batch::build_process_tree(self)
     }
+
+    //Every process tree, including those not under root_pid (kernel threads under kthreadd, orphans)
+    pub fn build_process_forest(&self) -> Vec<crate::process::tree::ProcessNode> {
+        batch::build_process_forest(self)
+    }
+
+    //The whole process hierarchy as Graphviz DOT, for rendering with `dot -Tsvg`
+    pub fn tree_to_dot(&self) -> String {
+        export::forest_to_dot(&self.build_process_forest())
+    }
+
+    //Records the current process list, e.g. to save and compare with a later one
+    pub fn snapshot(&self) -> snapshot::Snapshot {
+        snapshot::Snapshot::capture(self.iter())
+    }
+
+    //Spawned, exited and changed processes between snapshot a (before) and b (after)
+    pub fn diff_snapshots(a: &snapshot::Snapshot, b: &snapshot::Snapshot) -> snapshot::SnapshotDiff {
+        snapshot::diff(a, b)
+    }
+
+    //One line per process, sorted by PID (see export::processes_to_compact)
+    pub fn compact_listing(&self) -> String {
+        let mut processes: Vec<&Process> = self.iter().collect();
+        processes.sort_by_key(|p| p.process_id);
+        export::processes_to_compact(processes)
+    }
  
     pub fn processes(&self) -> Vec<&Process> { //Process getter
         self.processes.values().collect() // Collects references to the Process structs from the HashMap values
     }
+
+    //Borrows the processes without allocating; prefer this over processes() for iterating/filtering
+    pub fn iter(&self) -> impl Iterator<Item = &Process> {
+        self.processes.values()
+    }
+
+    //Switches between one entry per process and one per thread, taking effect at the next refresh.
+    //The CPU baselines are reset so the threads appearing/disappearing aren't reported as spawned processes.
+    pub fn set_group_threads(&mut self, group_threads: bool) {
+        if self.group_threads != group_threads {
+            self.group_threads = group_threads;
+            self.reset_cpu_baseline();
+        }
+    }
+
+    //The process with this PID as of the last refresh
+    pub fn find_by_pid(&self, pid: u32) -> Option<&Process> {
+        self.processes.get(&pid)
+    }
+
+    //Processes still running an executable that has since been deleted or replaced (e.g. by a
+    //package upgrade), so they keep running the old code until restarted; sorted by PID
+    pub fn stale_binaries(&self) -> Vec<&Process> {
+        let mut stale: Vec<&Process> = self.iter().filter(|p| p.exe_deleted).collect();
+        stale.sort_by_key(|p| p.process_id);
+        stale
+    }
+
+    //PIDs of processes that have path open; scans every process's descriptors, so only call on demand
+    pub fn processes_with_open_file(&self, path: &str) -> Vec<u32> {
+        lookup::processes_with_open_file(self, path)
+    }
+
+    //PIDs of processes listening on TCP port or bound to UDP port; computed on demand like the above
+    pub fn processes_listening_on(&self, port: u16) -> Vec<u32> {
+        lookup::processes_listening_on(self, port)
+    }
+
+    //Bytes (read, written) from/to storage by pid since this tool first saw it;
+    //None if its I/O counters aren't readable (another user's process) or it has exited
+    pub fn session_io(&self, pid: u32) -> Option<(u64, u64)> {
+        self.session_io.get(&pid).map(|io| (io.read_bytes, io.write_bytes))
+    }
+
+    //Processes in the zombie (Z) state, which can only be cleared by their parent reaping them
+    pub fn zombies(&self) -> Vec<&Process> {
+        self.iter().filter(|p| p.pcb_data.state == 'Z').collect()
+    }
+
+    //Totals over all processes: count, RSS, average CPU, per-state counts and the top_n memory users
+    pub fn summary(&self, top_n: usize) -> monitoring::ProcessSummary {
+        monitoring::summarize(self.iter(), top_n)
+    }
+
+    //Waits up to timeout for pid to exit; None if it is still running by then. For children spawned
+    //through creation (background mode) the real exit status is returned; any other process is
+    //polled in /proc and its exit code is unknown.
+    pub fn wait_spawned(&mut self, pid: u32, timeout: Duration) -> Result<Option<SpawnedExit>, String> {
+        if let Some(status) = self.spawned_exits.remove(&pid) {
+            return Ok(Some(SpawnedExit::Status(status)));
+        }
+        if !self.spawned.get_mut().contains_key(&pid) {
+            return Ok(self.wait_for_exit(pid, timeout)?.then_some(SpawnedExit::ExitedUnknown));
+        }
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = match self.spawned.get_mut().get_mut(&pid) {
+                Some(child) => child.try_wait().map_err(|e| format!("Failed to wait for PID {}: {}", pid, e))?,
+                None => None,
+            };
+            if let Some(status) = status {
+                self.spawned.get_mut().remove(&pid);
+                return Ok(Some(SpawnedExit::Status(status)));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            std::thread::sleep(DEFAULT_EXIT_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+
+    //The n processes whose CPU% rose the most since the previous refresh, biggest jump first
+    //(momentary spikes that sorting by CPU% misses); processes whose CPU% didn't rise are left out
+    pub fn top_cpu_delta(&self, n: usize) -> Vec<&Process> {
+        let mut rising: Vec<&Process> = self.iter().filter(|p| p.pcb_data.cpu_percent_delta > 0.0).collect();
+        rising.sort_by(|a, b| b.pcb_data.cpu_percent_delta.total_cmp(&a.pcb_data.cpu_percent_delta).then(a.process_id.cmp(&b.process_id)));
+        rising.truncate(n);
+        rising
+    }
+
+    //Longest-running process with exactly this name; ties (same start tick) go to the lower PID
+    pub fn oldest_by_name(&self, name: &str) -> Option<&Process> {
+        self.iter()
+            .filter(|p| p.name == name)
+            .min_by_key(|p| (p.pcb_data.start_time_ticks, p.process_id))
+    }
+
+    //Most recently started process with exactly this name; ties go to the higher PID
+    pub fn newest_by_name(&self, name: &str) -> Option<&Process> {
+        self.iter()
+            .filter(|p| p.name == name)
+            .max_by_key(|p| (p.pcb_data.start_time_ticks, p.process_id))
+    }
+
+    //Names that spawned at least threshold_per_interval new processes in the last refresh, busiest first
+    pub fn spawn_storms(&self, threshold_per_interval: usize) -> Vec<(String, usize)> {
+        let mut storms: Vec<(String, usize)> = self.last_spawn_counts.iter()
+            .filter(|&(_, &count)| count >= threshold_per_interval)
+            .map(|(name, &count)| (name.clone(), count))
+            .collect();
+        storms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        storms
+    }
+
+    //Processes that started less than `within` ago, newest first
+    pub fn recently_started(&self, within: Duration) -> Vec<&Process> {
+        let mut recent: Vec<&Process> = self.iter()
+            .filter(|p| (p.pcb_data.uptime_seconds as f64) < within.as_secs_f64())
+            .collect();
+        recent.sort_by_key(|p| std::cmp::Reverse((p.pcb_data.start_time_ticks, p.process_id)));
+        recent
+    }
+
+    //What the rules call for as of the last refresh: for each process, the action of the first rule
+    //whose name it matches and whose CPU% it has stayed under for the rule's duration; sorted by PID.
+    //Nothing is done here; see rules::Action::apply.
+    pub fn evaluate_rules(&self, rules: &[rules::Rule]) -> Vec<(u32, rules::Action)> {
+        rules::evaluate(rules, &self.processes, &self.idle, Instant::now())
+    }
+
+    //Processes whose RSS, FD count or thread count is rising faster than growth.limits, sorted by PID
+    pub fn growing_processes(&self) -> Vec<(u32, growth::GrowthKind)> {
+        let mut pids: Vec<u32> = self.processes.keys().copied().collect();
+        pids.sort_unstable();
+        pids.into_iter()
+            .flat_map(|pid| self.growth.growing(pid).into_iter().map(move |kind| (pid, kind)))
+            .collect()
+    }
+
+    //Whether pid has been in uninterruptible sleep for at least STUCK_D_REFRESHES consecutive refreshes
+    pub fn is_stuck_in_d(&self, pid: u32) -> bool {
+        self.d_state_streaks.get(&pid).is_some_and(|&n| n >= monitoring::STUCK_D_REFRESHES)
+    }
+
+    //How long this session has been watching pid (the span Process::avg_cpu_percent_since_seen covers)
+    pub fn watched_for(&self, pid: u32) -> Option<Duration> {
+        self.previous_cpu_times.get(&pid).map(|sample| sample.time.duration_since(sample.first_seen))
+    }
+
+    //Collects the exit status of every spawned child that has finished
+    fn reap_spawned(&mut self) {
+        let exits = &mut self.spawned_exits;
+        self.spawned.get_mut().retain(|&pid, child| match child.try_wait() {
+            Ok(Some(status)) => {
+                exits.insert(pid, status);
+                false
+            }
+            _ => true,
+        });
+    }
+
+    //Foreground process group of a terminal ("pts/3" or "/dev/pts/3"): what is actually running
+    //in it, as the tpgid every process on that terminal reports. None if nothing listed uses it.
+    pub fn foreground_pgrp(&self, tty: &str) -> Option<u32> {
+        let tty = tty.strip_prefix("/dev/").unwrap_or(tty);
+        self.iter()
+            .filter(|p| p.pcb_data.tpgid > 0 && p.tty().as_deref() == Some(tty))
+            .map(|p| p.pcb_data.tpgid as u32)
+            .next()
+    }
+
+    //Tallies processes by their state character (R, S, D, Z, T, ...)
+    pub fn state_counts(&self) -> HashMap<char, usize> {
+        let mut counts = HashMap::new();
+        for process in self.iter() {
+            *counts.entry(process.pcb_data.state).or_insert(0) += 1;
+        }
+        counts
+    }
 }
 
@@ -0,0 +1,55 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::manager::Manager;
+
+/// Opens (creating if needed) the audit log so a bad path is reported up front
+pub fn open_log(path: &PathBuf) -> Result<(), String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map(|_| ())
+        .map_err(|e| format!("Cannot open audit log {}: {}", path.display(), e))
+}
+
+/// Appends a record of a successful privileged operation to the audit log, if enabled.
+/// A write failure never blocks the operation; it is stored on the manager and reported once.
+pub fn record(manager: &Manager, op: &str, pid: u32) {
+    record_result(manager, op, pid, "ok");
+}
+
+/// Like `record`, but with an explicit result (e.g. "dry-run" for operations that were skipped)
+pub fn record_result(manager: &Manager, op: &str, pid: u32, result: &str) {
+    let Some(path) = &manager.audit_log else {
+        return;
+    };
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let line = format!(
+        "{} uid={} op={} pid={} result={}\n",
+        timestamp, manager.active_user.id, op, pid, result
+    );
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+
+    match result {
+        Ok(_) => manager.audit_failing.set(false),
+        Err(e) => {
+            // Only surface the first failure of a run of failures
+            if !manager.audit_failing.replace(true) {
+                *manager.audit_error.borrow_mut() =
+                    Some(format!("Failed to write audit log {}: {}", path.display(), e));
+            }
+        }
+    }
+}
@@ -1,9 +1,72 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 use crate::manager::Manager;
 use crate::manager::operations;
 use crate::process::{Process};
 use crate::process::tree::ProcessNode;
 
+//Largest range accepted by parse_pid_list, so a typo like 1-4000000 can't allocate millions of PIDs
+const MAX_PID_RANGE: u32 = 100_000;
+
+//Highest PID the kernel can ever hand out (PID_MAX_LIMIT on 64-bit), used when pid_max can't be read
+const PID_MAX_LIMIT: u32 = 4_194_304;
+
+//Upper bound (exclusive) on PIDs from /proc/sys/kernel/pid_max. Always at most i32::MAX + 1, so an
+//accepted PID never wraps to a negative pid_t (a process group, or -1 for every process).
+pub fn pid_max() -> u32 {
+    std::fs::read_to_string("/proc/sys/kernel/pid_max")
+        .ok()
+        .and_then(|text| text.trim().parse::<u32>().ok())
+        .unwrap_or(PID_MAX_LIMIT)
+        .min(i32::MAX as u32 + 1)
+}
+
+//Parses a PID list such as "1001,1002,2000-2010" (whitespace allowed around tokens) into PIDs,
+//in the order given and without duplicates. Any malformed token rejects the whole list, as does a
+//PID of 0 or one at or above pid_max: handed to kill(2) those signal process groups or everything.
+pub fn parse_pid_list(list: &str) -> Result<Vec<u32>, String> {
+    parse_pid_list_below(list, pid_max())
+}
+
+//parse_pid_list with an explicit pid_max
+fn parse_pid_list_below(list: &str, pid_max: u32) -> Result<Vec<u32>, String> {
+    let parse_pid = |text: &str| -> Result<u32, String> {
+        match text.trim().parse::<u32>() {
+            Ok(pid) if pid > 0 && pid < pid_max && pid <= i32::MAX as u32 => Ok(pid),
+            Ok(pid) if pid > 0 => Err(format!("Invalid PID: {} (PIDs on this system are below {})", pid, pid_max)),
+            _ => Err(format!("Invalid PID: \"{}\"", text.trim())),
+        }
+    };
+
+    let mut pids = Vec::new();
+    let mut seen = HashSet::new();
+    for token in list.split(',') {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err("Empty entry in PID list".to_string());
+        }
+        let (start, end) = match token.split_once('-') {
+            Some((start, end)) => (parse_pid(start)?, parse_pid(end)?),
+            None => {
+                let pid = parse_pid(token)?;
+                (pid, pid)
+            }
+        };
+        if start > end {
+            return Err(format!("Invalid range \"{}\": start is greater than end", token));
+        }
+        if end - start >= MAX_PID_RANGE {
+            return Err(format!("Range \"{}\" is too large (at most {} PIDs)", token, MAX_PID_RANGE));
+        }
+        for pid in start..=end {
+            if seen.insert(pid) {
+                pids.push(pid);
+            }
+        }
+    }
+    Ok(pids)
+}
+
 //Building the process_tree which will be used heavily especially with parent-child relationships
 pub fn build_process_tree(manager: &Manager) -> Option<ProcessNode> {
     
@@ -18,32 +81,22 @@ pub fn build_process_tree(manager: &Manager) -> Option<ProcessNode> {
     let mut children_map: HashMap<u32, Vec<Process>> = HashMap::new();
     
 
-    for (&pid, process) in manager.processes.iter() {
-    if pid == manager.root_pid {
+    for process in manager.iter() {
+    if process.process_id == manager.root_pid {
         continue;
     }
 
     if let Some(ppid) = process.parent_id {
         children_map
             .entry(ppid)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(process.clone());
     }
 }
 
-    
-    //Recursive helper function to build the tree from the top down
-    fn build_node(node: &mut ProcessNode, children_map: &HashMap<u32, Vec<Process>>) {
-        let pid = node.process.process_id;
-        
-        if let Some(children) = children_map.get(&pid) {
-            for child_process in children {
-                let mut child_node = ProcessNode::new(child_process.clone());
-                //Recursively build children of this child
-                build_node(&mut child_node, children_map);
-                node.children.push(child_node);
-            }
-        }
+    //Children in PID order, so the tree doesn't reshuffle with HashMap iteration order
+    for children in children_map.values_mut() {
+        children.sort_by_key(|p| p.process_id);
     }
 
     build_node(&mut root_node, &children_map);
@@ -51,6 +104,47 @@ pub fn build_process_tree(manager: &Manager) -> Option<ProcessNode> {
     Some(root_node)
 }
 
+//Recursive helper function to build the tree from the top down
+fn build_node(node: &mut ProcessNode, children_map: &HashMap<u32, Vec<Process>>) {
+    let pid = node.process.process_id;
+    
+    if let Some(children) = children_map.get(&pid) {
+        for child_process in children {
+            let mut child_node = ProcessNode::new(child_process.clone());
+            //Recursively build children of this child
+            build_node(&mut child_node, children_map);
+            node.children.push(child_node);
+        }
+    }
+}
+
+//Builds every process tree, not just the one under root_pid: any process whose parent
+//isn't in the list (e.g. init and kthreadd, whose parent is 0) becomes a root.
+//Roots and children are ordered by PID so the output is stable.
+pub fn build_process_forest(manager: &Manager) -> Vec<ProcessNode> {
+    let mut roots = Vec::new();
+    let mut children_map: HashMap<u32, Vec<Process>> = HashMap::new();
+
+    for process in manager.iter() {
+        match process.parent_id {
+            Some(ppid) if ppid != process.process_id && manager.processes.contains_key(&ppid) => {
+                children_map.entry(ppid).or_default().push(process.clone());
+            }
+            _ => roots.push(ProcessNode::new(process.clone())),
+        }
+    }
+
+    for children in children_map.values_mut() {
+        children.sort_by_key(|p| p.process_id);
+    }
+    roots.sort_by_key(|node| node.process.process_id);
+
+    for root in &mut roots {
+        build_node(root, &children_map);
+    }
+    roots
+}
+
 fn get_descendant_pids(node: &ProcessNode) -> Vec<u32> {
     let mut descendants = Vec::new();
     
@@ -65,55 +159,205 @@ fn get_descendant_pids(node: &ProcessNode) -> Vec<u32> {
     descendants
 }
 
-//Placeholder for a group action, like killing a process and all its children.
+//Finds pid in the process forest and returns the PIDs below it, in pre-order (parents before children)
+pub(crate) fn subtree_descendants(manager: &Manager, pid: u32) -> Result<Vec<u32>, String> {
+    let forest = manager.build_process_forest();
+
+    //Use a simple iterative search to find the node
+    let mut stack: Vec<&ProcessNode> = forest.iter().collect();
+    while let Some(current) = stack.pop() {
+        if current.process.process_id == pid {
+            return Ok(get_descendant_pids(current));
+        }
+        stack.extend(current.children.iter());
+    }
+
+    Err(format!("PID {} not found in active processes.", pid))
+}
+
+//Applies an operation to each PID, collecting successes; fails with a summary if any PID failed
+pub(crate) fn apply_to_all(
+    manager: &Manager,
+    pids: impl IntoIterator<Item = u32>,
+    verb: &str,
+    op: fn(&Manager, u32) -> Result<(), String>,
+) -> Result<Vec<u32>, String> {
+    let mut succeeded = Vec::new();
+    let mut failed = 0;
+
+    for pid in pids {
+        match op(manager, pid) {
+            Ok(_) => succeeded.push(pid),
+            Err(e) => {
+                log::warn!("Failed to {} PID {}: {}", verb, pid, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("Failed to {} {} of {} processes.", verb, failed, succeeded.len() + failed));
+    }
+    Ok(succeeded)
+}
+
+//Group action: kill all descendants of a process (but not the process itself)
 pub fn kill_descendants(manager: &Manager, parent_pid: u32) -> Result<Vec<u32>, String> {
-    // 1. Permission Check: Batch actions require Admin privileges.
+    //Permission Check: Batch actions require Admin privileges.
+    crate::manager::permissions::check_admin_privilege(manager)?;
+    //Everything descends from init, so its "descendants" would be the whole system
+    crate::manager::permissions::check_not_critical(manager, parent_pid)?;
+
+    //Kill children first (reverse pre-order) to prevent accidental reparenting
+    let pids_to_kill = subtree_descendants(manager, parent_pid)?;
+    apply_to_all(manager, pids_to_kill.into_iter().rev(), "kill", operations::kill_process)
+}
+
+//Kills a process and everything below it, deepest processes first
+pub fn kill_subtree(manager: &Manager, pid: u32) -> Result<Vec<u32>, String> {
     crate::manager::permissions::check_admin_privilege(manager)?;
+    crate::manager::permissions::check_not_critical(manager, pid)?;
 
+    let mut pids = subtree_descendants(manager, pid)?;
+    pids.reverse();
+    pids.push(pid);
+    apply_to_all(manager, pids, "kill", operations::kill_process)
+}
 
-    //Build the entire process tree structure
-    let root_node = manager.build_process_tree()
-        .ok_or_else(|| "Failed to build process tree.".to_string())?;
+//Pauses a process and everything below it, parents first so they can't react to their children stopping
+pub fn pause_subtree(manager: &Manager, pid: u32) -> Result<Vec<u32>, String> {
+    crate::manager::permissions::check_admin_privilege(manager)?;
+    crate::manager::permissions::check_not_critical(manager, pid)?;
 
-    //Find the starting node (the parent to be killed) in the tree
-    let mut stack = vec![&root_node];
-    let mut parent_node: Option<&ProcessNode> = None;
+    let mut pids = vec![pid];
+    pids.extend(subtree_descendants(manager, pid)?);
+    apply_to_all(manager, pids, "pause", operations::pause_process)
+}
 
-    //Use a simple iterative search to find the parent node
-    while let Some(current) = stack.pop() {
-        if current.process.process_id == parent_pid {
-            parent_node = Some(current);
-            break;
+/// Why a zombie was or wasn't cleared by `clean_zombies`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZombieOutcome {
+    /// The parent reaped it after being sent SIGCHLD
+    Reaped,
+    /// Init (PID 1) is the parent; it reaps on its own schedule and can't be forced
+    ParentIsInit,
+    /// The zombie has no parent we can signal
+    NoParent,
+    /// The parent ignored SIGCHLD; killing the parent hands the zombie to init for reaping
+    ParentIgnored(u32),
+    /// SIGCHLD could not be delivered to the parent
+    SignalFailed(String),
+}
+
+/// Result of trying to clear a single zombie
+#[derive(Debug, Clone)]
+pub struct ZombieReport {
+    pub pid: u32,
+    pub name: String,
+    pub parent_pid: Option<u32>,
+    pub outcome: ZombieOutcome,
+}
+
+impl ZombieReport {
+    /// Human-readable explanation of the outcome
+    pub fn describe(&self) -> String {
+        match &self.outcome {
+            ZombieOutcome::Reaped => format!("Zombie {} ({}) was reaped", self.name, self.pid),
+            ZombieOutcome::ParentIsInit => format!(
+                "Zombie {} ({}) belongs to init (PID 1), which reaps it by itself; it can't be forced",
+                self.name, self.pid
+            ),
+            ZombieOutcome::NoParent => format!(
+                "Zombie {} ({}) has no parent that can be signaled",
+                self.name, self.pid
+            ),
+            ZombieOutcome::ParentIgnored(ppid) => format!(
+                "Zombie {} ({}) is still there: parent {} isn't reaping it (killing the parent lets init clean it up)",
+                self.name, self.pid, ppid
+            ),
+            ZombieOutcome::SignalFailed(e) => format!(
+                "Zombie {} ({}) could not be cleared: {}",
+                self.name, self.pid, e
+            ),
         }
-        stack.extend(current.children.iter());
     }
+}
 
-    let start_node = parent_node.ok_or_else(|| format!("PID {} not found in active processes.", parent_pid))?;
+//Zombies can't be killed; their parent has to reap them. Sends SIGCHLD to each zombie's parent,
+//waits briefly, refreshes, and reports which zombies are gone and why the rest remain.
+pub fn clean_zombies(manager: &mut Manager, wait: Duration) -> Result<Vec<ZombieReport>, String> {
+    crate::manager::permissions::check_admin_privilege(manager)?;
 
-    //Get all descendant PIDs using the recursive helper
-    let pids_to_kill = get_descendant_pids(start_node);
-    
-    //Execute the kill operation on all descendants
-    let mut successful_kills = Vec::new();
-    let mut failed_kills = 0;
-    
-    for pid in pids_to_kill.iter().rev() {
-        //Kill children first to prevent accidental reparenting
-        match operations::kill_process(manager, *pid) {
-            Ok(_) => successful_kills.push(*pid),
-            Err(e) => {
-                eprintln!("Warning: Failed to kill descendant PID {}: {}", pid, e);
-                failed_kills += 1;
-            }
+    let zombies: Vec<(u32, String, Option<u32>)> = manager
+        .zombies()
+        .iter()
+        .map(|p| (p.process_id, p.name.clone(), p.parent_id.filter(|&ppid| ppid != 0)))
+        .collect();
+
+    //Signal each distinct parent once (init is skipped: it reaps without being asked)
+    let mut signal_errors: HashMap<u32, String> = HashMap::new();
+    let parents: HashSet<u32> = zombies.iter().filter_map(|(_, _, ppid)| *ppid).collect();
+    for ppid in parents {
+        if ppid == 1 {
+            continue;
+        }
+        if let Err(e) = operations::notify_child_exit(manager, ppid) {
+            signal_errors.insert(ppid, e);
         }
     }
 
-    //Optionally kill the parent last, if required by the user's intent.
-    // For this implementation, we will only return the descendants killed.
-    
-    if failed_kills > 0 {
-        return Err(format!("Successfully killed {} processes, but failed to kill {} descendants.", successful_kills.len(), failed_kills));
+    //Give the parents a moment to reap, then look again
+    std::thread::sleep(wait);
+    manager.refresh()?;
+
+    let reports = zombies
+        .into_iter()
+        .map(|(pid, name, parent_pid)| {
+            let still_zombie = manager
+                .processes
+                .get(&pid)
+                .is_some_and(|p| p.pcb_data.state == 'Z');
+            let outcome = match parent_pid {
+                _ if !still_zombie => ZombieOutcome::Reaped,
+                None => ZombieOutcome::NoParent,
+                Some(1) => ZombieOutcome::ParentIsInit,
+                Some(ppid) => match signal_errors.get(&ppid) {
+                    Some(e) => ZombieOutcome::SignalFailed(e.clone()),
+                    None => ZombieOutcome::ParentIgnored(ppid),
+                },
+            };
+            ZombieReport { pid, name, parent_pid, outcome }
+        })
+        .collect();
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pid_list_rejects_values_kill_would_misread() {
+        assert_eq!(parse_pid_list_below("1, 4194303", 4_194_304), Ok(vec![1, 4_194_303]));
+        assert!(parse_pid_list_below("0", 4_194_304).is_err());
+        assert!(parse_pid_list_below("4194304", 4_194_304).is_err());
+        // Would wrap to -1 (every process) or negative process groups as a pid_t
+        assert!(parse_pid_list_below("4294967295", u32::MAX).is_err());
+        assert!(parse_pid_list_below("2147483648", u32::MAX).is_err());
+        assert_eq!(parse_pid_list_below("2147483647", u32::MAX), Ok(vec![i32::MAX as u32]));
+        assert!(parse_pid_list_below("-1", 4_194_304).is_err());
+        assert!(pid_max() <= i32::MAX as u32 + 1);
     }
 
-    Ok(successful_kills)
-}   
+    #[test]
+    fn pid_list_ranges_stay_within_bounds() {
+        assert_eq!(parse_pid_list_below("5-7,6,9", 100), Ok(vec![5, 6, 7, 9]));
+        assert!(parse_pid_list_below("98-100", 100).is_err());
+        assert!(parse_pid_list_below("0-3", 100).is_err());
+        assert!(parse_pid_list_below("4294967290-4294967295", u32::MAX).is_err());
+        assert!(parse_pid_list_below("7-5", 100).is_err());
+        assert!(parse_pid_list_below("1-100001", u32::MAX).is_err());
+        assert!(parse_pid_list_below("1,,2", 100).is_err());
+    }
+}
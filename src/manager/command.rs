@@ -0,0 +1,220 @@
+use crate::manager::Manager;
+use crate::manager::batch;
+use crate::manager::operations;
+
+//One-line usage summary of every command, shown by "help" and on parse errors
+pub const COMMAND_HELP: &str = "kill <pids> | term <pids> | termhard <pid> | kill-oldest <name> | kill-newest <name> | pause <pids> | resume <pids> | nice <pids> <-20..19> | nice-user <uid> <-20..19> | lsof <path> | port <n>   (pids: 1234, 1234,1240 or 1200-1210)";
+
+//A text command such as "kill 1234" or "nice 1234 5"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Kill(Vec<u32>),
+    Terminate(Vec<u32>),
+    TerminateHard(u32), // SIGTERM, then SIGKILL if it won't exit (operations::force_terminate)
+    KillOldest(String), // Longest-running process with this exact name (Manager::oldest_by_name)
+    KillNewest(String), // Most recently started one, e.g. a stuck retry (Manager::newest_by_name)
+    Pause(Vec<u32>),
+    Resume(Vec<u32>),
+    Nice { pids: Vec<u32>, value: i32 },
+    NiceUser { uid: u32, value: i32 }, // Every process of this real UID (operations::renice_user)
+    Lsof(String), // Which processes have this file open
+    Port(u16),    // Which processes listen on this port
+    Help,
+}
+
+//Parses a command line: a verb followed by PIDs (space- or comma-separated, ranges allowed via
+//batch::parse_pid_list) and, for nice, the new nice value last. Verbs are case-insensitive.
+pub fn parse_command(input: &str) -> Result<Command, String> {
+    let mut words = input.split_whitespace();
+    let verb = words.next().ok_or_else(|| "Empty command".to_string())?.to_lowercase();
+    let args: Vec<&str> = words.collect();
+    let pids = |args: &[&str]| -> Result<Vec<u32>, String> {
+        if args.is_empty() {
+            return Err(format!("{} needs at least one PID", verb));
+        }
+        // "1234, 1240" splits into "1234," and "1240"; the separator is already the space
+        let words: Vec<&str> = args.iter().map(|arg| arg.trim_matches(',')).collect();
+        batch::parse_pid_list(&words.join(","))
+    };
+
+    match verb.as_str() {
+        "kill" | "k" => Ok(Command::Kill(pids(&args)?)),
+        "term" | "terminate" => Ok(Command::Terminate(pids(&args)?)),
+        "termhard" | "hard" => match pids(&args)?.as_slice() {
+            &[pid] => Ok(Command::TerminateHard(pid)),
+            _ => Err("termhard needs exactly one PID, e.g. \"termhard 1234\"".to_string()),
+        },
+        "kill-oldest" | "kill-newest" => {
+            if args.is_empty() {
+                return Err(format!("{} needs a process name, e.g. \"{} nginx\"", verb, verb));
+            }
+            // Rejoin so a name with spaces doesn't need quoting
+            let name = args.join(" ");
+            Ok(if verb == "kill-oldest" { Command::KillOldest(name) } else { Command::KillNewest(name) })
+        }
+        "pause" | "stop" => Ok(Command::Pause(pids(&args)?)),
+        "resume" | "cont" | "continue" => Ok(Command::Resume(pids(&args)?)),
+        "nice" | "renice" => {
+            let Some((value, pid_args)) = args.split_last() else {
+                return Err("nice needs PIDs and a nice value, e.g. \"nice 1234 5\"".to_string());
+            };
+            Ok(Command::Nice { pids: pids(pid_args)?, value: parse_nice(value)? })
+        }
+        "nice-user" | "renice-user" => match args.as_slice() {
+            [uid, value] => Ok(Command::NiceUser {
+                uid: uid.parse::<u32>().map_err(|_| format!("Invalid UID: {}", uid))?,
+                value: parse_nice(value)?,
+            }),
+            _ => Err("nice-user needs a UID and a nice value, e.g. \"nice-user 1000 10\"".to_string()),
+        },
+        "lsof" => match args.as_slice() {
+            [] => Err("lsof needs a file path, e.g. \"lsof /var/log/syslog\"".to_string()),
+            // Rejoin so a path with spaces doesn't need quoting
+            _ => Ok(Command::Lsof(args.join(" "))),
+        },
+        "port" => match args.as_slice() {
+            [port] => port
+                .parse::<u16>()
+                .map(Command::Port)
+                .map_err(|_| format!("Invalid port: {} (must be 0 to 65535)", port)),
+            _ => Err("port needs one port number, e.g. \"port 8080\"".to_string()),
+        },
+        "help" | "?" => Ok(Command::Help),
+        other => Err(format!("Unknown command: {}. Commands: {}", other, COMMAND_HELP)),
+    }
+}
+
+//A nice value from -20 (highest priority) to 19
+pub fn parse_nice(value: &str) -> Result<i32, String> {
+    value
+        .parse::<i32>()
+        .ok()
+        .filter(|v| (-20..=19).contains(v))
+        .ok_or_else(|| format!("Invalid nice value: {} (must be -20 to 19)", value))
+}
+
+//Runs a parsed command against the manager, returning a message describing what was done.
+//With several PIDs every one is attempted; any failures are summarized in the error.
+pub fn execute(manager: &Manager, command: &Command) -> Result<String, String> {
+    let (pids, past_tense, result) = match command {
+        Command::Help => return Ok(COMMAND_HELP.to_string()),
+        Command::Lsof(path) => {
+            return Ok(describe_matches(manager, &manager.processes_with_open_file(path), &format!("with {} open", path)));
+        }
+        Command::Port(port) => {
+            return Ok(describe_matches(manager, &manager.processes_listening_on(*port), &format!("listening on port {}", port)));
+        }
+        Command::TerminateHard(pid) => {
+            return match operations::force_terminate(manager, *pid, operations::DEFAULT_FORCE_TERMINATE_GRACE)? {
+                outcome @ operations::KillOutcome::Unkillable { .. } => Err(outcome.describe(*pid)),
+                outcome => Ok(outcome.describe(*pid)),
+            };
+        }
+        Command::KillOldest(name) | Command::KillNewest(name) => {
+            let (process, which) = match command {
+                Command::KillOldest(_) => (manager.oldest_by_name(name), "oldest"),
+                _ => (manager.newest_by_name(name), "newest"),
+            };
+            let pid = process.map(|p| p.process_id).ok_or_else(|| format!("No process named {}", name))?;
+            operations::kill_process(manager, pid)?;
+            return Ok(format!("Killed PID {}, the {} process named {}", pid, which, name));
+        }
+        Command::NiceUser { uid, value } => {
+            let pids = operations::renice_user(manager, *uid, *value)?;
+            return Ok(format!("Reniced {} process(es) of UID {} to {}", pids.len(), uid, value));
+        }
+        Command::Kill(pids) => (pids, "Killed", run_each(manager, pids, "kill", operations::kill_process)),
+        Command::Terminate(pids) => (pids, "Terminated", run_each(manager, pids, "terminate", operations::terminate_process)),
+        Command::Pause(pids) => (pids, "Paused", run_each(manager, pids, "pause", operations::pause_process)),
+        Command::Resume(pids) => (pids, "Resumed", run_each(manager, pids, "resume", operations::resume_process)),
+        Command::Nice { pids, value } => {
+            let result = operations::renice_all(manager, pids.clone(), *value, "listed").map(|_| ());
+            (pids, "Reniced", result.map_err(|e| format!("{} (nice {})", e, value)))
+        }
+    };
+    result?;
+    Ok(match pids.as_slice() {
+        [pid] => format!("{} PID {}", past_tense, pid),
+        _ => format!("{} {} processes", past_tense, pids.len()),
+    })
+}
+
+//A single PID reports its own error; several go through batch::apply_to_all's summary
+fn run_each(manager: &Manager, pids: &[u32], verb: &str, op: fn(&Manager, u32) -> Result<(), String>) -> Result<(), String> {
+    match pids {
+        [pid] => op(manager, *pid),
+        _ => batch::apply_to_all(manager, pids.iter().copied(), verb, op).map(|_| ()),
+    }
+}
+
+//"2 processes listening on port 80: 812 (nginx), 813 (nginx)", or "No processes ..." when pids is empty
+fn describe_matches(manager: &Manager, pids: &[u32], what: &str) -> String {
+    if pids.is_empty() {
+        return format!("No processes {}", what);
+    }
+    let list: Vec<String> = pids
+        .iter()
+        .map(|&pid| match manager.find_by_pid(pid) {
+            Some(p) => format!("{} ({})", pid, p.name),
+            None => pid.to_string(),
+        })
+        .collect();
+    let count = match pids.len() {
+        1 => "1 process".to_string(),
+        n => format!("{} processes", n),
+    };
+    format!("{} {}: {}", count, what, list.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_command_form() {
+        assert_eq!(parse_command("kill 10"), Ok(Command::Kill(vec![10])));
+        assert_eq!(parse_command("K 10, 12"), Ok(Command::Kill(vec![10, 12])));
+        assert_eq!(parse_command("term 10-12"), Ok(Command::Terminate(vec![10, 11, 12])));
+        assert_eq!(parse_command("termhard 10"), Ok(Command::TerminateHard(10)));
+        assert_eq!(parse_command("kill-oldest my app"), Ok(Command::KillOldest("my app".to_string())));
+        assert_eq!(parse_command("kill-newest nginx"), Ok(Command::KillNewest("nginx".to_string())));
+        assert_eq!(parse_command("pause 10"), Ok(Command::Pause(vec![10])));
+        assert_eq!(parse_command("cont 10"), Ok(Command::Resume(vec![10])));
+        assert_eq!(parse_command("nice 10 12 -5"), Ok(Command::Nice { pids: vec![10, 12], value: -5 }));
+        assert_eq!(parse_command("nice-user 1000 19"), Ok(Command::NiceUser { uid: 1000, value: 19 }));
+        assert_eq!(parse_command("lsof /tmp/a b.log"), Ok(Command::Lsof("/tmp/a b.log".to_string())));
+        assert_eq!(parse_command("port 8080"), Ok(Command::Port(8080)));
+        assert_eq!(parse_command("?"), Ok(Command::Help));
+    }
+
+    #[test]
+    fn rejects_bad_pids() {
+        assert!(parse_command("kill").is_err());
+        assert!(parse_command("kill 0").is_err());
+        assert!(parse_command("kill -1").is_err());
+        assert!(parse_command("kill abc").is_err());
+        assert!(parse_command("kill 4294967295").is_err());
+        assert!(parse_command("termhard 10 11").is_err());
+        assert!(parse_command("pause 12-10").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_nice_values() {
+        assert!(parse_command("nice 10 20").is_err());
+        assert!(parse_command("nice 10 -21").is_err());
+        assert!(parse_command("nice 10 high").is_err());
+        assert!(parse_command("nice").is_err());
+        assert!(parse_command("nice-user 1000").is_err());
+        assert!(parse_command("nice-user root 5").is_err());
+    }
+
+    #[test]
+    fn lookups_need_their_argument() {
+        assert!(parse_command("lsof").is_err());
+        assert!(parse_command("port").is_err());
+        assert!(parse_command("port 70000").is_err());
+        assert!(parse_command("port 80 443").is_err());
+        assert!(parse_command("").is_err());
+        assert!(parse_command("frobnicate 10").is_err());
+    }
+}
@@ -1,5 +1,10 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
 use std::process::{Command, Stdio};
 use crate::manager::Manager;
+use crate::manager::operations;
 use crate::manager::permissions;
 
 /// Create a new process in foreground mode (blocking)
@@ -11,6 +16,7 @@ pub fn create_process_foreground(manager: &Manager, command: &str, args: &[&str]
     cmd.args(args);
     
     // In foreground mode, we wait for the process to complete
+    log::debug!("Running {} {:?} in the foreground", command, args);
     match cmd.status() {
         Ok(status) => {
             if status.success() {
@@ -23,58 +29,157 @@ pub fn create_process_foreground(manager: &Manager, command: &str, args: &[&str]
     }
 }
 
-/// Create a new process in background mode (non-blocking)
+/// Constraints applied to a background process from birth, instead of adjusting it once it runs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpawnOptions {
+    pub nice: Option<i32>,            // Nice value (-20 to 19; below the current one needs root)
+    pub affinity: Option<Vec<usize>>, // CPU cores it may run on
+    pub cgroup: Option<String>,       // cgroup v2 path, absolute or relative to /sys/fs/cgroup
+}
+
+impl SpawnOptions {
+    /// Check every option before anything is started, so a typo doesn't leave an unconstrained process behind
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(nice) = self.nice
+            && !(-20..=19).contains(&nice)
+        {
+            return Err(format!("Invalid nice value: {} (must be -20 to 19)", nice));
+        }
+        if let Some(cpus) = &self.affinity {
+            if cpus.is_empty() {
+                return Err("CPU affinity needs at least one CPU".to_string());
+            }
+            let configured = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_CONF) }.max(1) as usize;
+            let limit = configured.min(libc::CPU_SETSIZE as usize);
+            if let Some(cpu) = cpus.iter().find(|&&cpu| cpu >= limit) {
+                return Err(format!("CPU {} does not exist (CPUs are 0-{})", cpu, limit - 1));
+            }
+        }
+        if let Some(cgroup) = &self.cgroup {
+            operations::resolve_cgroup(cgroup)?;
+        }
+        Ok(())
+    }
+
+    /// The affinity as the mask sched_setaffinity takes
+    fn cpu_set(&self) -> Option<libc::cpu_set_t> {
+        let cpus = self.affinity.as_ref()?;
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        for &cpu in cpus {
+            unsafe { libc::CPU_SET(cpu, &mut set) };
+        }
+        Some(set)
+    }
+}
+
+/// Parses a CPU list in the taskset/cpuset format, e.g. "0-3,6", in the order given and without duplicates
+pub fn parse_cpu_list(list: &str) -> Result<Vec<usize>, String> {
+    let parse_cpu = |text: &str| text.trim().parse::<usize>().map_err(|_| format!("Invalid CPU: \"{}\"", text.trim()));
+    let mut cpus = Vec::new();
+    for token in list.split(',').map(str::trim) {
+        let (start, end) = match token.split_once('-') {
+            Some((start, end)) => (parse_cpu(start)?, parse_cpu(end)?),
+            None => (parse_cpu(token)?, parse_cpu(token)?),
+        };
+        if start > end {
+            return Err(format!("Invalid CPU range \"{}\": start is greater than end", token));
+        }
+        // Beyond what a cpu_set_t can hold (CPU_SET would write past it)
+        if end >= libc::CPU_SETSIZE as usize {
+            return Err(format!("Invalid CPU \"{}\": CPUs go up to {}", token, libc::CPU_SETSIZE - 1));
+        }
+        for cpu in start..=end {
+            if !cpus.contains(&cpu) {
+                cpus.push(cpu);
+            }
+        }
+    }
+    Ok(cpus)
+}
+
+/// Create a new process in background mode (non-blocking), constrained by options from the start
 /// Returns the PID of the spawned process
-/// Uses shell with proper argument escaping to safely detach the process
-pub fn create_process_background(manager: &Manager, command: &str, args: &[&str]) -> Result<u32, String> {
+pub fn create_process_background(manager: &Manager, command: &str, args: &[&str], options: &SpawnOptions) -> Result<u32, String> {
     permissions::check_admin_privilege(manager)?;
-    
-    // Use shell to properly detach the process using double-fork technique
-    // This prevents the process from becoming a zombie
-    // We properly escape arguments to prevent shell injection
-    let mut cmd = Command::new("sh");
-    cmd.arg("-c");
-    
-    // Build the command with properly escaped arguments
-    // Using printf %q to safely quote arguments (if available) or manual escaping
-    let mut escaped_args = Vec::new();
-    for arg in args {
-        // Simple escaping: wrap in single quotes and escape single quotes within
-        let escaped = arg.replace('\'', "'\"'\"'");
-        escaped_args.push(format!("'{}'", escaped));
+    spawn_detached(manager, command, args, Stdio::null(), Stdio::null(), options)
+}
+
+/// Same as create_process_background, but appends the process's stdout and stderr to log_path
+/// The log file is created (or opened) up front so a bad path is reported before anything runs
+pub fn create_process_background_logged(manager: &Manager, command: &str, args: &[&str], log_path: &Path, options: &SpawnOptions) -> Result<u32, String> {
+    permissions::check_admin_privilege(manager)?;
+
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .map_err(|e| format!("Cannot open log file {}: {}", log_path.display(), e))?;
+    let log_for_stderr = log
+        .try_clone()
+        .map_err(|e| format!("Cannot open log file {}: {}", log_path.display(), e))?;
+
+    spawn_detached(manager, command, args, Stdio::from(log), Stdio::from(log_for_stderr), options)
+}
+
+/// Start command directly (no shell, so arguments need no quoting) in a session of its own, applying
+/// the nice value and affinity between fork and exec and the cgroup right after spawning
+fn spawn_detached(manager: &Manager, command: &str, args: &[&str], stdout: Stdio, stderr: Stdio, options: &SpawnOptions) -> Result<u32, String> {
+    // A dry run skips the cgroup move, so the process would run unconstrained; don't start it at all
+    if manager.dry_run
+        && let Some(cgroup) = &options.cgroup
+    {
+        log::info!("[dry run] would start {} {:?} in cgroup {}", command, args, cgroup);
+        return Err(format!("Dry run: {} was not started, since it can't be placed in cgroup {}", command, cgroup));
     }
-    
-    let full_command = if args.is_empty() {
-        command.to_string()
-    } else {
-        format!("{} {}", command, escaped_args.join(" "))
-    };
-    
-    // Use nohup and & to properly background the process
-    // The shell will handle the double-fork and detach it from our process
-    // echo $! outputs the PID of the backgrounded process
-    cmd.arg(&format!("nohup {} > /dev/null 2>&1 & echo $!", full_command));
-    
-    // Redirect stdin to null
-    cmd.stdin(Stdio::null());
-    
-    // Capture the output to get the PID
-    match cmd.output() {
-        Ok(output) => {
-            if output.status.success() {
-                // Parse the PID from stdout
-                let pid_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                match pid_str.parse::<u32>() {
-                    Ok(pid) => Ok(pid),
-                    Err(_) => Err(format!("Failed to parse PID from output: {}", pid_str))
-                }
-            } else {
-                let error_msg = String::from_utf8_lossy(&output.stderr);
-                Err(format!("Failed to create background process: {}", error_msg))
+    options.validate()?;
+    let nice = options.nice;
+    let cpu_set = options.cpu_set();
+
+    let mut cmd = Command::new(command);
+    cmd.args(args).stdin(Stdio::null()).stdout(stdout).stderr(stderr);
+    // Only async-signal-safe calls may run between fork and exec; the closure captures plain values
+    unsafe {
+        cmd.pre_exec(move || {
+            // No controlling terminal, so closing ours doesn't send it SIGHUP (what nohup was for)
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if let Some(nice) = nice
+                && libc::setpriority(libc::PRIO_PROCESS, 0, nice) == -1
+            {
+                return Err(io::Error::last_os_error());
             }
+            if let Some(set) = &cpu_set
+                && libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set) == -1
+            {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let child = cmd.spawn().map_err(|e| match (nice, e.raw_os_error()) {
+        (Some(nice), Some(libc::EACCES | libc::EPERM)) if nice < 0 => {
+            format!("Failed to start {}: raising priority to nice {} needs root", command, nice)
         }
-        Err(e) => Err(format!("Failed to spawn background process: {}", e))
+        _ => format!("Failed to start {}: {}", command, e),
+    })?;
+    // Keep the handle so it is reaped on exit instead of lingering as a zombie
+    let pid = child.id();
+    log::debug!("Started {} {:?} in the background as PID {}", command, args, pid);
+    manager.spawned.borrow_mut().insert(pid, child);
+
+    if let Some(cgroup) = &options.cgroup
+        && let Err(e) = operations::move_to_cgroup(manager, pid, cgroup)
+    {
+        // It was asked to run constrained; don't leave it running outside the cgroup
+        if let Some(mut child) = manager.spawned.borrow_mut().remove(&pid) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        return Err(format!("{}; PID {} was stopped", e, pid));
     }
+    Ok(pid)
 }
 
 /// Create a process with shell execution (supports shell features like pipes, redirects)
@@ -91,7 +196,13 @@ pub fn create_process_shell(manager: &Manager, shell_command: &str, background:
         cmd.stderr(Stdio::null());
         
         match cmd.spawn() {
-            Ok(child) => Ok(child.id() as u32),
+            Ok(child) => {
+                // Keep the handle so Manager::wait_spawned can collect the exit status later
+                let pid = child.id();
+                log::debug!("Started shell command as PID {}: {}", pid, shell_command);
+                manager.spawned.borrow_mut().insert(pid, child);
+                Ok(pid)
+            }
             Err(e) => Err(format!("Failed to spawn background shell process: {}", e))
         }
     } else {
@@ -100,6 +211,7 @@ pub fn create_process_shell(manager: &Manager, shell_command: &str, background:
         cmd.arg("-c");
         cmd.arg(shell_command);
         
+        log::debug!("Running shell command in the foreground: {}", shell_command);
         match cmd.status() {
             Ok(status) => {
                 if status.success() {
@@ -113,3 +225,30 @@ pub fn create_process_shell(manager: &Manager, shell_command: &str, background:
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::{Privilege, User};
+
+    #[test]
+    fn cpu_list_rejects_cpus_past_the_cpu_set() {
+        assert_eq!(parse_cpu_list("0-2, 6,1"), Ok(vec![0, 1, 2, 6]));
+        let last = libc::CPU_SETSIZE as usize - 1;
+        assert_eq!(parse_cpu_list(&last.to_string()), Ok(vec![last]));
+        let err = parse_cpu_list(&format!("0,{}-{}", last, last + 1)).unwrap_err();
+        assert!(err.contains(&format!("\"{}-{}\"", last, last + 1)), "{}", err);
+        assert!(parse_cpu_list("1000000").is_err());
+        assert!(parse_cpu_list("3-1").is_err());
+    }
+
+    #[test]
+    fn dry_run_never_starts_a_process_outside_its_cgroup() {
+        let mut manager = Manager::new(User::new(0, "admin", Privilege::Admin)).unwrap();
+        manager.dry_run = true;
+        let options = SpawnOptions { cgroup: Some("/".to_string()), ..Default::default() };
+
+        assert!(create_process_background(&manager, "sleep", &["30"], &options).is_err());
+        assert!(manager.spawned.borrow().is_empty());
+    }
+}
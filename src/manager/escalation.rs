@@ -0,0 +1,72 @@
+use std::process::Command;
+
+use nix::sys::signal::Signal;
+
+use crate::manager::Manager;
+
+/// External helper used to retry an operation with elevated privileges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscalationMethod {
+    /// `sudo -n` (non-interactive: only works with cached credentials or NOPASSWD rules)
+    Sudo,
+    /// `pkexec` (asks for authentication through the desktop's polkit agent)
+    Pkexec,
+}
+
+impl EscalationMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            EscalationMethod::Sudo => "sudo",
+            EscalationMethod::Pkexec => "pkexec",
+        }
+    }
+
+    fn command(self) -> Command {
+        match self {
+            EscalationMethod::Sudo => {
+                let mut cmd = Command::new("sudo");
+                cmd.arg("-n");
+                cmd
+            }
+            EscalationMethod::Pkexec => Command::new("pkexec"),
+        }
+    }
+}
+
+/// Re-send a signal through the configured escalation method (`kill -<signo> <pid>`)
+pub(crate) fn kill(manager: &Manager, method: EscalationMethod, pid: u32, signal: Signal) -> Result<(), String> {
+    let signo = format!("-{}", signal as i32);
+    let pid_arg = pid.to_string();
+    run(manager, method, &format!("{} PID {}", signal.as_str(), pid), &["kill", &signo, &pid_arg])
+}
+
+/// Re-apply a nice value through the configured escalation method (`renice -n <nice> -p <pid>`)
+pub(crate) fn renice(manager: &Manager, method: EscalationMethod, pid: u32, nice_value: i32) -> Result<(), String> {
+    let nice_arg = nice_value.to_string();
+    let pid_arg = pid.to_string();
+    run(manager, method, &format!("renice PID {} to {}", pid, nice_value), &["renice", "-n", &nice_arg, "-p", &pid_arg])
+}
+
+/// Run the elevated command and leave a note on the manager describing the attempt
+fn run(manager: &Manager, method: EscalationMethod, action: &str, args: &[&str]) -> Result<(), String> {
+    let result = match method.command().args(args).output() {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            Err(if stderr.is_empty() {
+                format!("exited with {}", output.status)
+            } else {
+                stderr
+            })
+        }
+        Err(e) => Err(format!("could not run {}: {}", method.label(), e)),
+    };
+
+    let note = match &result {
+        Ok(_) => format!("Permission denied; retried {} via {}: succeeded", action, method.label()),
+        Err(e) => format!("Permission denied; retried {} via {}: failed ({})", action, method.label(), e),
+    };
+    *manager.escalation_note.borrow_mut() = Some(note);
+
+    result.map_err(|e| format!("escalation via {} failed: {}", method.label(), e))
+}
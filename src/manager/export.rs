@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::process::Process;
+use crate::process::tree::ProcessNode;
+
+/// One process as written by the JSON export and returned by the API's `GET /processes` and
+/// `GET /processes/{pid}`
+#[derive(Debug, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub name: String,
+    pub cmdline: Vec<String>,
+    pub uid: u32,
+    pub euid: u32,
+    pub state: char,
+    pub cpu_percent: f32,
+    pub cpu_percent_smoothed: f32,
+    pub memory_rss_kb: u64,
+    pub nice: i32,
+    pub uptime_seconds: u64,
+}
+
+impl From<&Process> for ProcessInfo {
+    fn from(p: &Process) -> Self {
+        ProcessInfo {
+            pid: p.process_id,
+            ppid: p.parent_id,
+            name: p.name.clone(),
+            cmdline: p.cmdline.clone(),
+            uid: p.user_id,
+            euid: p.effective_uid,
+            state: p.pcb_data.state,
+            cpu_percent: p.pcb_data.cpu_percent,
+            cpu_percent_smoothed: p.cpu_percent_smoothed(),
+            memory_rss_kb: p.pcb_data.memory_rss_kb,
+            nice: p.pcb_data.priority,
+            uptime_seconds: p.pcb_data.uptime_seconds,
+        }
+    }
+}
+
+/// File formats a process list can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Csv, ExportFormat::Json];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Render processes in format, keeping the order they are given in
+pub fn export_processes<'a>(processes: impl IntoIterator<Item = &'a Process>, format: ExportFormat) -> Result<String, String> {
+    match format {
+        ExportFormat::Csv => Ok(processes_to_csv(processes)),
+        ExportFormat::Json => processes_to_json(processes),
+    }
+}
+
+/// Escape text for use inside a double-quoted DOT string
+fn escape_dot(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break (RFC 4180)
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Render processes as CSV with a header row, one row per process
+pub fn processes_to_csv<'a>(processes: impl IntoIterator<Item = &'a Process>) -> String {
+    let mut out = String::from("pid,ppid,name,uid,state,cpu_percent,memory_rss_kb,nice,uptime_seconds,cmdline\n");
+    for process in processes {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{:.1},{},{},{},{}",
+            process.process_id,
+            process.parent_id.map(|p| p.to_string()).unwrap_or_default(),
+            escape_csv(&process.name),
+            process.user_id,
+            process.pcb_data.state,
+            process.pcb_data.cpu_percent,
+            process.pcb_data.memory_rss_kb,
+            process.pcb_data.priority,
+            process.pcb_data.uptime_seconds,
+            escape_csv(&process.cmdline_string())
+        );
+    }
+    out
+}
+
+/// Render processes as a pretty-printed JSON array, in the same shape as the API's `GET /processes`
+pub fn processes_to_json<'a>(processes: impl IntoIterator<Item = &'a Process>) -> Result<String, String> {
+    let processes: Vec<ProcessInfo> = processes.into_iter().map(ProcessInfo::from).collect();
+    serde_json::to_string_pretty(&processes).map_err(|e| format!("Failed to serialize processes: {}", e))
+}
+
+/// Render processes one per line as `PID NAME STATE CPU% RSS_MB`, space-separated with
+/// no header, for piping into grep/awk. Whitespace inside names becomes '_' so every
+/// line has exactly five fields.
+pub fn processes_to_compact<'a>(processes: impl IntoIterator<Item = &'a Process>) -> String {
+    let mut out = String::new();
+    for process in processes {
+        let name: String = process
+            .name
+            .chars()
+            .map(|c| if c.is_whitespace() { '_' } else { c })
+            .collect();
+        let _ = writeln!(
+            out,
+            "{} {} {} {:.1} {:.1}",
+            process.process_id,
+            name,
+            process.pcb_data.state,
+            process.pcb_data.cpu_percent,
+            process.rss_mb()
+        );
+    }
+    out
+}
+
+/// Render process trees as a Graphviz DOT digraph: one node per process labeled
+/// "pid\nname", and an edge from each parent to each of its children
+pub fn forest_to_dot(roots: &[ProcessNode]) -> String {
+    fn write_node(dot: &mut String, node: &ProcessNode) {
+        let pid = node.process.process_id;
+        // Writing to a String can't fail
+        let _ = writeln!(dot, "    {} [label=\"{}\\n{}\"];", pid, pid, escape_dot(&node.process.name));
+        for child in &node.children {
+            let _ = writeln!(dot, "    {} -> {};", pid, child.process.process_id);
+            write_node(dot, child);
+        }
+    }
+
+    let mut dot = String::from("digraph processes {\n    node [shape=box];\n");
+    for root in roots {
+        write_node(&mut dot, root);
+    }
+    dot.push_str("}\n");
+    dot
+}
@@ -0,0 +1,131 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::process::Process;
+
+/// How far back growth rates are measured
+pub const DEFAULT_GROWTH_WINDOW: Duration = Duration::from_secs(60);
+
+/// Minimum span of samples before a rate is trusted (avoids flagging a single noisy refresh)
+const MIN_GROWTH_SPAN: Duration = Duration::from_secs(10);
+
+/// Which resource a process is steadily accumulating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrowthKind {
+    Memory,
+    FileDescriptors,
+    Threads,
+}
+
+impl GrowthKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            GrowthKind::Memory => "memory",
+            GrowthKind::FileDescriptors => "file descriptors",
+            GrowthKind::Threads => "threads",
+        }
+    }
+}
+
+/// Growth per minute above which a process is flagged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowthLimits {
+    pub rss_mb_per_min: f64,
+    pub fds_per_min: f64,
+    pub threads_per_min: f64,
+}
+
+impl Default for GrowthLimits {
+    fn default() -> Self {
+        Self {
+            rss_mb_per_min: 500.0,
+            fds_per_min: 100.0,
+            threads_per_min: 50.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GrowthSample {
+    time: Instant,
+    rss_kb: u64,
+    fds: Option<usize>, // None when /proc/[pid]/fd isn't readable (another user's process)
+    threads: u64,
+}
+
+/// Short per-PID history of RSS, open FDs and thread count, used to spot leaks
+/// before they hit a fixed threshold
+#[derive(Debug)]
+pub struct GrowthTracker {
+    pub limits: GrowthLimits,
+    window: Duration,
+    samples: HashMap<u32, VecDeque<GrowthSample>>,
+}
+
+impl GrowthTracker {
+    pub fn new(window: Duration) -> Self {
+        GrowthTracker {
+            limits: GrowthLimits::default(),
+            window,
+            samples: HashMap::new(),
+        }
+    }
+
+    /// Record the current figures of every process, forgetting exited PIDs and samples older than the window
+    pub fn sample(&mut self, processes: &HashMap<u32, Process>) {
+        let now = Instant::now();
+        self.samples.retain(|pid, _| processes.contains_key(pid));
+
+        for process in processes.values() {
+            let history = self.samples.entry(process.process_id).or_default();
+            history.push_back(GrowthSample {
+                time: now,
+                rss_kb: process.pcb_data.memory_rss_kb,
+                fds: process.pcb_data.fd_count,
+                threads: process.pcb_data.num_threads,
+            });
+            while history.front().is_some_and(|s| now.duration_since(s.time) > self.window) {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// Growth per minute of each resource for pid over the window (None until enough history)
+    pub fn rates(&self, pid: u32) -> Option<Vec<(GrowthKind, f64)>> {
+        let history = self.samples.get(&pid)?;
+        let (first, last) = (history.front()?, history.back()?);
+        let span = last.time.duration_since(first.time);
+        if span < MIN_GROWTH_SPAN {
+            return None;
+        }
+        let per_min = 60.0 / span.as_secs_f64();
+
+        let mut rates = vec![
+            (GrowthKind::Memory, (last.rss_kb as f64 - first.rss_kb as f64) / 1024.0 * per_min),
+            (GrowthKind::Threads, (last.threads as f64 - first.threads as f64) * per_min),
+        ];
+        if let (Some(first_fds), Some(last_fds)) = (first.fds, last.fds) {
+            rates.push((GrowthKind::FileDescriptors, (last_fds as f64 - first_fds as f64) * per_min));
+        }
+        Some(rates)
+    }
+
+    /// Resources of pid growing faster than the limits
+    pub fn growing(&self, pid: u32) -> Vec<GrowthKind> {
+        let Some(rates) = self.rates(pid) else {
+            return Vec::new();
+        };
+        rates
+            .into_iter()
+            .filter(|&(kind, rate)| {
+                let limit = match kind {
+                    GrowthKind::Memory => self.limits.rss_mb_per_min,
+                    GrowthKind::FileDescriptors => self.limits.fds_per_min,
+                    GrowthKind::Threads => self.limits.threads_per_min,
+                };
+                rate > limit
+            })
+            .map(|(kind, _)| kind)
+            .collect()
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use procfs::net::{TcpState, UdpState};
+use procfs::process::{FDTarget, Process as ProcfsProcess};
+
+use crate::manager::Manager;
+
+//Both lookups read every process's fd/ directory, which is too slow to do on each refresh,
+//so they only run when asked. Without root, other users' processes can't be inspected and are skipped.
+
+//Open file descriptor targets of pid (empty if its fd/ directory can't be read)
+fn fd_targets(pid: u32) -> Vec<FDTarget> {
+    ProcfsProcess::new(pid as i32)
+        .and_then(|p| p.fd())
+        .map(|fds| fds.flatten().map(|fd| fd.target).collect())
+        .unwrap_or_default()
+}
+
+//PIDs (sorted) of processes with path open. Symlinks and relative paths are resolved first,
+//since the kernel reports the canonical path.
+pub fn processes_with_open_file(manager: &Manager, path: &str) -> Vec<u32> {
+    let target: PathBuf = Path::new(path).canonicalize().unwrap_or_else(|_| PathBuf::from(path));
+    let mut pids: Vec<u32> = manager.iter()
+        .map(|p| p.process_id)
+        .filter(|&pid| fd_targets(pid).iter().any(|t| matches!(t, FDTarget::Path(p) if *p == target)))
+        .collect();
+    pids.sort_unstable();
+    pids
+}
+
+//Socket inodes listening on port in our network namespace: TCP sockets in LISTEN and bound UDP sockets
+fn listening_inodes(port: u16) -> HashSet<u64> {
+    let mut inodes = HashSet::new();
+    // A missing table (e.g. tcp6 with IPv6 disabled) just contributes nothing
+    for table in [procfs::net::tcp(), procfs::net::tcp6()] {
+        inodes.extend(table.unwrap_or_default().into_iter()
+            .filter(|e| e.state == TcpState::Listen && e.local_address.port() == port)
+            .map(|e| e.inode));
+    }
+    for table in [procfs::net::udp(), procfs::net::udp6()] {
+        inodes.extend(table.unwrap_or_default().into_iter()
+            .filter(|e| e.state == UdpState::Close && e.local_address.port() == port)
+            .map(|e| e.inode));
+    }
+    inodes
+}
+
+//PIDs (sorted) of processes listening on a TCP port or bound to a UDP port, found by matching
+//the socket inodes from /proc/net/{tcp,tcp6,udp,udp6} against each process's open descriptors
+pub fn processes_listening_on(manager: &Manager, port: u16) -> Vec<u32> {
+    let inodes = listening_inodes(port);
+    if inodes.is_empty() {
+        return Vec::new();
+    }
+    let mut pids: Vec<u32> = manager.iter()
+        .map(|p| p.process_id)
+        .filter(|&pid| fd_targets(pid).iter().any(|t| matches!(t, FDTarget::Socket(inode) if inodes.contains(inode))))
+        .collect();
+    pids.sort_unstable();
+    pids
+}
@@ -1,12 +1,192 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use procfs;
 
-use crate::process::Process;
+use crate::manager::system;
+use crate::process::{signal_name, Process, StateCause};
+
+/// Per-process counters remembered from the previous refresh, used to turn
+/// cumulative kernel counters into rates
+#[derive(Debug, Clone)]
+pub struct PreviousSample {
+    pub cpu_time: u64,    // utime + stime, in jiffies
+    pub blkio_ticks: u64, // Aggregated block I/O delay, in clock ticks
+    pub name: String,     // comm at the time of the sample, to notice renames
+    pub uid: Option<u32>, // Real UID at the last sample that could read it, to notice setuid transitions
+    pub cpu_smoothed: Option<f32>, // Moving average of CPU%, None until the first real sample
+    pub cpu_percent: Option<f32>,  // Raw CPU% of the last real sample, for the per-refresh change
+    pub time: Instant,
+    pub first_cpu_time: u64,  // cpu_time when this session first saw the process
+    pub first_seen: Instant,  // When that was, for the average CPU% since then
+}
+
+/// Aggregate figures over a set of processes, for footers and quick overviews
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSummary {
+    pub total: usize,
+    pub total_rss_kb: u64,
+    pub average_cpu_percent: f32,
+    pub state_counts: HashMap<char, usize>,
+    pub top_memory: Vec<(u32, String, u64)>, // (pid, name, rss kB), largest first
+}
+
+/// Summarize processes, keeping the top_n largest memory consumers
+pub fn summarize<'a>(processes: impl Iterator<Item = &'a Process>, top_n: usize) -> ProcessSummary {
+    let mut summary = ProcessSummary::default();
+    let mut cpu_total = 0.0;
+    let mut by_memory = Vec::new();
+
+    for process in processes {
+        summary.total += 1;
+        summary.total_rss_kb += process.pcb_data.memory_rss_kb;
+        cpu_total += process.pcb_data.cpu_percent;
+        *summary.state_counts.entry(process.pcb_data.state).or_insert(0) += 1;
+        by_memory.push((process.process_id, process.name.clone(), process.pcb_data.memory_rss_kb));
+    }
+
+    if summary.total > 0 {
+        summary.average_cpu_percent = cpu_total / summary.total as f32;
+    }
+    by_memory.sort_by_key(|&(pid, _, rss)| (std::cmp::Reverse(rss), pid));
+    by_memory.truncate(top_n);
+    summary.top_memory = by_memory;
+    summary
+}
+
+/// Consecutive refreshes in uninterruptible sleep after which a process is considered stuck
+pub const STUCK_D_REFRESHES: u32 = 3;
+
+/// Count, per PID, how many consecutive refreshes the process has been in the D state.
+/// Processes that left D (or exited) are dropped from the map.
+pub fn update_d_state_streaks(processes: &HashMap<u32, Process>, streaks: &mut HashMap<u32, u32>) {
+    streaks.retain(|pid, _| processes.get(pid).is_some_and(|p| p.pcb_data.state == 'D'));
+    for process in processes.values().filter(|p| p.pcb_data.state == 'D') {
+        *streaks.entry(process.process_id).or_insert(0) += 1;
+    }
+}
+
+/// Storage I/O a process did while this tool was watching it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionIo {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    last_read: u64,  // Kernel counters at the previous refresh, to take deltas from
+    last_write: u64,
+}
+
+/// Add each process's I/O since the previous refresh to its session totals.
+/// The first sighting only records a baseline; exited (or unreadable) processes are dropped.
+pub fn update_session_io(processes: &HashMap<u32, Process>, session_io: &mut HashMap<u32, SessionIo>) {
+    session_io.retain(|pid, _| processes.get(pid).is_some_and(|p| p.pcb_data.io_read_bytes.is_some()));
+    for process in processes.values() {
+        let (Some(read), Some(write)) = (process.pcb_data.io_read_bytes, process.pcb_data.io_write_bytes) else {
+            continue;
+        };
+        let totals = session_io.entry(process.process_id).or_insert(SessionIo {
+            last_read: read,
+            last_write: write,
+            ..SessionIo::default()
+        });
+        totals.read_bytes += read.saturating_sub(totals.last_read);
+        totals.write_bytes += write.saturating_sub(totals.last_write);
+        totals.last_read = read;
+        totals.last_write = write;
+    }
+}
+
+/// Default weight of the newest sample in the CPU% moving average
+pub const DEFAULT_CPU_SMOOTHING_ALPHA: f32 = 0.3;
+
+/// What changed between two refreshes, beyond the new process list itself
+#[derive(Debug, Clone, Default)]
+pub struct RefreshDelta {
+    pub loaded: usize,                       // Number of processes successfully read
+    pub renamed: Vec<(u32, String, String)>, // (pid, old name, new name) for processes whose comm changed
+    pub uid_changed: Vec<(u32, u32, u32)>,   // (pid, old uid, new uid) for privilege drops/escalations
+    pub spawned: Vec<(u32, String)>,         // (pid, name) of processes first seen this refresh (empty on the first refresh)
+}
+
+/// Where refresh_processes reads its data from. ProcSource reads the live /proc;
+/// tests substitute scripted values to check the rate math deterministically.
+pub trait MonitorSource {
+    /// PIDs currently listed (processes that fail to list are skipped)
+    fn pids(&self) -> Result<Vec<u32>, String>;
+    /// Full data of one process; NotFound if it exited since being listed
+    fn read_process(&self, pid: u32) -> Result<Process, procfs::ProcError>;
+    /// Cumulative utime + stime of pid, in jiffies
+    fn cpu_time_jiffies(&self, pid: u32) -> Result<u64, procfs::ProcError>;
+    /// Cumulative system-wide jiffies (None if unreadable)
+    fn total_jiffies(&self) -> Option<u64>;
+    fn num_cores(&self) -> f32;
+    /// Clock ticks per second (USER_HZ)
+    fn hz(&self) -> f64;
+    /// Time the sample is taken, for per-second rates
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// The live /proc filesystem. /proc lists one entry per process (thread group), whose stat
+/// already sums the CPU time of all its threads; with include_threads every other thread
+/// (/proc/[pid]/task/[tid]) is listed as an entry of its own as well, and each entry, the
+/// main thread included, only gets its own thread's CPU time so nothing is counted twice.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcSource {
+    pub include_threads: bool,
+}
+
+impl MonitorSource for ProcSource {
+    fn pids(&self) -> Result<Vec<u32>, String> {
+        let procfs_processes = procfs::process::all_processes()
+            .map_err(|e| format!("Failed to read process list: {}", e))?;
+        let mut pids = Vec::new();
+        for process in procfs_processes.filter_map(|p| p.ok()) { //Skip listing errors
+            pids.push(process.pid as u32);
+            if self.include_threads
+                && let Ok(tasks) = process.tasks()
+            {
+                pids.extend(tasks.filter_map(|t| t.ok()).map(|t| t.tid as u32).filter(|&tid| tid != process.pid as u32));
+            }
+        }
+        Ok(pids)
+    }
+
+    fn read_process(&self, pid: u32) -> Result<Process, procfs::ProcError> {
+        let mut process = Process::try_from(pid)?;
+        if self.include_threads {
+            let (utime, stime) = Process::get_thread_cpu_times(pid)?;
+            let hz = get_hz();
+            process.pcb_data.utime_seconds = utime as f64 / hz;
+            process.pcb_data.stime_seconds = stime as f64 / hz;
+        }
+        Ok(process)
+    }
+
+    fn cpu_time_jiffies(&self, pid: u32) -> Result<u64, procfs::ProcError> {
+        if self.include_threads {
+            Process::get_thread_cpu_times(pid).map(|(utime, stime)| utime + stime)
+        } else {
+            Process::get_cpu_time_jiffies(pid)
+        }
+    }
+
+    fn total_jiffies(&self) -> Option<u64> {
+        system::read_cpu_times().ok().map(|times| times.total)
+    }
+
+    fn num_cores(&self) -> f32 {
+        num_cores()
+    }
+
+    fn hz(&self) -> f64 {
+        get_hz()
+    }
+}
 
 /// Get the number of CPU cores for CPU percentage calculation
-fn get_num_cores() -> f32 {
+/// (CPU% tops out at 100 × this, one fully busy core being 100%)
+pub fn num_cores() -> f32 {
     // Try to read from /proc/cpuinfo or use sysconf
     // For simplicity, we'll use a fallback method
     match std::fs::read_to_string("/proc/cpuinfo") {
@@ -58,79 +238,83 @@ fn get_hz() -> f64 {
     }
 }
 
-// Reads the /proc filesystem, updates the provided HashMap with current data, and returns the number of processes successfully loaded.
-// Also calculates CPU percentage by tracking CPU time between refreshes.
+// CPU% the way top computes it: the process's share of all jiffies that elapsed system-wide,
+// scaled by the core count so one fully busy core reads 100%.
+// Capped at 100% per core: the two counters are read at slightly different moments, so the
+// process delta can overshoot the system delta by a few jiffies.
+pub fn cpu_percent_from_jiffies(process_delta: u64, total_system_delta: u64, num_cores: f32) -> f32 {
+    if total_system_delta == 0 {
+        return 0.0;
+    }
+    let share = (process_delta as f64 / total_system_delta as f64).min(1.0);
+    (share * num_cores as f64 * 100.0) as f32
+}
+
+// Reads the /proc filesystem, updates the provided HashMap with current data, and returns what changed (including the number of processes successfully loaded).
+// Also calculates CPU percentage and block I/O wait rate by tracking counters between refreshes,
+// and notices processes that renamed themselves (prctl(PR_SET_NAME)) or changed real UID since the last refresh.
+// CPU% is measured against the total system jiffies elapsed since previous_total_jiffies (see cpu_percent_from_jiffies),
+// and also smoothed with an exponential moving average: smoothed = alpha*new + (1-alpha)*old.
 pub fn refresh_processes(
     processes: &mut HashMap<u32, Process>,
-    previous_cpu_times: &mut HashMap<u32, (u64, Instant)>,
-) -> Result<usize, String> {
+    previous_cpu_times: &mut HashMap<u32, PreviousSample>,
+    previous_total_jiffies: &mut Option<u64>,
+    smoothing_alpha: f32,
+) -> Result<RefreshDelta, String> {
+    refresh_processes_from(&ProcSource::default(), processes, previous_cpu_times, previous_total_jiffies, smoothing_alpha)
+}
+
+// Same as refresh_processes, reading from any MonitorSource instead of /proc
+pub fn refresh_processes_from(
+    source: &impl MonitorSource,
+    processes: &mut HashMap<u32, Process>,
+    previous_cpu_times: &mut HashMap<u32, PreviousSample>,
+    previous_total_jiffies: &mut Option<u64>,
+    smoothing_alpha: f32,
+) -> Result<RefreshDelta, String> {
     
-    let procfs_processes = match procfs::process::all_processes() { //Reading intial process list
-        Ok(p) => p,
-        Err(e) => return Err(format!("Failed to read process list: {}", e)),
-    };
+    let pids = source.pids()?; //Reading intial process list
 
     let mut new_processes = HashMap::new(); //New temporary hash_map to store the new process list 
-    let mut successfully_loaded = 0;
-    let current_time = Instant::now();
-    let num_cores = get_num_cores();
-    let hz = get_hz();
+    let mut delta = RefreshDelta::default();
+    // On the very first refresh every process is "new", so nothing counts as spawned
+    let first_refresh = previous_cpu_times.is_empty();
+    let sample = SampleContext::new(source, previous_total_jiffies, smoothing_alpha);
     
     //Loop over new process info, validate it, and add it to the new hash_map
-    for p in procfs_processes { //Loops every process that procfs managed to find
-        let procfs_proc = match p {
-            Ok(p) => p,
-            Err(_) => continue, //Skip listing errors
-        };
-
-        let pid = procfs_proc.pid as u32;
-
-        match Process::try_from(pid) {
+    for pid in pids { //Loops every process that was listed
+        match source.read_process(pid) {
             Ok(mut proc) => {
-                // Calculate CPU percentage if we have previous data
-                if let Some((prev_cpu_time, prev_time)) = previous_cpu_times.get(&pid) {
-                    // Get current CPU time
-                    match Process::get_cpu_time_jiffies(pid) {
-                        Ok(current_cpu_time) => {
-                            let delta_cpu_time = current_cpu_time.saturating_sub(*prev_cpu_time);
-                            let delta_wall_time = current_time.duration_since(*prev_time).as_secs_f64();
-                            
-                            // Calculate CPU percentage
-                            // CPU% = (delta_cpu_time / delta_wall_time) * 100 / num_cores
-                            // Convert jiffies to seconds using system HZ
-                            let cpu_time_seconds = delta_cpu_time as f64 / hz;
-                            
-                            if delta_wall_time > 0.0 {
-                                let cpu_percent = (cpu_time_seconds / delta_wall_time) * 100.0 / num_cores as f64;
-                                proc.set_cpu_percent(cpu_percent as f32);
-                            } else {
-                                proc.set_cpu_percent(0.0);
-                            }
-                            
-                            // Update previous CPU time
-                            previous_cpu_times.insert(pid, (current_cpu_time, current_time));
+                if proc.status_unreadable {
+                    keep_last_ids(&mut proc, processes.get(&pid));
+                }
+                match previous_cpu_times.get(&pid) {
+                    Some(prev) => {
+                        if prev.name != proc.name {
+                            delta.renamed.push((pid, prev.name.clone(), proc.name.clone()));
                         }
-                        Err(_) => {
-                            // If we can't get CPU time, keep previous value or set to 0
-                            proc.set_cpu_percent(0.0);
+                        // Only UIDs actually read from status count: a fallback is no transition
+                        if let Some(old_uid) = prev.uid
+                            && !proc.status_unreadable
+                            && old_uid != proc.user_id
+                        {
+                            delta.uid_changed.push((pid, old_uid, proc.user_id));
                         }
                     }
-                } else {
-                    // First time seeing this process - no CPU percentage yet
-                    // Store current CPU time for next refresh
-                    if let Ok(cpu_time) = Process::get_cpu_time_jiffies(pid) {
-                        previous_cpu_times.insert(pid, (cpu_time, current_time));
-                    }
-                    proc.set_cpu_percent(0.0);
+                    None if !first_refresh => delta.spawned.push((pid, proc.name.clone())),
+                    None => {}
                 }
+                sample.update_rates(source, &mut proc, previous_cpu_times);
                 
                 new_processes.insert(pid, proc);
-                successfully_loaded += 1;
+                delta.loaded += 1;
             }
             Err(e) => {
-                //Ignore the error if a process vanished between listing and reading its data
-                if !matches!(e, procfs::ProcError::NotFound(_)) {
-                    eprintln!("Warning: Could not fully read data for PID {}: {:?}", pid, e);
+                //A process that vanished between listing and reading its data is routine, not a warning
+                if matches!(e, procfs::ProcError::NotFound(_)) {
+                    log::debug!("PID {} exited before its data could be read", pid);
+                } else {
+                    log::warn!("Could not fully read data for PID {}: {:?}", pid, e);
                 }
             }
         }
@@ -141,5 +325,584 @@ pub fn refresh_processes(
     
     *processes = new_processes; //Replace the old process map with the new one
     
-    Ok(successfully_loaded)
+    Ok(delta)
+}
+
+// When status couldn't be read, proc only has the /proc/[pid] owner as its UIDs and no tgid.
+// If the last sample of the same process (same start time) had them, keep those instead.
+fn keep_last_ids(proc: &mut Process, last: Option<&Process>) {
+    let Some(last) = last.filter(|last| last.pcb_data.start_time_ticks == proc.pcb_data.start_time_ticks) else {
+        return;
+    };
+    proc.user_id = last.user_id;
+    proc.effective_uid = last.effective_uid;
+    proc.saved_uid = last.saved_uid;
+    proc.groups = last.groups.clone();
+    proc.tgid = last.tgid;
+    proc.parent_id = last.parent_id;
+}
+
+// Re-reads only pid, with the same CPU%/block I/O rate bookkeeping as a full refresh.
+// Returns false (and forgets pid) once it no longer exists.
+// It moves the system-wide jiffies baseline, so the CPU% of every other process is off
+// in the next full refresh; meant for watching one process on its own.
+pub fn refresh_pid_from(
+    source: &impl MonitorSource,
+    pid: u32,
+    processes: &mut HashMap<u32, Process>,
+    previous_cpu_times: &mut HashMap<u32, PreviousSample>,
+    previous_total_jiffies: &mut Option<u64>,
+    smoothing_alpha: f32,
+) -> Result<bool, String> {
+    let sample = SampleContext::new(source, previous_total_jiffies, smoothing_alpha);
+    match source.read_process(pid) {
+        Ok(mut proc) => {
+            sample.update_rates(source, &mut proc, previous_cpu_times);
+            processes.insert(pid, proc);
+            Ok(true)
+        }
+        Err(procfs::ProcError::NotFound(_)) => {
+            processes.remove(&pid);
+            previous_cpu_times.remove(&pid);
+            Ok(false)
+        }
+        Err(e) => Err(format!("Failed to read PID {}: {:?}", pid, e)),
+    }
+}
+
+// Values shared by every process read in one refresh
+struct SampleContext {
+    time: Instant,
+    num_cores: f32,
+    hz: f64,
+    total_delta: Option<u64>, // System-wide jiffies elapsed since the last refresh (None on the first refresh or if /proc/stat is unreadable)
+    smoothing_alpha: f32,
+}
+
+impl SampleContext {
+    // Takes the system-wide jiffies now, moving previous_total_jiffies on to them
+    fn new(source: &impl MonitorSource, previous_total_jiffies: &mut Option<u64>, smoothing_alpha: f32) -> Self {
+        let total_jiffies = source.total_jiffies();
+        let total_delta = match (total_jiffies, *previous_total_jiffies) {
+            (Some(now), Some(before)) => Some(now.saturating_sub(before)),
+            _ => None,
+        };
+        *previous_total_jiffies = total_jiffies;
+        SampleContext {
+            time: source.now(),
+            num_cores: source.num_cores(),
+            hz: source.hz(),
+            total_delta,
+            smoothing_alpha,
+        }
+    }
+
+    // Sets proc's CPU%, smoothed CPU%, CPU% change and block I/O wait from its counters at the previous sample,
+    // then stores the current counters as the next baseline. A process seen for the first time gets 0% CPU.
+    fn update_rates(&self, source: &impl MonitorSource, proc: &mut Process, previous_cpu_times: &mut HashMap<u32, PreviousSample>) {
+        let pid = proc.process_id;
+        // Calculate CPU percentage if we have previous data
+        if let Some(prev) = previous_cpu_times.get(&pid) {
+            // Get current CPU time
+            match source.cpu_time_jiffies(pid) {
+                Ok(current_cpu_time) => {
+                    let delta_cpu_time = current_cpu_time.saturating_sub(prev.cpu_time);
+                    let delta_wall_time = self.time.duration_since(prev.time).as_secs_f64();
+                    
+                    // Block I/O wait = milliseconds spent waiting on block devices per second of wall time
+                    let delta_blkio = proc.pcb_data.blkio_delay_ticks.saturating_sub(prev.blkio_ticks);
+                    let blkio_seconds = delta_blkio as f64 / self.hz;
+                    
+                    proc.set_cpu_percent(match self.total_delta {
+                        Some(total_delta) => cpu_percent_from_jiffies(delta_cpu_time, total_delta, self.num_cores),
+                        None => 0.0,
+                    });
+                    if delta_wall_time > 0.0 {
+                        proc.pcb_data.blkio_wait_ms_per_sec = (blkio_seconds * 1000.0 / delta_wall_time) as f32;
+                    }
+                    
+                    // Blend into the moving average, seeding it with the first real sample
+                    let raw = proc.pcb_data.cpu_percent;
+                    let smoothed = match prev.cpu_smoothed {
+                        Some(old) => self.smoothing_alpha * raw + (1.0 - self.smoothing_alpha) * old,
+                        None => raw,
+                    };
+                    proc.pcb_data.cpu_percent_smoothed = smoothed;
+                    proc.pcb_data.cpu_percent_delta = prev.cpu_percent.map_or(0.0, |before| raw - before);
+
+                    // CPU time can't go down, so a drop means the PID was reused: start watching afresh
+                    let (first_cpu_time, first_seen) = if current_cpu_time >= prev.first_cpu_time {
+                        (prev.first_cpu_time, prev.first_seen)
+                    } else {
+                        (current_cpu_time, self.time)
+                    };
+                    let watched_secs = self.time.duration_since(first_seen).as_secs_f64();
+                    if watched_secs > 0.0 {
+                        let cpu_secs = (current_cpu_time - first_cpu_time) as f64 / self.hz;
+                        proc.pcb_data.cpu_percent_since_seen = Some((cpu_secs / watched_secs * 100.0) as f32);
+                    }
+                    
+                    // Update previous counters
+                    previous_cpu_times.insert(pid, PreviousSample {
+                        cpu_time: current_cpu_time,
+                        blkio_ticks: proc.pcb_data.blkio_delay_ticks,
+                        name: proc.name.clone(),
+                        uid: if proc.status_unreadable { prev.uid } else { Some(proc.user_id) },
+                        cpu_smoothed: Some(smoothed),
+                        cpu_percent: Some(raw),
+                        time: self.time,
+                        first_cpu_time,
+                        first_seen,
+                    });
+                }
+                Err(_) => {
+                    // If we can't get CPU time, keep previous value or set to 0
+                    proc.set_cpu_percent(0.0);
+                }
+            }
+        } else {
+            // First time seeing this process - no CPU percentage yet
+            // Store current CPU time for next refresh
+            if let Ok(cpu_time) = source.cpu_time_jiffies(pid) {
+                previous_cpu_times.insert(pid, PreviousSample {
+                    cpu_time,
+                    blkio_ticks: proc.pcb_data.blkio_delay_ticks,
+                    name: proc.name.clone(),
+                    uid: (!proc.status_unreadable).then_some(proc.user_id),
+                    cpu_smoothed: None,
+                    cpu_percent: None,
+                    time: self.time,
+                    first_cpu_time: cpu_time,
+                    first_seen: self.time,
+                });
+            }
+            proc.set_cpu_percent(0.0);
+        }
+    }
+}
+
+// Total time the system has spent suspended since boot: the boot clock keeps counting through
+// suspend while the monotonic clock stops. None if either clock can't be read.
+pub fn time_suspended() -> Option<Duration> {
+    use nix::time::{clock_gettime, ClockId};
+    let boot = Duration::from(clock_gettime(ClockId::CLOCK_BOOTTIME).ok()?);
+    let monotonic = Duration::from(clock_gettime(ClockId::CLOCK_MONOTONIC).ok()?);
+    Some(boot.saturating_sub(monotonic))
+}
+
+// Checks that /proc is a mounted, listable procfs before anything tries to read processes from it
+pub fn check_proc_available() -> Result<(), String> {
+    std::fs::read_dir("/proc").map_err(|e| format!("cannot list /proc: {}", e))?;
+    // An empty directory (procfs not mounted) or a non-Linux /proc has no self/stat
+    if !std::path::Path::new("/proc/self/stat").exists() {
+        return Err("/proc exists but is not a Linux procfs mount".to_string());
+    }
+    Ok(())
+}
+
+// Why the process list may be incomplete: /proc mounted with hidepid (other users' processes
+// hidden) or subset=pid. None when every process should be visible.
+pub fn proc_restriction() -> Option<String> {
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+    mounts.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [_, "/proc", "proc", options, ..] = fields.as_slice() else {
+            return None;
+        };
+        options.split(',').find_map(|option| match option.split_once('=') {
+            Some(("hidepid", "0" | "off")) => None,
+            Some(("hidepid", level)) => Some(format!(
+                "/proc is mounted with hidepid={}: processes of other users are hidden unless running as root",
+                level
+            )),
+            Some(("subset", "pid")) => Some("/proc is mounted with subset=pid: system-wide figures may be missing".to_string()),
+            _ => None,
+        })
+    })
+}
+
+// Checks whether a process has exited. A zombie counts as exited: it has finished running
+// and only lingers until its parent reaps it.
+pub fn has_exited(pid: u32) -> Result<bool, String> {
+    let stat = procfs::process::Process::new(pid as i32).and_then(|p| p.stat());
+    match stat {
+        Ok(stat) => Ok(matches!(stat.state, 'Z' | 'X')),
+        Err(procfs::ProcError::NotFound(_)) => Ok(true),
+        Err(e) => Err(format!("Failed to check PID {}: {}", pid, e)),
+    }
+}
+
+// Why a zombie exited, from the exit code the kernel keeps in its stat until the parent reaps it:
+// "exited with status 1" or "killed by SIGKILL". None if pid isn't a zombie or the code isn't readable.
+pub fn exit_reason(pid: u32) -> Option<String> {
+    let stat = procfs::process::Process::new(pid as i32).and_then(|p| p.stat()).ok()?;
+    if stat.state != 'Z' {
+        return None;
+    }
+    Some(match StateCause::from_wait_status(stat.exit_code?) {
+        StateCause::ExitedWith(status) => format!("exited with status {}", status),
+        StateCause::KilledBy { signal, .. } => format!("killed by {}", signal_name(signal)),
+        // A zombie can't be stopped; kept for completeness
+        StateCause::StoppedBy(signal) => format!("stopped by {}", signal_name(signal)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    /// One scripted process: what read_process and cpu_time_jiffies return for it
+    struct MockProcess {
+        name: String,
+        cpu_jiffies: u64,
+        blkio_ticks: u64,
+        uid: u32,
+        status_unreadable: bool, // Reads like a process whose status is denied: owner UID, no tgid
+        vanished: bool,          // Still listed, but gone by the time its data is read
+    }
+
+    /// Scripted /proc: tests change the values between refreshes
+    struct MockSource {
+        processes: RefCell<HashMap<u32, MockProcess>>,
+        total_jiffies: RefCell<u64>,
+        now: RefCell<Instant>,
+        cores: f32,
+    }
+
+    impl MockSource {
+        fn new(cores: f32) -> Self {
+            MockSource {
+                processes: RefCell::new(HashMap::new()),
+                total_jiffies: RefCell::new(1_000),
+                now: RefCell::new(Instant::now()),
+                cores,
+            }
+        }
+
+        fn spawn(&self, pid: u32, name: &str) {
+            self.processes.borrow_mut().insert(pid, MockProcess {
+                name: name.to_string(),
+                cpu_jiffies: 0,
+                blkio_ticks: 0,
+                uid: 1000,
+                status_unreadable: false,
+                vanished: false,
+            });
+        }
+
+        fn exit(&self, pid: u32) {
+            self.processes.borrow_mut().remove(&pid);
+        }
+
+        /// Let `secs` of wall time pass, during which the whole system used `total_jiffies`
+        /// and each listed pid used the given jiffies
+        fn advance(&self, secs: u64, total_jiffies: u64, usage: &[(u32, u64)]) {
+            *self.now.borrow_mut() += Duration::from_secs(secs);
+            *self.total_jiffies.borrow_mut() += total_jiffies;
+            let mut processes = self.processes.borrow_mut();
+            for &(pid, jiffies) in usage {
+                processes.get_mut(&pid).unwrap().cpu_jiffies += jiffies;
+            }
+        }
+    }
+
+    impl MonitorSource for MockSource {
+        fn pids(&self) -> Result<Vec<u32>, String> {
+            Ok(self.processes.borrow().keys().copied().collect())
+        }
+
+        fn read_process(&self, pid: u32) -> Result<Process, procfs::ProcError> {
+            let processes = self.processes.borrow();
+            let mock = processes
+                .get(&pid)
+                .filter(|m| !m.vanished)
+                .ok_or(procfs::ProcError::NotFound(None))?;
+            let mut process = Process {
+                process_id: pid,
+                tgid: (!mock.status_unreadable).then_some(pid),
+                user_id: mock.uid,
+                effective_uid: mock.uid,
+                saved_uid: mock.uid,
+                groups: Vec::new(),
+                name: mock.name.clone(),
+                cmdline: Vec::new(),
+                parent_id: Some(1),
+                exe_deleted: false,
+                status_unreadable: mock.status_unreadable,
+                pcb_data: Default::default(),
+            };
+            process.pcb_data.state = 'S';
+            process.pcb_data.blkio_delay_ticks = mock.blkio_ticks;
+            Ok(process)
+        }
+
+        fn cpu_time_jiffies(&self, pid: u32) -> Result<u64, procfs::ProcError> {
+            self.processes
+                .borrow()
+                .get(&pid)
+                .map(|m| m.cpu_jiffies)
+                .ok_or(procfs::ProcError::NotFound(None))
+        }
+
+        fn total_jiffies(&self) -> Option<u64> {
+            Some(*self.total_jiffies.borrow())
+        }
+
+        fn num_cores(&self) -> f32 {
+            self.cores
+        }
+
+        fn hz(&self) -> f64 {
+            100.0
+        }
+
+        fn now(&self) -> Instant {
+            *self.now.borrow()
+        }
+    }
+
+    /// Manager-like state carried between refreshes
+    #[derive(Default)]
+    struct Monitor {
+        processes: HashMap<u32, Process>,
+        previous: HashMap<u32, PreviousSample>,
+        previous_total_jiffies: Option<u64>,
+    }
+
+    impl Monitor {
+        fn refresh(&mut self, source: &MockSource, alpha: f32) -> RefreshDelta {
+            refresh_processes_from(source, &mut self.processes, &mut self.previous, &mut self.previous_total_jiffies, alpha).unwrap()
+        }
+
+        fn cpu(&self, pid: u32) -> f32 {
+            self.processes[&pid].pcb_data.cpu_percent
+        }
+    }
+
+    #[test]
+    fn cpu_percent_from_jiffies_matches_top() {
+        // 4 cores for 1s at HZ=100: 400 system jiffies. A process using 50 of them kept half a core busy.
+        assert_eq!(cpu_percent_from_jiffies(50, 400, 4.0), 50.0);
+        // One fully busy core reads 100%, all four read 400%
+        assert_eq!(cpu_percent_from_jiffies(100, 400, 4.0), 100.0);
+        assert_eq!(cpu_percent_from_jiffies(400, 400, 4.0), 400.0);
+        assert_eq!(cpu_percent_from_jiffies(0, 400, 4.0), 0.0);
+        // Sampling skew can't push it past every core being busy
+        assert_eq!(cpu_percent_from_jiffies(410, 400, 4.0), 400.0);
+        // No elapsed system time: nothing to measure against
+        assert_eq!(cpu_percent_from_jiffies(10, 0, 4.0), 0.0);
+    }
+
+    #[test]
+    fn refresh_computes_cpu_percent_from_jiffy_deltas() {
+        let source = MockSource::new(4.0);
+        source.spawn(10, "idle");
+        source.spawn(11, "half");
+        source.spawn(12, "busy");
+        let mut monitor = Monitor::default();
+
+        // First sample: nothing to compare against yet
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.cpu(11), 0.0);
+
+        // 1s on 4 cores at HZ=100 is 400 system jiffies
+        source.advance(1, 400, &[(11, 50), (12, 400)]);
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.cpu(10), 0.0);
+        assert_eq!(monitor.cpu(11), 50.0);
+        assert_eq!(monitor.cpu(12), 400.0);
+    }
+
+    #[test]
+    fn refresh_smooths_cpu_percent() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "worker");
+        let mut monitor = Monitor::default();
+        monitor.refresh(&source, 0.5);
+
+        // The first real sample seeds the average
+        source.advance(1, 100, &[(10, 100)]);
+        monitor.refresh(&source, 0.5);
+        assert_eq!(monitor.processes[&10].pcb_data.cpu_percent_smoothed, 100.0);
+
+        // Then each new sample moves it halfway (alpha = 0.5)
+        source.advance(1, 100, &[(10, 0)]);
+        monitor.refresh(&source, 0.5);
+        assert_eq!(monitor.cpu(10), 0.0);
+        assert_eq!(monitor.processes[&10].pcb_data.cpu_percent_smoothed, 50.0);
+    }
+
+    #[test]
+    fn refresh_tracks_cpu_percent_delta() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "spiky");
+        let mut monitor = Monitor::default();
+        monitor.refresh(&source, 1.0);
+
+        // The first real sample has nothing to compare with
+        source.advance(1, 100, &[(10, 10)]);
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.processes[&10].pcb_data.cpu_percent_delta, 0.0);
+
+        source.advance(1, 100, &[(10, 90)]);
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.processes[&10].pcb_data.cpu_percent_delta, 80.0);
+
+        source.advance(1, 100, &[(10, 30)]);
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.processes[&10].pcb_data.cpu_percent_delta, -60.0);
+    }
+
+    #[test]
+    fn refresh_averages_cpu_since_first_seen() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "bursty");
+        let mut monitor = Monitor::default();
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.processes[&10].avg_cpu_percent_since_seen(), None);
+
+        // 1s at 90%, then 3s idle: the instantaneous value drops to 0, the session average to 22.5%
+        source.advance(1, 100, &[(10, 90)]);
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.processes[&10].avg_cpu_percent_since_seen(), Some(90.0));
+        source.advance(3, 300, &[]);
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.cpu(10), 0.0);
+        assert_eq!(monitor.processes[&10].avg_cpu_percent_since_seen(), Some(22.5));
+    }
+
+    #[test]
+    fn refresh_computes_blkio_wait_rate() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "dd");
+        let mut monitor = Monitor::default();
+        monitor.refresh(&source, 1.0);
+
+        // 20 ticks at HZ=100 = 200ms of I/O wait spread over 2s of wall time
+        source.processes.borrow_mut().get_mut(&10).unwrap().blkio_ticks += 20;
+        source.advance(2, 200, &[]);
+        monitor.refresh(&source, 1.0);
+        assert_eq!(monitor.processes[&10].pcb_data.blkio_wait_ms_per_sec, 100.0);
+    }
+
+    #[test]
+    fn refresh_pid_updates_only_that_process() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "watched");
+        source.spawn(11, "other");
+        let mut monitor = Monitor::default();
+        monitor.refresh(&source, 1.0);
+
+        source.advance(1, 100, &[(10, 25), (11, 100)]);
+        let alive = refresh_pid_from(&source, 10, &mut monitor.processes, &mut monitor.previous, &mut monitor.previous_total_jiffies, 1.0).unwrap();
+        assert!(alive);
+        assert_eq!(monitor.cpu(10), 25.0);
+        // Not re-read, so still at its first-sample 0%
+        assert_eq!(monitor.cpu(11), 0.0);
+
+        source.exit(10);
+        let alive = refresh_pid_from(&source, 10, &mut monitor.processes, &mut monitor.previous, &mut monitor.previous_total_jiffies, 1.0).unwrap();
+        assert!(!alive);
+        assert!(!monitor.processes.contains_key(&10));
+        assert!(!monitor.previous.contains_key(&10));
+    }
+
+    #[test]
+    fn refresh_drops_vanished_processes() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "init");
+        source.spawn(11, "short-lived");
+        let mut monitor = Monitor::default();
+        let delta = monitor.refresh(&source, 1.0);
+        assert_eq!(delta.loaded, 2);
+        assert!(delta.spawned.is_empty());
+
+        // 11 exits, 12 starts, and 13 is listed but exits before it can be read
+        source.exit(11);
+        source.spawn(12, "new");
+        source.spawn(13, "racy");
+        source.processes.borrow_mut().get_mut(&13).unwrap().vanished = true;
+        source.advance(1, 100, &[]);
+        let delta = monitor.refresh(&source, 1.0);
+
+        assert_eq!(delta.loaded, 2);
+        assert_eq!(delta.spawned, vec![(12, "new".to_string())]);
+        let mut pids: Vec<u32> = monitor.processes.keys().copied().collect();
+        pids.sort();
+        assert_eq!(pids, vec![10, 12]);
+        let mut remembered: Vec<u32> = monitor.previous.keys().copied().collect();
+        remembered.sort();
+        assert_eq!(remembered, vec![10, 12]);
+    }
+
+    #[test]
+    fn session_io_accumulates_deltas_until_exit() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "db");
+        let with_io = |read: u64, write: u64| {
+            let mut process = source.read_process(10).unwrap();
+            process.pcb_data.io_read_bytes = Some(read);
+            process.pcb_data.io_write_bytes = Some(write);
+            HashMap::from([(10, process)])
+        };
+        let mut session_io = HashMap::new();
+
+        // I/O done before the tool started isn't counted
+        update_session_io(&with_io(5_000, 1_000), &mut session_io);
+        assert_eq!((session_io[&10].read_bytes, session_io[&10].write_bytes), (0, 0));
+
+        update_session_io(&with_io(7_000, 1_500), &mut session_io);
+        update_session_io(&with_io(8_000, 1_500), &mut session_io);
+        assert_eq!((session_io[&10].read_bytes, session_io[&10].write_bytes), (3_000, 500));
+
+        update_session_io(&HashMap::new(), &mut session_io);
+        assert!(session_io.is_empty());
+    }
+
+    #[test]
+    fn refresh_reports_renames() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "python3");
+        let mut monitor = Monitor::default();
+        monitor.refresh(&source, 1.0);
+
+        source.processes.borrow_mut().get_mut(&10).unwrap().name = "worker-1".to_string();
+        source.advance(1, 100, &[]);
+        let delta = monitor.refresh(&source, 1.0);
+        assert_eq!(delta.renamed, vec![(10, "python3".to_string(), "worker-1".to_string())]);
+    }
+
+    #[test]
+    fn unreadable_status_keeps_last_uids() {
+        let source = MockSource::new(1.0);
+        source.spawn(10, "sshd");
+        let mut monitor = Monitor::default();
+        monitor.refresh(&source, 1.0);
+
+        // status denied: the fallback owner UID is no setuid transition
+        {
+            let mut processes = source.processes.borrow_mut();
+            let mock = processes.get_mut(&10).unwrap();
+            mock.uid = 0;
+            mock.status_unreadable = true;
+        }
+        source.advance(1, 100, &[]);
+        let delta = monitor.refresh(&source, 1.0);
+        assert!(delta.uid_changed.is_empty());
+        assert_eq!(monitor.processes[&10].user_id, 1000);
+        assert_eq!(monitor.processes[&10].tgid, Some(10));
+
+        // Readable again with a real change: compared against the last UID actually read
+        {
+            let mut processes = source.processes.borrow_mut();
+            let mock = processes.get_mut(&10).unwrap();
+            mock.uid = 33;
+            mock.status_unreadable = false;
+        }
+        source.advance(1, 100, &[]);
+        let delta = monitor.refresh(&source, 1.0);
+        assert_eq!(delta.uid_changed, vec![(10, 1000, 33)]);
+    }
 }
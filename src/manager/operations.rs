@@ -1,30 +1,158 @@
+use nix::errno::Errno;
 use nix::sys::signal::{self, Signal};
 use nix::unistd::Pid;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use libc::{setpriority, PRIO_PROCESS};
+use crate::manager::audit;
+use crate::manager::batch;
+use crate::manager::escalation;
+use crate::manager::monitoring;
 use crate::manager::permissions;
 use crate::manager::Manager;
+use crate::process::{Process, RlimitResource};
+
+
+//Delivers a signal; on EPERM, retries through the manager's escalation method if one is configured.
+//Checks the PID itself too, so no caller can turn a bad u32 into a group or broadcast signal.
+fn send_signal(manager: &Manager, pid: u32, sig: Signal) -> Result<(), String> {
+    permissions::check_single_process_pid(pid)?;
+    match signal::kill(Pid::from_raw(pid as i32), sig) {
+        Ok(()) => Ok(()),
+        Err(Errno::EPERM) => match manager.privilege_escalation {
+            Some(method) => escalation::kill(manager, method, pid, sig),
+            None => Err(Errno::EPERM.to_string()),
+        },
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+
+//Takes a token from the manager's rate limiter, failing once destructive operations come too fast
+fn check_rate_limit(manager: &Manager) -> Result<(), String> {
+    if manager.rate_limiter.borrow_mut().try_acquire() {
+        Ok(())
+    } else {
+        Err("rate limit exceeded, slow down".to_string())
+    }
+}
+
+
+//In dry-run mode, logs the action that would have been taken and returns true so the caller skips it
+fn skip_for_dry_run(manager: &Manager, op: &str, pid: u32, action: &str) -> bool {
+    if !manager.dry_run {
+        return false;
+    }
+    log::info!("[dry run] would {} (PID {})", action, pid);
+    audit::record_result(manager, op, pid, "dry-run");
+    true
+}
 
 
 //Kill (Force terminate)
 pub fn kill_process(manager: &Manager, pid: u32) -> Result<(), String> {
     permissions::check_admin_privilege(manager)?;
+    permissions::check_not_protected(manager, pid)?;
+    if skip_for_dry_run(manager, "kill", pid, "send SIGKILL") {
+        return Ok(());
+    }
+    check_rate_limit(manager)?;
 
-    let nix_pid = Pid::from_raw(pid as i32);
+    send_signal(manager, pid, Signal::SIGKILL)
+        .map_err(|e| format!("Failed to send SIGKILL to PID {}: {}", pid, e))?;
 
-    signal::kill(nix_pid, Signal::SIGKILL)
-        .map_err(|e| format!("Failed to send SIGKILL to PID {}: {}", pid, e))
+    audit::record(manager, "kill", pid);
+    Ok(())
 }
 
 //Terminate (Graceful stop)
 //Sends SIGTERM, giving process a chance to shut down cleanly
 pub fn terminate_process(manager: &Manager, pid: u32) -> Result<(), String> {
     permissions::check_admin_privilege(manager)?;
+    permissions::check_not_protected(manager, pid)?;
+    if skip_for_dry_run(manager, "terminate", pid, "send SIGTERM") {
+        return Ok(());
+    }
+    check_rate_limit(manager)?;
 
-    let nix_pid = Pid::from_raw(pid as i32);
+    send_signal(manager, pid, Signal::SIGTERM)
+        .map_err(|e| format!("Failed to send SIGTERM to PID {}: {}", pid, e))?;
 
-    signal::kill(nix_pid, Signal::SIGTERM)
-        .map_err(|e| format!("Failed to send SIGTERM to PID {}: {}", pid, e))
+    audit::record(manager, "terminate", pid);
+    Ok(())
+}
+
+
+//Default time a process gets to honour SIGTERM before force_terminate sends SIGKILL
+pub const DEFAULT_FORCE_TERMINATE_GRACE: Duration = Duration::from_secs(3);
+
+//How long after SIGKILL force_terminate waits before declaring a process unkillable
+const KILL_SETTLE_TIME: Duration = Duration::from_secs(1);
+
+//How a force_terminate ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillOutcome {
+    TermSucceeded,              // Exited after SIGTERM
+    KillRequired,               // Ignored SIGTERM for the whole grace period; SIGKILL finished it
+    Unkillable { state: char }, // Still alive after SIGKILL, in this state
+}
+
+impl KillOutcome {
+    //One-line report for the frontends, e.g. "PID 1234 needed SIGKILL (ignored SIGTERM)"
+    pub fn describe(self, pid: u32) -> String {
+        match self {
+            KillOutcome::TermSucceeded => format!("PID {} exited after SIGTERM", pid),
+            KillOutcome::KillRequired => format!("PID {} ignored SIGTERM and was killed with SIGKILL", pid),
+            KillOutcome::Unkillable { state: 'D' } => format!(
+                "PID {} survived SIGKILL: it is in uninterruptible sleep (D), waiting on kernel I/O such as a hung NFS mount \
+                 or a failing disk. It will die as soon as that I/O completes or fails; no signal can end it sooner.",
+                pid
+            ),
+            KillOutcome::Unkillable { state } => format!(
+                "PID {} survived SIGKILL (state {}); it may be stuck in the kernel or still tearing down",
+                pid, state
+            ),
+        }
+    }
+}
+
+//Terminate hard: SIGTERM, wait; SIGTERM again; once the grace period is over, SIGKILL.
+//Blocks for up to grace plus a second. In dry-run mode only the first SIGTERM is logged.
+pub fn force_terminate(manager: &Manager, pid: u32, grace: Duration) -> Result<KillOutcome, String> {
+    permissions::check_admin_privilege(manager)?;
+    permissions::check_not_protected(manager, pid)?;
+    if manager.dry_run {
+        terminate_process(manager, pid)?;
+        return Ok(KillOutcome::TermSucceeded);
+    }
+
+    //Two SIGTERMs, half the grace each: some programs only start shutting down on the second
+    //A signal failing because the process exited just after the last check isn't an error
+    let exited_meanwhile = |result: Result<(), String>| match result {
+        Ok(()) => Ok(false),
+        Err(e) => match monitoring::has_exited(pid) {
+            Ok(true) => Ok(true),
+            _ => Err(e),
+        },
+    };
+    for _ in 0..2 {
+        if exited_meanwhile(terminate_process(manager, pid))? || manager.wait_for_exit(pid, grace / 2)? {
+            return Ok(KillOutcome::TermSucceeded);
+        }
+    }
+
+    if exited_meanwhile(kill_process(manager, pid))? {
+        return Ok(KillOutcome::TermSucceeded);
+    }
+    if manager.wait_for_exit(pid, KILL_SETTLE_TIME)? {
+        return Ok(KillOutcome::KillRequired);
+    }
+    let state = procfs::process::Process::new(pid as i32)
+        .and_then(|p| p.stat())
+        .map(|stat| stat.state)
+        .unwrap_or('?');
+    Ok(KillOutcome::Unkillable { state })
 }
 
 
@@ -32,11 +160,17 @@ pub fn terminate_process(manager: &Manager, pid: u32) -> Result<(), String> {
 //Fully pauses a process without killing it
 pub fn pause_process(manager: &Manager, pid: u32) -> Result<(), String> {
     permissions::check_admin_privilege(manager)?;
+    permissions::check_not_protected(manager, pid)?;
+    if skip_for_dry_run(manager, "pause", pid, "send SIGSTOP") {
+        return Ok(());
+    }
+    check_rate_limit(manager)?;
 
-    let nix_pid = Pid::from_raw(pid as i32);
+    send_signal(manager, pid, Signal::SIGSTOP)
+        .map_err(|e| format!("Failed to pause PID {}: {}", pid, e))?;
 
-    signal::kill(nix_pid, Signal::SIGSTOP)
-        .map_err(|e| format!("Failed to pause PID {}: {}", pid, e))
+    audit::record(manager, "pause", pid);
+    Ok(())
 }
 
 
@@ -44,29 +178,303 @@ pub fn pause_process(manager: &Manager, pid: u32) -> Result<(), String> {
 //Resumes a paused process
 pub fn resume_process(manager: &Manager, pid: u32) -> Result<(), String> {
     permissions::check_admin_privilege(manager)?;
+    if skip_for_dry_run(manager, "resume", pid, "send SIGCONT") {
+        return Ok(());
+    }
 
-    let nix_pid = Pid::from_raw(pid as i32);
+    send_signal(manager, pid, Signal::SIGCONT)
+        .map_err(|e| format!("Failed to resume PID {}: {}", pid, e))?;
 
-    signal::kill(nix_pid, Signal::SIGCONT)
-        .map_err(|e| format!("Failed to resume PID {}: {}", pid, e))
+    audit::record(manager, "resume", pid);
+    Ok(())
+}
+
+
+//Notify child exit (SIGCHLD)
+//Nudges a parent into reaping its zombie children
+pub fn notify_child_exit(manager: &Manager, pid: u32) -> Result<(), String> {
+    permissions::check_admin_privilege(manager)?;
+    if skip_for_dry_run(manager, "sigchld", pid, "send SIGCHLD") {
+        return Ok(());
+    }
+
+    send_signal(manager, pid, Signal::SIGCHLD)
+        .map_err(|e| format!("Failed to send SIGCHLD to PID {}: {}", pid, e))?;
+
+    audit::record(manager, "sigchld", pid);
+    Ok(())
 }
 
 
 //Set Priority (nice value)
 pub fn set_priority(manager: &Manager, pid: u32, nice_value: i32) -> Result<(), String> {
     permissions::check_admin_privilege(manager)?;
+    //setpriority(PRIO_PROCESS, 0) would renice the manager itself (and renice_user gets here too)
+    permissions::check_single_process_pid(pid)?;
+    if skip_for_dry_run(manager, "renice", pid, &format!("set nice value {}", nice_value)) {
+        return Ok(());
+    }
 
     let res = unsafe {
-        setpriority(PRIO_PROCESS, pid as u32, nice_value)
+        setpriority(PRIO_PROCESS, pid, nice_value)
     };
 
     if res == 0 {
-        Ok(())
+        audit::record(manager, "renice", pid);
+        return Ok(());
+    }
+
+    let error = std::io::Error::last_os_error();
+    //EPERM: another user's process; EACCES: raising priority needs CAP_SYS_NICE
+    let denied = matches!(error.raw_os_error(), Some(libc::EPERM) | Some(libc::EACCES));
+    match manager.privilege_escalation {
+        Some(method) if denied => {
+            escalation::renice(manager, method, pid, nice_value)
+                .map_err(|e| format!("Failed to set nice value for PID {}: {}", pid, e))?;
+            audit::record(manager, "renice", pid);
+            Ok(())
+        }
+        _ => Err(format!("Failed to set nice value for PID {}: {}", pid, error)),
+    }
+}
+
+
+//Set a process's soft and hard limit for one resource with prlimit; pass libc::RLIM_INFINITY for unlimited.
+//Raising the hard limit, or changing another user's process, needs root (CAP_SYS_RESOURCE).
+pub fn set_rlimit(manager: &Manager, pid: u32, resource: RlimitResource, soft: u64, hard: u64) -> Result<(), String> {
+    permissions::check_admin_privilege(manager)?;
+    //prlimit(0) would change the manager's own limits
+    permissions::check_single_process_pid(pid)?;
+    if soft > hard {
+        return Err(format!(
+            "Soft limit ({}) can't be above the hard limit ({})",
+            resource.format_value(Some(soft)),
+            resource.format_value(Some(hard))
+        ));
+    }
+    let limit_text = |v: u64| resource.format_value((v != libc::RLIM_INFINITY).then_some(v));
+    let action = format!("set {} limit to {} soft / {} hard", resource.label().to_lowercase(), limit_text(soft), limit_text(hard));
+    if skip_for_dry_run(manager, "rlimit", pid, &action) {
+        return Ok(());
+    }
+
+    let raw_resource = match resource {
+        RlimitResource::OpenFiles => libc::RLIMIT_NOFILE,
+        RlimitResource::AddressSpace => libc::RLIMIT_AS,
+        RlimitResource::CpuTime => libc::RLIMIT_CPU,
+    };
+    let new_limit = libc::rlimit { rlim_cur: soft, rlim_max: hard };
+    let res = unsafe {
+        libc::prlimit(pid as libc::pid_t, raw_resource, &new_limit, std::ptr::null_mut())
+    };
+
+    if res == 0 {
+        audit::record(manager, "rlimit", pid);
+        return Ok(());
+    }
+
+    let error = std::io::Error::last_os_error();
+    let reason = match error.raw_os_error() {
+        Some(libc::EPERM) => "permission denied (raising the hard limit or changing another user's process needs root)".to_string(),
+        Some(libc::ESRCH) => "no such process".to_string(),
+        //e.g. an open-files limit above fs.nr_open
+        Some(libc::EINVAL) => "the kernel rejected the value".to_string(),
+        _ => error.to_string(),
+    };
+    Err(format!("Failed to {} for PID {}: {}", action, pid, reason))
+}
+
+
+//Send a signal to every process whose cgroup path contains cgroup_substr (e.g. "nginx.service"),
+//so a whole service can be stopped or restarted at once. Returns the PIDs signalled.
+pub fn signal_cgroup(manager: &Manager, cgroup_substr: &str, sig: Signal) -> Result<Vec<u32>, String> {
+    permissions::check_admin_privilege(manager)?;
+    if cgroup_substr.is_empty() {
+        return Err("Cgroup pattern must not be empty".to_string());
+    }
+
+    let mut pids: Vec<u32> = manager.iter()
+        .map(|p| p.process_id)
+        .filter(|&pid| Process::cgroup_paths(pid).iter().any(|path| path.contains(cgroup_substr)))
+        .collect();
+    pids.sort_unstable();
+    if pids.is_empty() {
+        return Err(format!("No processes in cgroup matching \"{}\"", cgroup_substr));
+    }
+
+    let mut signalled = Vec::new();
+    let mut failures = Vec::new();
+    for pid in pids {
+        let result = permissions::check_not_protected(manager, pid).and_then(|()| {
+            if skip_for_dry_run(manager, "signal", pid, &format!("send {}", sig)) {
+                return Ok(());
+            }
+            check_rate_limit(manager)?;
+            send_signal(manager, pid, sig)
+                .map_err(|e| format!("Failed to send {} to PID {}: {}", sig, pid, e))?;
+            audit::record(manager, "signal", pid);
+            Ok(())
+        });
+        match result {
+            Ok(()) => signalled.push(pid),
+            Err(e) => failures.push(e),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(signalled)
     } else {
         Err(format!(
-            "Failed to set nice value for PID {}: {}",
-            pid,
-            std::io::Error::last_os_error()
+            "Signalled {} of {} processes in cgroup \"{}\"; {} failed: {}",
+            signalled.len(),
+            signalled.len() + failures.len(),
+            cgroup_substr,
+            failures.len(),
+            failures.join("; ")
         ))
     }
 }
+
+
+//Root of the cgroup v2 hierarchy
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+//Move a process into a cgroup by writing its PID to <cgroup>/cgroup.procs (cgroups v2 only).
+//cgroup_path may be absolute ("/sys/fs/cgroup/system.slice/foo.service") or relative to the
+//cgroup root ("/system.slice/foo.service"); either way it must resolve to a cgroup under /sys/fs/cgroup.
+pub fn move_to_cgroup(manager: &Manager, pid: u32, cgroup_path: &str) -> Result<(), String> {
+    permissions::check_admin_privilege(manager)?;
+    //Writing 0 to cgroup.procs moves the writer, i.e. the manager itself
+    permissions::check_single_process_pid(pid)?;
+    let cgroup = resolve_cgroup(cgroup_path)?;
+    let procs_file = cgroup.join("cgroup.procs");
+
+    if skip_for_dry_run(manager, "move-cgroup", pid, &format!("move to cgroup {}", cgroup.display())) {
+        return Ok(());
+    }
+
+    std::fs::write(&procs_file, pid.to_string()).map_err(|e| {
+        let reason = match e.raw_os_error() {
+            Some(libc::EACCES) | Some(libc::EPERM) => "permission denied (needs root or delegation of the cgroup)".to_string(),
+            Some(libc::ESRCH) => "no such process".to_string(),
+            _ => e.to_string(),
+        };
+        format!("Failed to move PID {} to {}: {}", pid, cgroup.display(), reason)
+    })?;
+
+    audit::record(manager, "move-cgroup", pid);
+    Ok(())
+}
+
+//Resolves a cgroup path (absolute or relative to the cgroup root) to a cgroup directory,
+//refusing anything outside /sys/fs/cgroup or without a cgroup.procs file
+pub(crate) fn resolve_cgroup(cgroup_path: &str) -> Result<PathBuf, String> {
+    let root = Path::new(CGROUP_ROOT);
+    if !root.join("cgroup.controllers").exists() {
+        return Err(format!("cgroups v2 is not mounted at {}", CGROUP_ROOT));
+    }
+
+    let requested = Path::new(cgroup_path);
+    let full_path = if requested.starts_with(root) {
+        requested.to_path_buf()
+    } else {
+        root.join(cgroup_path.trim_start_matches('/'))
+    };
+    //Resolve ".." and symlinks before checking the path stays inside the hierarchy
+    let cgroup = full_path
+        .canonicalize()
+        .map_err(|e| format!("Cgroup {} not found: {}", full_path.display(), e))?;
+    let procs_file = cgroup.join("cgroup.procs");
+    if !cgroup.starts_with(root) || !procs_file.exists() {
+        return Err(format!("{} is not a cgroup under {}", cgroup.display(), CGROUP_ROOT));
+    }
+    Ok(cgroup)
+}
+
+
+//Renice every process owned by a user (e.g. to calm down a runaway account)
+//Returns the PIDs that were reniced; if any failed, returns an error summarizing each failure
+pub fn renice_user(manager: &Manager, uid: u32, nice_value: i32) -> Result<Vec<u32>, String> {
+    permissions::check_admin_privilege(manager)?;
+
+    //Processes whose status couldn't be read only have a guessed UID, so they're left out
+    let mut pids: Vec<u32> = manager.iter()
+        .filter(|p| p.user_id == uid && !p.status_unreadable)
+        .map(|p| p.process_id)
+        .collect();
+    pids.sort_unstable();
+
+    renice_all(manager, pids, nice_value, &format!("of UID {}", uid))
+}
+
+
+//Renice a process and, when recursive, every descendant of it as well (parents before children)
+pub fn set_priority_tree(manager: &Manager, pid: u32, nice_value: i32, recursive: bool) -> Result<Vec<u32>, String> {
+    if !recursive {
+        set_priority(manager, pid, nice_value)?;
+        return Ok(vec![pid]);
+    }
+
+    permissions::check_admin_privilege(manager)?;
+    let mut pids = vec![pid];
+    pids.extend(batch::subtree_descendants(manager, pid)?);
+
+    renice_all(manager, pids, nice_value, &format!("in the subtree of PID {}", pid))
+}
+
+
+//Sets the nice value of each PID, collecting successes; fails with a summary if any PID failed
+pub(crate) fn renice_all(manager: &Manager, pids: Vec<u32>, nice_value: i32, scope: &str) -> Result<Vec<u32>, String> {
+    let mut reniced = Vec::new();
+    let mut failures = Vec::new();
+    for pid in pids {
+        match set_priority(manager, pid, nice_value) {
+            Ok(()) => reniced.push(pid),
+            Err(e) => failures.push(e),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(reniced)
+    } else {
+        Err(format!(
+            "Reniced {} of {} processes {}; {} failed: {}",
+            reniced.len(),
+            reniced.len() + failures.len(),
+            scope,
+            failures.len(),
+            failures.join("; ")
+        ))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::{Privilege, User};
+
+    fn admin_manager() -> Manager {
+        Manager::new(User::new(0, "admin", Privilege::Admin)).unwrap()
+    }
+
+    #[test]
+    fn renice_refuses_pid_zero() {
+        let manager = admin_manager();
+        assert!(set_priority(&manager, 0, 19).is_err());
+        assert!(set_priority(&manager, i32::MAX as u32 + 1, 19).is_err());
+    }
+
+    #[test]
+    fn rlimit_refuses_pid_zero() {
+        let manager = admin_manager();
+        assert!(set_rlimit(&manager, 0, RlimitResource::OpenFiles, 16, 16).is_err());
+    }
+
+    #[test]
+    fn cgroup_move_refuses_pid_zero() {
+        let manager = admin_manager();
+        let err = move_to_cgroup(&manager, 0, "/").unwrap_err();
+        assert!(err.contains("not a single process"), "{}", err);
+    }
+}
@@ -1,11 +1,78 @@
+use std::collections::HashSet;
+
 use crate::manager::Manager;
 use crate::user::Privilege;
 
-//Checks if the active user has Admin privileges (Done before execution)
+//Checks if the active user has Admin privileges (Done before execution).
+//Every operation starts here, so read-only mode is enforced here too, ahead of privilege.
 pub fn check_admin_privilege(manager: &Manager) -> Result<(), String> {
+    check_not_read_only(manager)?;
     if manager.active_user.privilege == Privilege::Admin {
         Ok(())
     } else {
         Err("Permission denied: Admin privileges required to perform this action.".to_string())
     }
 }
+
+//Refuses everything while the manager is in read-only mode, even for admins
+pub fn check_not_read_only(manager: &Manager) -> Result<(), String> {
+    if manager.read_only {
+        Err("read-only mode".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+//Refuses PIDs that kill(2) doesn't read as one process: 0 is the caller's process group, and
+//anything above i32::MAX wraps to a negative pid_t (-1 is every process, others whole groups)
+pub fn check_single_process_pid(pid: u32) -> Result<(), String> {
+    if pid == 0 || pid > i32::MAX as u32 {
+        Err(format!("refusing PID {}: not a single process", pid))
+    } else {
+        Ok(())
+    }
+}
+
+//Refuses to signal init or the manager's own process, whatever the protected lists say
+//(they can be edited); only manager.force_protected overrides this. PIDs that aren't a single
+//process are refused even then.
+pub fn check_not_critical(manager: &Manager, pid: u32) -> Result<(), String> {
+    check_single_process_pid(pid)?;
+    if manager.force_protected {
+        return Ok(());
+    }
+    if pid == 1 {
+        Err("refusing to signal PID 1 (init): stopping it brings down the whole system".to_string())
+    } else if pid == std::process::id() {
+        Err(format!("refusing to signal PID {}: that is this process manager", pid))
+    } else {
+        Ok(())
+    }
+}
+
+//Refuses destructive operations on protected PIDs or process names unless manager.force_protected is set
+pub fn check_not_protected(manager: &Manager, pid: u32) -> Result<(), String> {
+    check_not_critical(manager, pid)?;
+    if manager.force_protected {
+        return Ok(());
+    }
+    let name = manager.processes.get(&pid).map(|p| p.name.as_str());
+    let protected = manager.protected_pids.contains(&pid)
+        || name.is_some_and(|name| manager.protected_names.contains(name));
+    if protected {
+        Err(format!("refusing to signal protected process {} ({})", pid, name.unwrap_or("unknown")))
+    } else {
+        Ok(())
+    }
+}
+
+//PIDs protected by default: init and the leader of our own session (usually the shell we were started from)
+pub fn default_protected_pids() -> HashSet<u32> {
+    let mut pids = HashSet::from([1]);
+    if let Ok(sid) = nix::unistd::getsid(None)
+        && sid.as_raw() > 1
+    {
+        pids.insert(sid.as_raw() as u32);
+    }
+    pids
+}
@@ -0,0 +1,188 @@
+use crate::process::Process;
+use crate::user;
+
+/// Field names accepted as `field:value` prefixes
+const FIELDS: [&str; 7] = ["name", "user", "uid", "pid", "state", "cmdline", "tty"];
+
+/// A single search condition
+#[derive(Debug, Clone)]
+enum Term {
+    /// Bare word: matched against name and cmdline (and PID/UID digits)
+    Text(String),
+    Name(String),
+    Cmdline(String),
+    Pid(u32),
+    Uid(u32),
+    State(char),
+    /// Controlling terminal, e.g. "pts/3"
+    Tty(String),
+    /// A field with a value that can never match (e.g. an unknown user)
+    Never,
+}
+
+/// Parsed search query such as `user:root state:Z` or `name:chrome`.
+/// All terms must match; bare words search the name and command line.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    terms: Vec<Term>,
+    /// Hints about parts of the query that were ignored or can't match
+    pub hints: Vec<String>,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = Query::default();
+
+        for token in query.split_whitespace() {
+            let Some((field, value)) = token.split_once(':') else {
+                parsed.terms.push(Term::Text(token.to_lowercase()));
+                continue;
+            };
+
+            let field = field.to_lowercase();
+            if value.is_empty() {
+                continue; // Still typing the value
+            }
+
+            let term = match field.as_str() {
+                "name" => Term::Name(value.to_lowercase()),
+                "cmdline" => Term::Cmdline(value.to_lowercase()),
+                "tty" => Term::Tty(value.strip_prefix("/dev/").unwrap_or(value).to_string()),
+                "pid" => match value.parse() {
+                    Ok(pid) => Term::Pid(pid),
+                    Err(_) => {
+                        parsed.hints.push(format!("'{}' is not a valid PID", value));
+                        Term::Never
+                    }
+                },
+                "uid" => match value.parse() {
+                    Ok(uid) => Term::Uid(uid),
+                    Err(_) => {
+                        parsed.hints.push(format!("'{}' is not a valid UID", value));
+                        Term::Never
+                    }
+                },
+                "user" => match value.parse().ok().or_else(|| user::uid_for_name(value)) {
+                    Some(uid) => Term::Uid(uid),
+                    None => {
+                        parsed.hints.push(format!("Unknown user '{}'", value));
+                        Term::Never
+                    }
+                },
+                "state" => {
+                    let mut chars = value.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(state), None) => Term::State(state.to_ascii_uppercase()),
+                        _ => {
+                            parsed.hints.push(format!("State should be a single letter, e.g. state:Z (got '{}')", value));
+                            Term::Never
+                        }
+                    }
+                }
+                _ => {
+                    // Ignore the token rather than silently matching nothing
+                    parsed.hints.push(format!(
+                        "Unknown field '{}:' ignored (try {})",
+                        field,
+                        FIELDS.join(", ")
+                    ));
+                    continue;
+                }
+            };
+            parsed.terms.push(term);
+        }
+
+        parsed
+    }
+
+    /// Terminal the query filters on (the first tty: term), if any
+    pub fn tty(&self) -> Option<&str> {
+        self.terms.iter().find_map(|term| match term {
+            Term::Tty(tty) => Some(tty.as_str()),
+            _ => None,
+        })
+    }
+
+    /// True when the query has no conditions (everything matches)
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, p: &Process) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Text(text) => {
+                p.name.to_lowercase().contains(text)
+                    || p.cmdline_string().to_lowercase().contains(text)
+                    || p.process_id.to_string().contains(text)
+                    || p.user_id.to_string().contains(text)
+            }
+            Term::Name(name) => p.name.to_lowercase().contains(name),
+            Term::Cmdline(cmdline) => p.cmdline_string().to_lowercase().contains(cmdline),
+            Term::Pid(pid) => p.process_id == *pid,
+            Term::Uid(uid) => p.user_id == *uid,
+            Term::State(state) => p.pcb_data.state.to_ascii_uppercase() == *state,
+            Term::Tty(tty) => p.tty().as_deref() == Some(tty.as_str()),
+            Term::Never => false,
+        })
+    }
+}
+
+/// Check a process against a query string (see `Query` for the syntax)
+pub fn matches_query(p: &Process, query: &str) -> bool {
+    Query::parse(query).matches(p)
+}
+
+/// Longest pattern the fuzzy scorer looks at, to bound the edit-distance cost per name
+const FUZZY_MAX_PATTERN: usize = 32;
+
+/// Score how well `candidate` matches a possibly mistyped `pattern` (case-insensitive);
+/// higher is better, None means no match. Substrings rank first (earlier is better),
+/// then in-order subsequences ("chrm" in "chrome", tighter is better), then names within
+/// a small edit distance of the pattern ("chorme").
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<u32> {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().take(FUZZY_MAX_PATTERN).collect();
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    // Substring
+    if let Some(pos) = candidate.windows(pattern.len()).position(|w| w == pattern.as_slice()) {
+        return Some(3000 - pos.min(999) as u32);
+    }
+
+    // Subsequence: penalize the characters skipped between the first and last match
+    let mut next = 0;
+    let mut first = None;
+    for (i, &c) in candidate.iter().enumerate() {
+        if next < pattern.len() && c == pattern[next] {
+            first.get_or_insert(i);
+            next += 1;
+            if next == pattern.len() {
+                let span = i + 1 - first.unwrap_or(0);
+                return Some(2000 - (span - pattern.len()).min(999) as u32);
+            }
+        }
+    }
+
+    // Typos: compare with the whole name and with its prefix of the pattern's length
+    let prefix_len = candidate.len().min(pattern.len());
+    let distance = levenshtein(&pattern, &candidate).min(levenshtein(&pattern, &candidate[..prefix_len]));
+    let allowed = (pattern.len() / 3).max(1);
+    (distance <= allowed).then(|| 1000 - distance as u32)
+}
+
+/// Edit distance (insertions, deletions, substitutions) between two strings
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, &ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
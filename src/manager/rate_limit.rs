@@ -0,0 +1,105 @@
+use std::time::Instant;
+
+/// Sustained rate of destructive operations allowed by default
+pub const DEFAULT_OPS_PER_SECOND: f64 = 100.0;
+
+/// Operations allowed in a burst by default (enough for a large batch kill from the GUI)
+pub const DEFAULT_BURST: f64 = 1000.0;
+
+/// Token bucket guarding destructive operations (kill, terminate, pause, signal) against
+/// runaway loops in code built on the library. Each operation takes one token; tokens
+/// refill continuously at `per_second` up to `burst`.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    pub enabled: bool,
+    per_second: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(per_second: f64, burst: f64) -> Self {
+        RateLimiter {
+            enabled: true,
+            per_second: per_second.max(0.0),
+            burst: burst.max(1.0),
+            tokens: burst.max(1.0),
+            last_refill: Instant::now(),
+        }
+    }
+
+    pub fn per_second(&self) -> f64 {
+        self.per_second
+    }
+
+    pub fn burst(&self) -> f64 {
+        self.burst
+    }
+
+    /// Change the limits, starting again with a full bucket
+    pub fn set_rate(&mut self, per_second: f64, burst: f64) {
+        *self = RateLimiter { enabled: self.enabled, ..RateLimiter::new(per_second, burst) };
+    }
+
+    /// Take a token if one is available. Always succeeds while disabled.
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    /// try_acquire as of `now`, so tests don't depend on the wall clock
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.burst);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new(DEFAULT_OPS_PER_SECOND, DEFAULT_BURST)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn blocks_past_the_burst_until_tokens_refill() {
+        let mut limiter = RateLimiter::new(2.0, 3.0);
+        let start = limiter.last_refill;
+
+        assert!((0..3).all(|_| limiter.try_acquire_at(start)));
+        assert!(!limiter.try_acquire_at(start));
+        assert!(!limiter.try_acquire_at(start + Duration::from_millis(250)));
+
+        // 0.6s at 2/s refills one token (and a fraction of the next)
+        assert!(limiter.try_acquire_at(start + Duration::from_millis(600)));
+        assert!(!limiter.try_acquire_at(start + Duration::from_millis(600)));
+
+        // A long pause refills up to the burst, not beyond
+        let later = start + Duration::from_secs(60);
+        assert!((0..3).all(|_| limiter.try_acquire_at(later)));
+        assert!(!limiter.try_acquire_at(later));
+    }
+
+    #[test]
+    fn disabled_limiter_never_blocks() {
+        let mut limiter = RateLimiter::new(0.0, 1.0);
+        limiter.enabled = false;
+        let now = Instant::now();
+        assert!((0..10).all(|_| limiter.try_acquire_at(now)));
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::manager::operations;
+use crate::manager::Manager;
+use crate::process::Process;
+
+/// What a rule does to a process that matches it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Kill,      // SIGKILL
+    Terminate, // SIGTERM
+    Pause,     // SIGSTOP
+}
+
+impl Action {
+    pub fn parse(word: &str) -> Result<Self, String> {
+        match word.to_lowercase().as_str() {
+            "kill" => Ok(Action::Kill),
+            "term" | "terminate" => Ok(Action::Terminate),
+            "pause" | "stop" => Ok(Action::Pause),
+            other => Err(format!("Unknown action: {} (kill, terminate or pause)", other)),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Kill => "kill",
+            Action::Terminate => "terminate",
+            Action::Pause => "pause",
+        }
+    }
+
+    pub fn past_tense(self) -> &'static str {
+        match self {
+            Action::Kill => "Killed",
+            Action::Terminate => "Terminated",
+            Action::Pause => "Paused",
+        }
+    }
+
+    /// Carry the action out through the operations layer, so protection, dry run, the audit log
+    /// and the rate limit apply exactly as for a manual kill
+    pub fn apply(self, manager: &Manager, pid: u32) -> Result<(), String> {
+        match self {
+            Action::Kill => operations::kill_process(manager, pid),
+            Action::Terminate => operations::terminate_process(manager, pid),
+            Action::Pause => operations::pause_process(manager, pid),
+        }
+    }
+}
+
+/// "Kill any process named X using less than 1% CPU for more than 10 minutes"
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name_match: String, // Exact process name (comm), or "*" for any process
+    pub cpu_below: f32,     // CPU% (per core, as displayed) the process has to stay under...
+    pub duration: Duration, // ...for at least this long
+    pub action: Action,
+}
+
+impl Rule {
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.name_match == "*" || self.name_match == name
+    }
+}
+
+/// Parse a rules file: one rule per line as "<name> <cpu%> <duration> <action>", e.g.
+/// "bash 1 10m kill". Durations take an s, m or h suffix; blank lines and "#" comments are skipped.
+pub fn parse_rules(text: &str) -> Result<Vec<Rule>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(number, line)| parse_rule(line).map_err(|e| format!("Line {}: {}", number, e)))
+        .collect()
+}
+
+pub fn load_rules(path: &Path) -> Result<Vec<Rule>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read rules {}: {}", path.display(), e))?;
+    parse_rules(&text)
+}
+
+fn parse_rule(line: &str) -> Result<Rule, String> {
+    let [name, cpu, duration, action] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+        return Err(format!("expected \"<name> <cpu%> <duration> <action>\", got \"{}\"", line));
+    };
+    let cpu_below = cpu
+        .trim_end_matches('%')
+        .parse::<f32>()
+        .ok()
+        .filter(|cpu| *cpu > 0.0)
+        .ok_or_else(|| format!("Invalid CPU%: {} (must be above 0)", cpu))?;
+    Ok(Rule {
+        name_match: name.to_string(),
+        cpu_below,
+        duration: parse_duration(duration)?,
+        action: Action::parse(action)?,
+    })
+}
+
+/// "90s", "10m" or "2h"
+fn parse_duration(word: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid duration: {} (e.g. 90s, 10m or 2h)", word);
+    let (value, unit_secs) = [('s', 1), ('m', 60), ('h', 3600)]
+        .into_iter()
+        .find_map(|(suffix, unit_secs)| word.strip_suffix(suffix).map(|value| (value, unit_secs)))
+        .ok_or_else(invalid)?;
+    let secs = value
+        .parse::<u64>()
+        .ok()
+        .and_then(|value| value.checked_mul(unit_secs))
+        .ok_or_else(invalid)?;
+    Ok(Duration::from_secs(secs))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CpuSample {
+    time: Instant,
+    cpu_percent: f32,
+}
+
+#[derive(Debug)]
+struct IdleHistory {
+    start_time_ticks: u64, // A different start time means the PID was reused
+    first_seen: Instant,
+    // Only the samples no later sample was as busy as, oldest (and busiest) first. The last
+    // sample at or above a threshold is all "how long under X%" needs, and no other sample
+    // can be that one, so the rest are dropped and the history stays short however long it runs.
+    busiest: Vec<CpuSample>,
+}
+
+/// Per-PID CPU% history answering "how long has this process stayed under X% CPU?" for any X,
+/// so rules with different thresholds can share it
+#[derive(Debug, Default)]
+pub struct IdleTracker {
+    history: HashMap<u32, IdleHistory>,
+}
+
+impl IdleTracker {
+    /// Record the current CPU% of every process, forgetting exited PIDs
+    pub fn sample(&mut self, processes: &HashMap<u32, Process>, now: Instant) {
+        self.history.retain(|pid, _| processes.contains_key(pid));
+
+        for process in processes.values() {
+            let start_time_ticks = process.pcb_data.start_time_ticks;
+            let history = self.history.entry(process.process_id).or_insert_with(|| IdleHistory {
+                start_time_ticks,
+                first_seen: now,
+                busiest: Vec::new(),
+            });
+            if history.start_time_ticks != start_time_ticks {
+                *history = IdleHistory { start_time_ticks, first_seen: now, busiest: Vec::new() };
+            }
+            let cpu_percent = process.pcb_data.cpu_percent;
+            while history.busiest.last().is_some_and(|s| s.cpu_percent <= cpu_percent) {
+                history.busiest.pop();
+            }
+            history.busiest.push(CpuSample { time: now, cpu_percent });
+        }
+    }
+
+    /// How long pid has used less than cpu_below % CPU, counting from when it was first seen if
+    /// it never reached it; None for a PID that hasn't been sampled
+    pub fn idle_for(&self, pid: u32, cpu_below: f32, now: Instant) -> Option<Duration> {
+        let history = self.history.get(&pid)?;
+        let idle_since = history
+            .busiest
+            .iter()
+            .rev()
+            .find(|s| s.cpu_percent >= cpu_below)
+            .map_or(history.first_seen, |s| s.time);
+        Some(now.saturating_duration_since(idle_since))
+    }
+}
+
+/// The action of the first rule (in file order) each process matches, sorted by PID. Zombies are
+/// skipped: they use no CPU, and only their parent can clear them.
+pub fn evaluate(rules: &[Rule], processes: &HashMap<u32, Process>, idle: &IdleTracker, now: Instant) -> Vec<(u32, Action)> {
+    let mut actions: Vec<(u32, Action)> = processes
+        .values()
+        .filter(|p| p.pcb_data.state != 'Z')
+        .filter_map(|p| {
+            rules
+                .iter()
+                .find(|rule| {
+                    rule.matches_name(&p.name)
+                        && idle.idle_for(p.process_id, rule.cpu_below, now).is_some_and(|idle| idle >= rule.duration)
+                })
+                .map(|rule| (p.process_id, rule.action))
+        })
+        .collect();
+    actions.sort_by_key(|&(pid, _)| pid);
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, cpu_percent: f32, start_time_ticks: u64) -> Process {
+        let mut process = Process {
+            process_id: pid,
+            tgid: Some(pid),
+            status_unreadable: false,
+            user_id: 1000,
+            effective_uid: 1000,
+            saved_uid: 1000,
+            groups: Vec::new(),
+            name: name.to_string(),
+            cmdline: Vec::new(),
+            parent_id: Some(1),
+            exe_deleted: false,
+            pcb_data: Default::default(),
+        };
+        process.pcb_data.state = 'S';
+        process.pcb_data.cpu_percent = cpu_percent;
+        process.pcb_data.start_time_ticks = start_time_ticks;
+        process
+    }
+
+    fn table(processes: Vec<Process>) -> HashMap<u32, Process> {
+        processes.into_iter().map(|p| (p.process_id, p)).collect()
+    }
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn rules_parse_with_comments_and_units() {
+        let rules = parse_rules("# clean up idle shells\n\nbash 1% 10m kill  # trailing note\n* 0.5 2h PAUSE\nworker 5 90s term\n").unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                Rule { name_match: "bash".to_string(), cpu_below: 1.0, duration: secs(600), action: Action::Kill },
+                Rule { name_match: "*".to_string(), cpu_below: 0.5, duration: secs(7200), action: Action::Pause },
+                Rule { name_match: "worker".to_string(), cpu_below: 5.0, duration: secs(90), action: Action::Terminate },
+            ]
+        );
+        assert_eq!(parse_rules("# nothing here\n"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn rules_reject_malformed_lines() {
+        let error = |text: &str| parse_rules(text).unwrap_err();
+        assert!(error("bash 1 10m").starts_with("Line 1: expected"));
+        assert!(error("# ok\nbash 1 10m kill now").starts_with("Line 2: expected"));
+        assert!(error("bash 0 10m kill").contains("Invalid CPU%"));
+        assert!(error("bash -1 10m kill").contains("Invalid CPU%"));
+        assert!(error("bash busy 10m kill").contains("Invalid CPU%"));
+        assert!(error("bash 1 10 kill").contains("Invalid duration"));
+        assert!(error("bash 1 m kill").contains("Invalid duration"));
+        assert!(error("bash 1 10d kill").contains("Invalid duration"));
+        // Would overflow u64 seconds
+        assert!(error("bash 1 18446744073709551615h kill").contains("Invalid duration"));
+        assert!(error("bash 1 10m nuke").contains("Unknown action"));
+    }
+
+    #[test]
+    fn idle_time_counts_from_the_last_sample_at_or_above_the_threshold() {
+        let start = Instant::now();
+        let mut idle = IdleTracker::default();
+        idle.sample(&table(vec![process(10, "bash", 0.0, 1)]), start);
+        assert_eq!(idle.idle_for(10, 1.0, start + secs(30)), Some(secs(30)));
+        assert_eq!(idle.idle_for(11, 1.0, start), None);
+
+        // A 20% burst at 60s, 3% at 120s, then quiet
+        idle.sample(&table(vec![process(10, "bash", 20.0, 1)]), start + secs(60));
+        idle.sample(&table(vec![process(10, "bash", 3.0, 1)]), start + secs(120));
+        idle.sample(&table(vec![process(10, "bash", 0.2, 1)]), start + secs(180));
+        let now = start + secs(300);
+        assert_eq!(idle.idle_for(10, 1.0, now), Some(secs(180)));
+        assert_eq!(idle.idle_for(10, 3.0, now), Some(secs(180)));
+        assert_eq!(idle.idle_for(10, 5.0, now), Some(secs(240)));
+        // Never reached: idle since first seen
+        assert_eq!(idle.idle_for(10, 50.0, now), Some(secs(300)));
+        // Everything so far counts as busy against a threshold this low
+        assert_eq!(idle.idle_for(10, 0.1, now), Some(secs(120)));
+    }
+
+    #[test]
+    fn idle_history_restarts_when_a_pid_is_reused_or_exits() {
+        let start = Instant::now();
+        let mut idle = IdleTracker::default();
+        idle.sample(&table(vec![process(10, "bash", 0.0, 1)]), start);
+        idle.sample(&table(vec![process(10, "bash", 0.0, 1)]), start + secs(600));
+        assert_eq!(idle.idle_for(10, 1.0, start + secs(600)), Some(secs(600)));
+
+        // Same PID, new start time: a different process, watched from now on
+        idle.sample(&table(vec![process(10, "bash", 0.0, 500)]), start + secs(700));
+        assert_eq!(idle.idle_for(10, 1.0, start + secs(760)), Some(secs(60)));
+
+        idle.sample(&HashMap::new(), start + secs(800));
+        assert_eq!(idle.idle_for(10, 1.0, start + secs(800)), None);
+    }
+
+    #[test]
+    fn evaluate_uses_the_first_matching_rule() {
+        let rules = parse_rules("bash 1 10m kill\n* 5 10m pause\nbash 5 1m term\n").unwrap();
+        let start = Instant::now();
+        let mut zombie = process(13, "bash", 0.0, 1);
+        zombie.pcb_data.state = 'Z';
+        let processes = table(vec![
+            process(10, "bash", 0.0, 1),
+            process(11, "vim", 2.0, 1),
+            process(12, "bash", 2.0, 1),
+            zombie,
+        ]);
+        let mut idle = IdleTracker::default();
+        // The first refresh has no CPU% yet; the real figures come a second later
+        let first_refresh = table(processes.values().map(|p| process(p.process_id, &p.name, 0.0, 1)).collect());
+        idle.sample(&first_refresh, start);
+        idle.sample(&processes, start + secs(1));
+
+        // After a minute only the one-minute rule applies, to both live shells
+        assert_eq!(
+            evaluate(&rules, &processes, &idle, start + secs(60)),
+            vec![(10, Action::Terminate), (12, Action::Terminate)]
+        );
+        // 10 matches the first rule; 11 only the catch-all; 12 (2% CPU a second in) the catch-all
+        // before the term rule; the zombie nothing
+        assert_eq!(
+            evaluate(&rules, &processes, &idle, start + secs(600)),
+            vec![(10, Action::Kill), (11, Action::Pause), (12, Action::Pause)]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::process::Process;
+
+/// One process as recorded in a snapshot file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotProcess {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub name: String,
+    pub cmdline: Vec<String>,
+    pub uid: u32,
+    pub state: char,
+    pub cpu_percent: f32,
+    pub memory_rss_kb: u64,
+    pub start_time_ticks: u64, // Tells a restarted process apart from one that reused the PID
+}
+
+impl From<&Process> for SnapshotProcess {
+    fn from(process: &Process) -> Self {
+        SnapshotProcess {
+            pid: process.process_id,
+            ppid: process.parent_id,
+            name: process.name.clone(),
+            cmdline: process.cmdline.clone(),
+            uid: process.user_id,
+            state: process.pcb_data.state,
+            cpu_percent: process.pcb_data.cpu_percent,
+            memory_rss_kb: process.pcb_data.memory_rss_kb,
+            start_time_ticks: process.pcb_data.start_time_ticks,
+        }
+    }
+}
+
+/// The process list at one point in time, saved as JSON for later comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: u64, // Unix time in seconds
+    pub processes: Vec<SnapshotProcess>, // Sorted by PID
+}
+
+impl Snapshot {
+    pub fn capture<'a>(processes: impl Iterator<Item = &'a Process>) -> Self {
+        let mut processes: Vec<SnapshotProcess> = processes.map(SnapshotProcess::from).collect();
+        processes.sort_by_key(|p| p.pid);
+        Snapshot {
+            taken_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            processes,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+        std::fs::write(path, json)
+            .map_err(|e| format!("Failed to write snapshot {}: {}", path.display(), e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read snapshot {}: {}", path.display(), e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid snapshot {}: {}", path.display(), e))
+    }
+}
+
+/// CPU% changes smaller than this are rounding noise, not a change worth reporting
+const MIN_CPU_DELTA: f32 = 0.1;
+
+/// How a process present in both snapshots changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessDelta {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_percent_delta: f32,
+    pub rss_kb_delta: i64,
+    pub state_change: Option<(char, char)>, // (before, after) if the state differs
+}
+
+/// Differences between two snapshots; every list is sorted by PID
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SnapshotDiff {
+    pub spawned: Vec<SnapshotProcess>, // Only in the later snapshot
+    pub exited: Vec<SnapshotProcess>,  // Only in the earlier snapshot
+    pub changed: Vec<ProcessDelta>,    // In both, with a state change or a CPU/RSS delta
+}
+
+/// Compare snapshot a (before) with b (after). A PID whose start time differs was reused
+/// by a new process, so it counts as one exit plus one spawn.
+pub fn diff(a: &Snapshot, b: &Snapshot) -> SnapshotDiff {
+    let before: HashMap<u32, &SnapshotProcess> = a.processes.iter().map(|p| (p.pid, p)).collect();
+    let after: HashMap<u32, &SnapshotProcess> = b.processes.iter().map(|p| (p.pid, p)).collect();
+    let same_process = |old: &SnapshotProcess, new: &SnapshotProcess| old.start_time_ticks == new.start_time_ticks;
+
+    let mut result = SnapshotDiff::default();
+    for old in &a.processes {
+        match after.get(&old.pid) {
+            Some(new) if same_process(old, new) => {
+                let delta = ProcessDelta {
+                    pid: old.pid,
+                    name: new.name.clone(),
+                    cpu_percent_delta: new.cpu_percent - old.cpu_percent,
+                    rss_kb_delta: new.memory_rss_kb as i64 - old.memory_rss_kb as i64,
+                    state_change: (old.state != new.state).then_some((old.state, new.state)),
+                };
+                if delta.state_change.is_some() || delta.rss_kb_delta != 0 || delta.cpu_percent_delta.abs() >= MIN_CPU_DELTA {
+                    result.changed.push(delta);
+                }
+            }
+            _ => result.exited.push(old.clone()),
+        }
+    }
+    for new in &b.processes {
+        if !before.get(&new.pid).is_some_and(|old| same_process(old, new)) {
+            result.spawned.push(new.clone());
+        }
+    }
+
+    result.spawned.sort_by_key(|p| p.pid);
+    result.exited.sort_by_key(|p| p.pid);
+    result.changed.sort_by_key(|d| d.pid);
+    result
+}
+
+impl SnapshotDiff {
+    /// Human-readable report: spawned, exited, then changed processes
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        // Writing to a String can't fail
+        let _ = writeln!(report, "Spawned ({}):", self.spawned.len());
+        for p in &self.spawned {
+            let _ = writeln!(report, "  + {} {} [{}] {} kB", p.pid, p.name, p.state, p.memory_rss_kb);
+        }
+        let _ = writeln!(report, "Exited ({}):", self.exited.len());
+        for p in &self.exited {
+            let _ = writeln!(report, "  - {} {}", p.pid, p.name);
+        }
+        let _ = writeln!(report, "Changed ({}):", self.changed.len());
+        for d in &self.changed {
+            let mut line = format!("  ~ {} {}: CPU {:+.1}%, RSS {:+} kB", d.pid, d.name, d.cpu_percent_delta, d.rss_kb_delta);
+            if let Some((before, after)) = d.state_change {
+                let _ = write!(line, ", state {} -> {}", before, after);
+            }
+            let _ = writeln!(report, "{}", line);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, start_time_ticks: u64) -> SnapshotProcess {
+        SnapshotProcess {
+            pid,
+            ppid: Some(1),
+            name: name.to_string(),
+            cmdline: Vec::new(),
+            uid: 1000,
+            state: 'S',
+            cpu_percent: 0.0,
+            memory_rss_kb: 1_000,
+            start_time_ticks,
+        }
+    }
+
+    fn snapshot(processes: Vec<SnapshotProcess>) -> Snapshot {
+        Snapshot { taken_at: 0, processes }
+    }
+
+    #[test]
+    fn diff_reports_spawned_exited_and_changed() {
+        let before = snapshot(vec![process(10, "idle", 100), process(11, "busy", 100), process(12, "gone", 100)]);
+        let mut busy = process(11, "busy", 100);
+        busy.cpu_percent = 50.0;
+        busy.memory_rss_kb = 3_000;
+        busy.state = 'R';
+        let after = snapshot(vec![process(10, "idle", 100), busy, process(13, "new", 200)]);
+
+        let diff = diff(&before, &after);
+        assert_eq!(diff.spawned, vec![process(13, "new", 200)]);
+        assert_eq!(diff.exited, vec![process(12, "gone", 100)]);
+        assert_eq!(diff.changed, vec![ProcessDelta {
+            pid: 11,
+            name: "busy".to_string(),
+            cpu_percent_delta: 50.0,
+            rss_kb_delta: 2_000,
+            state_change: Some(('S', 'R')),
+        }]);
+    }
+
+    #[test]
+    fn reused_pid_is_an_exit_and_a_spawn() {
+        let before = snapshot(vec![process(10, "worker", 100)]);
+        let after = snapshot(vec![process(10, "worker", 900)]);
+
+        let diff = diff(&before, &after);
+        assert_eq!(diff.exited, vec![process(10, "worker", 100)]);
+        assert_eq!(diff.spawned, vec![process(10, "worker", 900)]);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn cpu_noise_is_not_a_change() {
+        let before = snapshot(vec![process(10, "idle", 100)]);
+        let mut idle = process(10, "idle", 100);
+        idle.cpu_percent = MIN_CPU_DELTA / 2.0;
+        let after = snapshot(vec![idle]);
+
+        assert_eq!(diff(&before, &after), SnapshotDiff::default());
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+
+use procfs::{Current, CurrentSI, KernelStats, Meminfo};
+
+/// Default number of samples kept in the system history
+pub const DEFAULT_HISTORY_LEN: usize = 120;
+
+/// Aggregate CPU time across all cores, in jiffies, from the `cpu` line of /proc/stat
+#[derive(Debug, Clone, Copy)]
+pub struct CpuTimes {
+    pub busy: u64,
+    pub total: u64,
+}
+
+/// Read the system-wide CPU times from /proc/stat
+pub fn read_cpu_times() -> Result<CpuTimes, String> {
+    let stats = KernelStats::current().map_err(|e| format!("Failed to read /proc/stat: {}", e))?;
+    let cpu = stats.total;
+    let idle = cpu.idle + cpu.iowait.unwrap_or(0);
+    let busy = cpu.user
+        + cpu.nice
+        + cpu.system
+        + cpu.irq.unwrap_or(0)
+        + cpu.softirq.unwrap_or(0)
+        + cpu.steal.unwrap_or(0);
+    Ok(CpuTimes { busy, total: busy + idle })
+}
+
+/// Physical memory totals from /proc/meminfo, in kilobytes
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryInfo {
+    pub total_kb: u64,
+    pub available_kb: u64,
+}
+
+impl MemoryInfo {
+    /// Share of memory in use (total minus available), 0-100
+    pub fn used_percent(&self) -> f32 {
+        if self.total_kb == 0 {
+            return 0.0;
+        }
+        let used = self.total_kb.saturating_sub(self.available_kb);
+        (used as f64 / self.total_kb as f64 * 100.0) as f32
+    }
+}
+
+/// Read physical memory totals from /proc/meminfo
+pub fn read_memory_info() -> Result<MemoryInfo, String> {
+    let meminfo = Meminfo::current().map_err(|e| format!("Failed to read /proc/meminfo: {}", e))?;
+    // Older kernels lack MemAvailable; fall back to MemFree
+    let available = meminfo.mem_available.unwrap_or(meminfo.mem_free);
+    Ok(MemoryInfo {
+        total_kb: meminfo.mem_total / 1024,
+        available_kb: available / 1024,
+    })
+}
+
+/// Pressure stall averages of one resource: the share of wall time (0-100) in which at least one
+/// task was stalled waiting for it, over the last 10 s, 60 s and 300 s
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureAverages {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+}
+
+/// Pressure stall information (PSI) for CPU, memory and I/O from /proc/pressure
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureInfo {
+    pub cpu: PressureAverages,
+    pub memory: PressureAverages,
+    pub io: PressureAverages,
+}
+
+/// Read /proc/pressure/{cpu,memory,io}. None on kernels before 4.20, or with PSI compiled
+/// out or disabled (psi=0), where the files are missing or can't be read.
+pub fn read_pressure() -> Option<PressureInfo> {
+    let read = |resource: &str| {
+        let text = std::fs::read_to_string(format!("/proc/pressure/{}", resource)).ok()?;
+        parse_pressure(&text)
+    };
+    Some(PressureInfo {
+        cpu: read("cpu")?,
+        memory: read("memory")?,
+        io: read("io")?,
+    })
+}
+
+/// The "some" line of a pressure file: "some avg10=0.00 avg60=0.02 avg300=0.00 total=854106"
+fn parse_pressure(text: &str) -> Option<PressureAverages> {
+    let line = text.lines().find_map(|line| line.strip_prefix("some "))?;
+    let mut averages = PressureAverages::default();
+    for field in line.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        let slot = match key {
+            "avg10" => &mut averages.avg10,
+            "avg60" => &mut averages.avg60,
+            "avg300" => &mut averages.avg300,
+            _ => continue,
+        };
+        *slot = value.parse().ok()?;
+    }
+    Some(averages)
+}
+
+/// Rolling window of system-wide CPU% and memory% samples, oldest first
+#[derive(Debug)]
+pub struct SystemHistory {
+    pub cpu_percent: VecDeque<f32>,
+    pub memory_percent: VecDeque<f32>,
+    pub pressure: Option<PressureInfo>, // Latest PSI reading (None where the kernel has no PSI)
+    capacity: usize,
+    // CPU times at the previous sample, needed to turn the counters into a percentage
+    last_cpu_times: Option<CpuTimes>,
+}
+
+impl SystemHistory {
+    pub fn new(capacity: usize) -> Self {
+        SystemHistory {
+            cpu_percent: VecDeque::with_capacity(capacity),
+            memory_percent: VecDeque::with_capacity(capacity),
+            pressure: None,
+            capacity: capacity.max(1),
+            last_cpu_times: None,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change how many samples are kept, dropping the oldest if shrinking
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.trim();
+    }
+
+    /// Take a new sample. CPU needs two samples, so the first call only records a baseline.
+    pub fn sample(&mut self) -> Result<(), String> {
+        let cpu_times = read_cpu_times()?;
+        if let Some(last) = self.last_cpu_times {
+            let delta_total = cpu_times.total.saturating_sub(last.total);
+            let delta_busy = cpu_times.busy.saturating_sub(last.busy);
+            let percent = if delta_total > 0 {
+                (delta_busy as f64 / delta_total as f64 * 100.0) as f32
+            } else {
+                0.0
+            };
+            self.cpu_percent.push_back(percent);
+        }
+        self.last_cpu_times = Some(cpu_times);
+
+        self.memory_percent.push_back(read_memory_info()?.used_percent());
+        self.pressure = read_pressure();
+        self.trim();
+        Ok(())
+    }
+
+    fn trim(&mut self) {
+        while self.cpu_percent.len() > self.capacity {
+            self.cpu_percent.pop_front();
+        }
+        while self.memory_percent.len() > self.capacity {
+            self.memory_percent.pop_front();
+        }
+    }
+}
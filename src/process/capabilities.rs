@@ -0,0 +1,100 @@
+use crate::process::Process; // Import the base Process struct
+
+/// Capability names by bit number, as in linux/capability.h
+const CAP_NAMES: [&str; 41] = [
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_DAC_READ_SEARCH",
+    "CAP_FOWNER",
+    "CAP_FSETID",
+    "CAP_KILL",
+    "CAP_SETGID",
+    "CAP_SETUID",
+    "CAP_SETPCAP",
+    "CAP_LINUX_IMMUTABLE",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_NET_BROADCAST",
+    "CAP_NET_ADMIN",
+    "CAP_NET_RAW",
+    "CAP_IPC_LOCK",
+    "CAP_IPC_OWNER",
+    "CAP_SYS_MODULE",
+    "CAP_SYS_RAWIO",
+    "CAP_SYS_CHROOT",
+    "CAP_SYS_PTRACE",
+    "CAP_SYS_PACCT",
+    "CAP_SYS_ADMIN",
+    "CAP_SYS_BOOT",
+    "CAP_SYS_NICE",
+    "CAP_SYS_RESOURCE",
+    "CAP_SYS_TIME",
+    "CAP_SYS_TTY_CONFIG",
+    "CAP_MKNOD",
+    "CAP_LEASE",
+    "CAP_AUDIT_WRITE",
+    "CAP_AUDIT_CONTROL",
+    "CAP_SETFCAP",
+    "CAP_MAC_OVERRIDE",
+    "CAP_MAC_ADMIN",
+    "CAP_SYSLOG",
+    "CAP_WAKE_ALARM",
+    "CAP_BLOCK_SUSPEND",
+    "CAP_AUDIT_READ",
+    "CAP_PERFMON",
+    "CAP_BPF",
+    "CAP_CHECKPOINT_RESTORE",
+];
+
+/// Capabilities that amount to (or easily lead to) full root: worth a warning when held
+pub const DANGEROUS_CAPABILITIES: [&str; 3] = ["CAP_SYS_ADMIN", "CAP_SYS_MODULE", "CAP_SYS_PTRACE"];
+
+/// Capability sets of a process from /proc/[pid]/status, decoded to CAP_* names
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub capabilities: Vec<String>, // Effective set: what the kernel checks right now
+    pub permitted: Vec<String>,    // What it may raise into the effective set
+    pub bounding: Option<Vec<String>>, // Upper limit for anything it execs (None on kernels before 2.6.26)
+}
+
+impl Capabilities {
+    /// Effective capabilities from DANGEROUS_CAPABILITIES
+    pub fn dangerous(&self) -> Vec<&str> {
+        self.capabilities
+            .iter()
+            .map(String::as_str)
+            .filter(|cap| DANGEROUS_CAPABILITIES.contains(cap))
+            .collect()
+    }
+
+    /// Whether a set holds every capability this build knows about, as root's sets usually do
+    pub fn is_full(set: &[String]) -> bool {
+        CAP_NAMES.iter().all(|name| set.iter().any(|cap| cap == name))
+    }
+}
+
+/// Names of the bits set in a CapEff/CapPrm/CapBnd mask, lowest bit first. Bits newer than
+/// this build's table come out as "cap_41" and so on.
+pub fn decode_capabilities(mask: u64) -> Vec<String> {
+    (0..64)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(|bit| match CAP_NAMES.get(bit as usize) {
+            Some(name) => name.to_string(),
+            None => format!("cap_{}", bit),
+        })
+        .collect()
+}
+
+impl Process {
+    /// Effective, permitted and bounding capabilities of the process. Decoding all three sets is
+    /// more work than a refresh should do for every process, so this is read on demand.
+    pub fn capabilities(pid: u32) -> Result<Capabilities, String> {
+        let status = procfs::process::Process::new(pid as i32)
+            .and_then(|p| p.status())
+            .map_err(|e| format!("Failed to read status of PID {}: {}", pid, e))?;
+        Ok(Capabilities {
+            capabilities: decode_capabilities(status.capeff),
+            permitted: decode_capabilities(status.capprm),
+            bounding: status.capbnd.map(decode_capabilities),
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use nix::sys::signal::Signal;
+
+use crate::process::Process; // Import the base Process struct
+
+/// Why a process is in its current state: how a zombie ended, or what stopped a stopped process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateCause {
+    ExitedWith(i32),                          // exit() status
+    KilledBy { signal: i32, core_dumped: bool },
+    StoppedBy(i32),                           // Stop signal (SIGSTOP, SIGTSTP, ...)
+}
+
+impl StateCause {
+    /// Decode a wait() status word, the encoding the kernel also uses for exit_code in
+    /// /proc/[pid]/stat: stop signal in the second byte over 0x7f, death signal in the low
+    /// 7 bits (0x80 = core dumped), otherwise the exit status in the second byte
+    pub fn from_wait_status(status: i32) -> StateCause {
+        let signal = status & 0x7f;
+        if signal == 0x7f {
+            StateCause::StoppedBy((status >> 8) & 0xff)
+        } else if signal == 0 {
+            StateCause::ExitedWith((status >> 8) & 0xff)
+        } else {
+            StateCause::KilledBy { signal, core_dumped: status & 0x80 != 0 }
+        }
+    }
+
+    /// Label and value for the details panel, e.g. ("Exit signal", "SIGSEGV (core dumped)")
+    pub fn describe(&self) -> (&'static str, String) {
+        match *self {
+            StateCause::ExitedWith(status) => ("Exit status", status.to_string()),
+            StateCause::KilledBy { signal, core_dumped } => (
+                "Exit signal",
+                format!("{}{}", signal_name(signal), if core_dumped { " (core dumped)" } else { "" }),
+            ),
+            StateCause::StoppedBy(signal) => ("Stopped by", signal_name(signal)),
+        }
+    }
+}
+
+/// "SIGSEGV" for a known signal number, "signal 42" otherwise (e.g. real-time signals)
+pub fn signal_name(signal: i32) -> String {
+    match Signal::try_from(signal) {
+        Ok(signal) => signal.as_str().to_string(),
+        Err(_) => format!("signal {}", signal),
+    }
+}
+
+impl Process {
+    /// Why the process is a zombie or stopped, if that can be found out.
+    /// For a child of this program, waitid(WNOWAIT) reports both without reaping it.
+    /// For anyone else only a zombie's exit code is known (from stat, Linux 3.5+, and only
+    /// readable with ptrace access); what stopped another program's process isn't recorded.
+    pub fn state_cause(&self) -> Option<StateCause> {
+        if !matches!(self.pcb_data.state, 'Z' | 'T' | 't') {
+            return None;
+        }
+        peek_child_status(self.process_id).or_else(|| match self.pcb_data.exit_code {
+            Some(code) if self.pcb_data.state == 'Z' => Some(StateCause::from_wait_status(code)),
+            _ => None,
+        })
+    }
+}
+
+/// Status change of pid if it is our child, left in place so a later wait() still collects it
+fn peek_child_status(pid: u32) -> Option<StateCause> {
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let options = libc::WEXITED | libc::WSTOPPED | libc::WNOHANG | libc::WNOWAIT;
+    // ECHILD for processes that aren't ours
+    let res = unsafe { libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, options) };
+    // With WNOHANG, si_pid stays 0 when there is nothing to report
+    if res != 0 || unsafe { info.si_pid() } == 0 {
+        return None;
+    }
+    let status = unsafe { info.si_status() };
+    match info.si_code {
+        libc::CLD_EXITED => Some(StateCause::ExitedWith(status)),
+        libc::CLD_KILLED => Some(StateCause::KilledBy { signal: status, core_dumped: false }),
+        libc::CLD_DUMPED => Some(StateCause::KilledBy { signal: status, core_dumped: true }),
+        libc::CLD_STOPPED | libc::CLD_TRAPPED => Some(StateCause::StoppedBy(status)),
+        _ => None,
+    }
+}
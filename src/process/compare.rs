@@ -0,0 +1,48 @@
+use crate::process::{format_elapsed, format_memory, MemoryUnit, Process};
+
+/// A statistic shown in the side-by-side comparison of two processes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareField {
+    Cpu,
+    Rss,
+    Threads,
+    OpenFds,
+    Uptime,
+}
+
+impl CompareField {
+    pub fn label(self) -> &'static str {
+        match self {
+            CompareField::Cpu => "CPU %",
+            CompareField::Rss => "RSS",
+            CompareField::Threads => "Threads",
+            CompareField::OpenFds => "Open FDs",
+            CompareField::Uptime => "Uptime",
+        }
+    }
+
+    /// A value as returned by compare, formatted for display
+    pub fn format(self, value: f64) -> String {
+        match self {
+            CompareField::Cpu => format!("{:.1}%", value),
+            CompareField::Rss => format_memory(value as u64, MemoryUnit::Auto),
+            CompareField::Threads | CompareField::OpenFds => format!("{}", value as u64),
+            CompareField::Uptime => format_elapsed(value as u64),
+        }
+    }
+}
+
+/// Stats of a and b side by side as (field, a's value, b's value); RSS is in kB and uptime in
+/// seconds. Open FDs is left out when either count is unreadable (another user's process).
+pub fn compare(a: &Process, b: &Process) -> Vec<(CompareField, f64, f64)> {
+    let mut rows = vec![
+        (CompareField::Cpu, a.pcb_data.cpu_percent as f64, b.pcb_data.cpu_percent as f64),
+        (CompareField::Rss, a.pcb_data.memory_rss_kb as f64, b.pcb_data.memory_rss_kb as f64),
+        (CompareField::Threads, a.pcb_data.num_threads as f64, b.pcb_data.num_threads as f64),
+    ];
+    if let (Some(a_fds), Some(b_fds)) = (a.pcb_data.fd_count, b.pcb_data.fd_count) {
+        rows.push((CompareField::OpenFds, a_fds as f64, b_fds as f64));
+    }
+    rows.push((CompareField::Uptime, a.pcb_data.uptime_seconds as f64, b.pcb_data.uptime_seconds as f64));
+    rows
+}
@@ -0,0 +1,75 @@
+use procfs::process::{LimitValue, Process as ProcfsProcess};
+
+use crate::process::Process; // Import the base Process struct
+
+/// A per-process resource limit that can be read and changed (see operations::set_rlimit)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RlimitResource {
+    OpenFiles,    // RLIMIT_NOFILE
+    AddressSpace, // RLIMIT_AS, in bytes
+    CpuTime,      // RLIMIT_CPU, in seconds
+}
+
+impl RlimitResource {
+    pub const ALL: [RlimitResource; 3] = [RlimitResource::OpenFiles, RlimitResource::AddressSpace, RlimitResource::CpuTime];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RlimitResource::OpenFiles => "Open files",
+            RlimitResource::AddressSpace => "Address space",
+            RlimitResource::CpuTime => "CPU time",
+        }
+    }
+
+    /// Unit the limit is given in
+    pub fn unit(self) -> &'static str {
+        match self {
+            RlimitResource::OpenFiles => "files",
+            RlimitResource::AddressSpace => "bytes",
+            RlimitResource::CpuTime => "seconds",
+        }
+    }
+
+    /// A limit value for display; None is unlimited
+    pub fn format_value(self, value: Option<u64>) -> String {
+        match (self, value) {
+            (_, None) => "unlimited".to_string(),
+            (RlimitResource::AddressSpace, Some(bytes)) => format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0)),
+            (RlimitResource::OpenFiles, Some(files)) => files.to_string(),
+            (RlimitResource::CpuTime, Some(secs)) => format!("{} s", secs),
+        }
+    }
+}
+
+/// Soft and hard limit of one resource; None means unlimited (RLIM_INFINITY)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLimit {
+    pub resource: RlimitResource,
+    pub soft: Option<u64>,
+    pub hard: Option<u64>,
+}
+
+impl Process {
+    /// Current limits of every resource in RlimitResource::ALL, from /proc/[pid]/limits
+    /// (readable for other users' processes too, unlike prlimit)
+    pub fn rlimits(pid: u32) -> Result<Vec<ResourceLimit>, String> {
+        let limits = ProcfsProcess::new(pid as i32)
+            .and_then(|p| p.limits())
+            .map_err(|e| format!("Failed to read limits of {}: {}", pid, e))?;
+        let value = |v: LimitValue| match v {
+            LimitValue::Unlimited => None,
+            LimitValue::Value(v) => Some(v),
+        };
+        Ok(RlimitResource::ALL
+            .into_iter()
+            .map(|resource| {
+                let limit = match resource {
+                    RlimitResource::OpenFiles => &limits.max_open_files,
+                    RlimitResource::AddressSpace => &limits.max_address_space,
+                    RlimitResource::CpuTime => &limits.max_cpu_time,
+                };
+                ResourceLimit { resource, soft: value(limit.soft_limit), hard: value(limit.hard_limit) }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::process::Process; // Import the base Process struct
+
+/// Where a process's resident memory comes from, in kilobytes, from /proc/[pid]/smaps_rollup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryBreakdown {
+    pub rss_kb: u64,
+    pub pss_kb: u64, // Proportional share: shared pages divided among the processes mapping them
+    pub anonymous_kb: u64, // Heap, stack and other memory not backed by a file
+    pub shared_clean_kb: u64,
+    pub shared_dirty_kb: u64,
+    pub private_clean_kb: u64,
+    pub private_dirty_kb: u64,
+}
+
+impl MemoryBreakdown {
+    /// Resident memory backed by files (mapped libraries, mmapped data)
+    pub fn file_backed_kb(&self) -> u64 {
+        self.rss_kb.saturating_sub(self.anonymous_kb)
+    }
+}
+
+impl Process {
+    /// Memory breakdown of the process. The kernel walks every mapping to produce smaps_rollup,
+    /// so this is meant to be read on demand (e.g. for the selected process), never on every refresh.
+    /// None on permission errors or kernels older than 4.14.
+    pub fn memory_breakdown(pid: u32) -> Option<MemoryBreakdown> {
+        let rollup = std::fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).ok()?;
+        Some(parse_smaps_rollup(&rollup))
+    }
+}
+
+/// Pick the "Key:   <n> kB" lines we care about, ignoring the header and unknown keys
+fn parse_smaps_rollup(rollup: &str) -> MemoryBreakdown {
+    let mut breakdown = MemoryBreakdown::default();
+
+    for line in rollup.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(kb) = value.split_whitespace().next().and_then(|v| v.parse::<u64>().ok()) else {
+            continue;
+        };
+        let field = match key.trim() {
+            "Rss" => &mut breakdown.rss_kb,
+            "Pss" => &mut breakdown.pss_kb,
+            "Anonymous" => &mut breakdown.anonymous_kb,
+            "Shared_Clean" => &mut breakdown.shared_clean_kb,
+            "Shared_Dirty" => &mut breakdown.shared_dirty_kb,
+            "Private_Clean" => &mut breakdown.private_clean_kb,
+            "Private_Dirty" => &mut breakdown.private_dirty_kb,
+            _ => continue,
+        };
+        *field = kb;
+    }
+
+    breakdown
+}
@@ -3,11 +3,37 @@ use procfs::{
     process::Process as ProcfsProcess,
     ProcError,
 };
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
+use std::time::Duration;
 
 // 1. Declare submodules
 mod pcb; 
 pub mod tree;
+mod report;
+pub use report::sched_policy_name;
+pub mod thread;
+mod numa;
+mod memory;
+pub use memory::MemoryBreakdown;
+mod namespace;
+pub use namespace::NamespaceInfo;
+mod mounts;
+pub use mounts::{parse_mountinfo_line, MountEntry};
+mod network;
+pub use network::Connection;
+mod limits;
+pub use limits::{ResourceLimit, RlimitResource};
+mod schedstat;
+pub use schedstat::SchedStats;
+mod compare;
+pub use compare::{compare, CompareField};
+mod cause;
+pub use cause::{signal_name, StateCause};
+mod capabilities;
+mod tty;
+pub use tty::tty_name;
+pub use capabilities::{decode_capabilities, Capabilities, DANGEROUS_CAPABILITIES};
 
 // 2. Import the public PcbData struct from the pcb submodule
 use pcb::PcbData; 
@@ -19,9 +45,18 @@ use pcb::PcbData;
 #[derive(Debug, Clone)]
 pub struct Process {
     pub process_id: u32,
+    pub tgid: Option<u32>, // Thread group (the process a thread belongs to); equals process_id for a process's main thread. None when status was unreadable
+    // Set when /proc/[pid]/status couldn't be read: the UIDs below are then the /proc/[pid] owner's
+    // (or the last ones read, see monitoring), groups are empty and tgid is unknown
+    pub status_unreadable: bool,
     pub user_id: u32,
+    pub effective_uid: u32,
+    pub saved_uid: u32,
+    pub groups: Vec<u32>, // Supplementary group IDs
     pub name: String,
+    pub cmdline: Vec<String>, // Full command line (empty for kernel threads or when unreadable)
     pub parent_id: Option<u32>,
+    pub exe_deleted: bool, // The executable was deleted or replaced (e.g. by a package upgrade) after the process started
     pub pcb_data: PcbData, 
 }
 
@@ -32,31 +67,86 @@ impl TryFrom<u32> for Process {
     type Error = ProcError;
 
     fn try_from(pid: u32) -> Result<Self, Self::Error> {
+        // Only stat is required: if it can't be read the process has truly vanished.
+        // The other files can fail when the process exits mid-read, so they fall back to defaults.
         let procfs_proc = ProcfsProcess::new(pid as i32)?;
         let stat = procfs_proc.stat()?;
-        let status = procfs_proc.status()?;
         let page_size: u64 = procfs::page_size();
-        let memory_rss_mb = (stat.rss as u64 * page_size) / 1024 / 1024;
-        let user_id = status.ruid;
+        let memory_rss_kb = (stat.rss * page_size) / 1024;
+        let (user_id, effective_uid, saved_uid, groups, tgid) = match procfs_proc.status() {
+            Ok(status) => (
+                status.ruid,
+                status.euid,
+                status.suid,
+                status.groups.iter().map(|&gid| gid as u32).collect(),
+                Some(status.tgid as u32),
+            ),
+            Err(_) => {
+                // The owner of /proc/[pid] is the effective UID; use it for all three.
+                // If that can't be read either the process is gone: no guessing at root.
+                let uid = procfs_proc.uid()?;
+                (uid, uid, uid, Vec::new(), None)
+            }
+        };
+        let status_unreadable = tgid.is_none();
+        let cmdline = procfs_proc.cmdline().unwrap_or_default();
+        // Like fd/, io is only readable for our own processes unless we are root
+        let io = procfs_proc.io().ok();
+        // The kernel appends " (deleted)" to the exe link once the file is unlinked.
+        // Unreadable for other users' processes (ptrace access check), so those read as not deleted.
+        let exe_deleted = procfs_proc
+            .exe()
+            .is_ok_and(|path| path.as_os_str().as_encoded_bytes().ends_with(b" (deleted)"));
         let cpu_percent_placeholder = 0.0;
         
         // Calculate process uptime/runtime
         // starttime is in jiffies since system boot
         // We need to get system uptime and calculate the difference
-        let uptime_seconds = Self::calculate_uptime(stat.starttime as u64)?;
+        let uptime_seconds = Self::calculate_uptime(stat.starttime)?;
+        let hz = Self::get_system_hz();
 
         // 3. Construct the custom Process struct
         Ok(Process {
             process_id: pid,
+            tgid,
+            status_unreadable,
             user_id,
+            effective_uid,
+            saved_uid,
+            groups,
             name: stat.comm,
-            parent_id: Some(stat.ppid as u32), 
+            cmdline,
+            // A thread's ppid is its process's parent; nest it under its own process instead
+            parent_id: Some(match tgid {
+                Some(tgid) if tgid != pid => tgid,
+                _ => stat.ppid as u32,
+            }),
+            exe_deleted,
             pcb_data: PcbData { 
                 cpu_percent: cpu_percent_placeholder,
-                memory_rss_mb,
+                cpu_percent_smoothed: cpu_percent_placeholder,
+                cpu_percent_delta: 0.0,
+                cpu_percent_since_seen: None, // Computed by monitoring from the first-seen baseline
+                utime_seconds: stat.utime as f64 / hz,
+                stime_seconds: stat.stime as f64 / hz,
+                memory_rss_kb,
                 state: stat.state,
                 priority: stat.nice as i32,
+                sched_policy: stat.policy.unwrap_or(0), // Missing on very old kernels
+                rt_priority: stat.rt_priority.unwrap_or(0),
                 uptime_seconds,
+                start_time_ticks: stat.starttime,
+                last_cpu: stat.processor.unwrap_or(-1),
+                blkio_delay_ticks: stat.delayacct_blkio_ticks.unwrap_or(0), // Missing on older kernels
+                blkio_wait_ms_per_sec: 0.0, // Computed by monitoring between refreshes
+                num_threads: stat.num_threads.max(0) as u64,
+                fd_count: procfs_proc.fd_count().ok(),
+                io_read_bytes: io.as_ref().map(|io| io.read_bytes),
+                io_write_bytes: io.as_ref().map(|io| io.write_bytes),
+                pgrp: stat.pgrp,
+                tty_nr: stat.tty_nr,
+                tpgid: stat.tpgid,
+                exit_code: stat.exit_code,
             },
         })
     }
@@ -68,12 +158,70 @@ impl Process {
         self.pcb_data.cpu_percent = cpu_percent;
     }
     
+    /// CPU percentage averaged over recent refreshes (less jumpy than the raw value)
+    pub fn cpu_percent_smoothed(&self) -> f32 {
+        self.pcb_data.cpu_percent_smoothed
+    }
+
+    /// Average CPU percentage over everything this session has watched of the process: total CPU
+    /// time since it was first seen divided by the wall time since then. Steadier than the smoothed
+    /// value, which still follows the last few refreshes. None until it has been sampled twice.
+    pub fn avg_cpu_percent_since_seen(&self) -> Option<f32> {
+        self.pcb_data.cpu_percent_since_seen
+    }
+
+    /// CPU percentage as a share of the whole machine (0-100) rather than per core (0-100 × cores)
+    pub fn cpu_percent_normalized(&self, num_cores: f32) -> f32 {
+        if num_cores > 0.0 {
+            self.pcb_data.cpu_percent / num_cores
+        } else {
+            self.pcb_data.cpu_percent
+        }
+    }
+    
+    /// Whether this entry is a secondary thread of another process (only listed when threads aren't grouped)
+    pub fn is_thread(&self) -> bool {
+        self.tgid.is_some_and(|tgid| tgid != self.process_id)
+    }
+
+    /// Whether the process runs under a real-time policy (SCHED_FIFO/SCHED_RR), where the
+    /// nice value is ignored and rt_priority decides scheduling instead
+    pub fn is_realtime(&self) -> bool {
+        matches!(self.pcb_data.sched_policy, 1 | 2)
+    }
+    
+    /// Command line joined with spaces (empty if unavailable)
+    pub fn cmdline_string(&self) -> String {
+        self.cmdline.join(" ")
+    }
+    
+    /// Resident memory in megabytes, keeping the fractional part for display
+    pub fn rss_mb(&self) -> f64 {
+        self.pcb_data.memory_rss_kb as f64 / 1024.0
+    }
+    
     /// Get the total CPU time (utime + stime) in jiffies from /proc/[pid]/stat
     pub fn get_cpu_time_jiffies(pid: u32) -> Result<u64, ProcError> {
         let procfs_proc = ProcfsProcess::new(pid as i32)?;
         let stat = procfs_proc.stat()?;
         // Total CPU time = user time + system time (in jiffies)
-        Ok(stat.utime as u64 + stat.stime as u64)
+        Ok(stat.utime + stat.stime)
+    }
+
+    /// (utime, stime) in jiffies of the single thread tid, from /proc/[tid]/task/[tid]/stat.
+    /// /proc/[tid]/stat sums the whole thread group for every thread, leader or not.
+    pub fn get_thread_cpu_times(tid: u32) -> Result<(u64, u64), ProcError> {
+        let stat = ProcfsProcess::new(tid as i32)?.task_from_tid(tid as i32)?.stat()?;
+        Ok((stat.utime, stat.stime))
+    }
+    
+    /// Paths of the cgroups the process belongs to, from /proc/[pid]/cgroup
+    /// (e.g. "/system.slice/nginx.service"). Empty if the file can't be read.
+    pub fn cgroup_paths(pid: u32) -> Vec<String> {
+        ProcfsProcess::new(pid as i32)
+            .and_then(|proc| proc.cgroups())
+            .map(|cgroups| cgroups.0.into_iter().map(|cgroup| cgroup.pathname).collect())
+            .unwrap_or_default()
     }
     
     /// Calculate process uptime in seconds
@@ -86,7 +234,7 @@ impl Process {
             .split_whitespace()
             .next()
             .and_then(|s| s.parse().ok())
-            .ok_or_else(|| ProcError::NotFound(None))?;
+            .ok_or(ProcError::NotFound(None))?;
         
         // Get system HZ (clock ticks per second)
         let hz = Self::get_system_hz();
@@ -118,19 +266,240 @@ impl Process {
         100.0
     }
     
-    /// Format uptime as human-readable string (e.g., "1h 23m 45s" or "5m 30s")
-    pub fn format_uptime(&self) -> String {
-        let seconds = self.pcb_data.uptime_seconds;
-        let hours = seconds / 3600;
-        let minutes = (seconds % 3600) / 60;
-        let secs = seconds % 60;
-        
-        if hours > 0 {
-            format!("{}h {}m {}s", hours, minutes, secs)
-        } else if minutes > 0 {
-            format!("{}m {}s", minutes, secs)
+    /// Total CPU time accumulated since the process started (user + system), in seconds
+    pub fn cpu_time_seconds(&self) -> f64 {
+        self.pcb_data.utime_seconds + self.pcb_data.stime_seconds
+    }
+    
+    /// Format accumulated CPU time: "12.34s" under a minute, otherwise like format_uptime
+    pub fn format_cpu_time(&self) -> String {
+        let seconds = self.cpu_time_seconds();
+        if seconds < 60.0 {
+            format!("{:.2}s", seconds)
         } else {
-            format!("{}s", secs)
+            format_elapsed(seconds as u64)
+        }
+    }
+    
+    /// Time elapsed since the process started
+    pub fn uptime(&self) -> Duration {
+        Duration::from_secs(self.pcb_data.uptime_seconds)
+    }
+    
+    /// Format uptime as human-readable string (e.g., "3d 4h 21m", "1h 23m 45s" or "5m 30s")
+    pub fn format_uptime(&self) -> String {
+        format_elapsed(self.pcb_data.uptime_seconds)
+    }
+    
+    /// Describe when the process started relative to now (e.g., "2 days ago")
+    pub fn age_relative(&self) -> String {
+        format_age(self.pcb_data.uptime_seconds)
+    }
+}
+
+/// Unit used to display memory sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MemoryUnit {
+    KB,
+    #[default]
+    MB,
+    GB,
+    Auto, // Pick KB/MB/GB per value
+}
+
+impl MemoryUnit {
+    pub const ALL: [MemoryUnit; 4] = [MemoryUnit::KB, MemoryUnit::MB, MemoryUnit::GB, MemoryUnit::Auto];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MemoryUnit::KB => "KB",
+            MemoryUnit::MB => "MB",
+            MemoryUnit::GB => "GB",
+            MemoryUnit::Auto => "Auto",
+        }
+    }
+}
+
+/// Format a size in kilobytes in the given unit, e.g. "1.40 GB", "512.0 MB", "2048 KB".
+/// Auto uses the largest unit that keeps the value at least 1, with one decimal below 100
+/// ("12.5 MB") and none above ("512 MB").
+pub fn format_memory(kb: u64, unit: MemoryUnit) -> String {
+    let mb = kb as f64 / 1024.0;
+    let gb = mb / 1024.0;
+    match unit {
+        MemoryUnit::KB => format!("{} KB", kb),
+        MemoryUnit::MB => format!("{:.1} MB", mb),
+        MemoryUnit::GB => format!("{:.2} GB", gb),
+        MemoryUnit::Auto => {
+            let (value, suffix) = if gb >= 1.0 {
+                (gb, "GB")
+            } else if mb >= 1.0 {
+                (mb, "MB")
+            } else {
+                return format!("{} KB", kb);
+            };
+            if value >= 100.0 {
+                format!("{:.0} {}", value, suffix)
+            } else {
+                format!("{:.1} {}", value, suffix)
+            }
         }
     }
 }
+
+/// Format a number of seconds as "3d 4h 21m" / "1h 23m 45s" / "5m 30s" / "12s"
+/// Seconds are dropped once the duration reaches a day
+pub fn format_elapsed(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+    
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, secs)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Format a number of seconds as a rough relative age using the largest whole unit
+fn format_age(seconds: u64) -> String {
+    let (count, unit) = if seconds >= 86400 {
+        (seconds / 86400, "day")
+    } else if seconds >= 3600 {
+        (seconds / 3600, "hour")
+    } else if seconds >= 60 {
+        (seconds / 60, "minute")
+    } else if seconds > 0 {
+        (seconds, "second")
+    } else {
+        return "just now".to_string();
+    };
+    
+    if count == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", count, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_elapsed_boundaries() {
+        assert_eq!(format_elapsed(0), "0s");
+        assert_eq!(format_elapsed(59), "59s");
+        assert_eq!(format_elapsed(60), "1m 0s");
+        assert_eq!(format_elapsed(3599), "59m 59s");
+        assert_eq!(format_elapsed(3600), "1h 0m 0s");
+        assert_eq!(format_elapsed(86399), "23h 59m 59s");
+        assert_eq!(format_elapsed(86400), "1d 0h 0m");
+        assert_eq!(format_elapsed(3 * 86400 + 4 * 3600 + 21 * 60 + 5), "3d 4h 21m");
+    }
+
+    #[test]
+    fn compare_lists_fields_side_by_side() {
+        let process = |pid: u32, rss_kb: u64, fds: Option<usize>| {
+            let mut p = Process {
+                process_id: pid,
+                tgid: Some(pid),
+                status_unreadable: false,
+                user_id: 1000,
+                effective_uid: 1000,
+                saved_uid: 1000,
+                groups: Vec::new(),
+                name: "app".to_string(),
+                cmdline: Vec::new(),
+                parent_id: Some(1),
+                exe_deleted: false,
+                pcb_data: PcbData::default(),
+            };
+            p.pcb_data.memory_rss_kb = rss_kb;
+            p.pcb_data.fd_count = fds;
+            p
+        };
+
+        let rows = compare(&process(10, 2048, Some(5)), &process(11, 1024, Some(9)));
+        let fields: Vec<CompareField> = rows.iter().map(|&(field, _, _)| field).collect();
+        assert_eq!(fields, [CompareField::Cpu, CompareField::Rss, CompareField::Threads, CompareField::OpenFds, CompareField::Uptime]);
+        assert_eq!(rows[1], (CompareField::Rss, 2048.0, 1024.0));
+        assert_eq!(rows[3], (CompareField::OpenFds, 5.0, 9.0));
+
+        // An unreadable FD count drops the row rather than comparing against 0
+        let rows = compare(&process(10, 2048, None), &process(11, 1024, Some(9)));
+        assert!(rows.iter().all(|&(field, _, _)| field != CompareField::OpenFds));
+    }
+
+    #[test]
+    fn state_cause_decodes_wait_status() {
+        assert_eq!(StateCause::from_wait_status(0), StateCause::ExitedWith(0));
+        assert_eq!(StateCause::from_wait_status(3 << 8), StateCause::ExitedWith(3));
+        assert_eq!(StateCause::from_wait_status(9), StateCause::KilledBy { signal: 9, core_dumped: false });
+        assert_eq!(StateCause::from_wait_status(11 | 0x80), StateCause::KilledBy { signal: 11, core_dumped: true });
+        assert_eq!(StateCause::from_wait_status((19 << 8) | 0x7f), StateCause::StoppedBy(19));
+        assert_eq!(StateCause::KilledBy { signal: 11, core_dumped: true }.describe(), ("Exit signal", "SIGSEGV (core dumped)".to_string()));
+        assert_eq!(StateCause::StoppedBy(19).describe(), ("Stopped by", "SIGSTOP".to_string()));
+    }
+
+    #[test]
+    fn capability_masks_decode_to_names() {
+        assert!(decode_capabilities(0).is_empty());
+        // CAP_NET_BIND_SERVICE (10) and CAP_NET_RAW (13), as ping or a web server might hold
+        assert_eq!(decode_capabilities(0x2400), vec!["CAP_NET_BIND_SERVICE", "CAP_NET_RAW"]);
+        // Root's usual full set on a 5.9+ kernel, plus an unknown future bit
+        let full = decode_capabilities(0x1ff_ffff_ffff);
+        assert!(Capabilities::is_full(&full));
+        assert_eq!(full.last().map(String::as_str), Some("CAP_CHECKPOINT_RESTORE"));
+        assert_eq!(decode_capabilities(1 << 41), vec!["cap_41"]);
+
+        let caps = Capabilities { capabilities: decode_capabilities(1 << 21 | 1 << 12), ..Default::default() };
+        assert_eq!(caps.dangerous(), vec!["CAP_SYS_ADMIN"]);
+    }
+
+    #[test]
+    fn tty_numbers_decode_like_ps() {
+        assert_eq!(tty_name(0), None);
+        assert_eq!(tty_name(136 << 8 | 3), Some("pts/3".to_string()));
+        // pts/300 needs the second pty major
+        assert_eq!(tty_name(137 << 8 | 44), Some("pts/300".to_string()));
+        assert_eq!(tty_name(4 << 8 | 1), Some("tty1".to_string()));
+        assert_eq!(tty_name(4 << 8 | 64), Some("ttyS0".to_string()));
+        // Minor numbers above 255 continue in bits 20-31
+        assert_eq!(tty_name(5 << 8 | 1 << 20), Some("5:256".to_string()));
+    }
+
+    #[test]
+    fn mountinfo_lines_parse_with_escapes() {
+        let line = "36 35 98:0 /mnt1 /mnt/my\\040disk rw,noatime master:1 - ext3 /dev/root rw,errors=continue";
+        assert_eq!(
+            parse_mountinfo_line(line),
+            Some(MountEntry {
+                source: "/dev/root".to_string(),
+                target: "/mnt/my disk".to_string(),
+                fstype: "ext3".to_string(),
+                options: "rw,noatime".to_string(),
+            })
+        );
+        // No optional fields, as for a container's overlay root
+        let overlay = parse_mountinfo_line("512 480 0:52 / / rw,relatime - overlay overlay rw,lowerdir=/l").unwrap();
+        assert_eq!((overlay.target.as_str(), overlay.fstype.as_str()), ("/", "overlay"));
+        assert_eq!(parse_mountinfo_line("garbage"), None);
+    }
+
+    #[test]
+    fn format_age_boundaries() {
+        assert_eq!(format_age(0), "just now");
+        assert_eq!(format_age(1), "1 second ago");
+        assert_eq!(format_age(59), "59 seconds ago");
+        assert_eq!(format_age(60), "1 minute ago");
+        assert_eq!(format_age(3600), "1 hour ago");
+        assert_eq!(format_age(7199), "1 hour ago");
+        assert_eq!(format_age(2 * 86400 + 5), "2 days ago");
+    }
+}
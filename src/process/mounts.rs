@@ -0,0 +1,65 @@
+use std::io::ErrorKind;
+
+use crate::process::Process; // Import the base Process struct
+
+/// One line of /proc/[pid]/mountinfo: a mount as the process's mount namespace sees it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    pub source: String,  // Device or pseudo-source ("/dev/sda1", "overlay", "tmpfs")
+    pub target: String,  // Mount point, relative to the process's root
+    pub fstype: String,
+    pub options: String, // Per-mount options ("rw,nosuid,relatime")
+}
+
+/// Parse one mountinfo line. The layout is
+/// "id parent major:minor root target options [optional fields...] - fstype source super-options",
+/// with spaces and other special characters in paths escaped as octal ("\040").
+pub fn parse_mountinfo_line(line: &str) -> Option<MountEntry> {
+    let (before, after) = line.split_once(" - ")?;
+    let before: Vec<&str> = before.split(' ').collect();
+    let mut after = after.split(' ');
+    let target = before.get(4)?;
+    let options = before.get(5)?;
+    let fstype = after.next()?;
+    let source = after.next()?;
+    Some(MountEntry {
+        source: unescape_octal(source),
+        target: unescape_octal(target),
+        fstype: fstype.to_string(),
+        options: options.to_string(),
+    })
+}
+
+/// Undo the kernel's "\ooo" escaping of space, tab, newline and backslash
+fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes.get(i + 1..i + 4).filter(|_| bytes[i] == b'\\');
+        match escaped.and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok()) {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+impl Process {
+    /// Mount table of the process's mount namespace, in mount order. A container's table can run
+    /// to thousands of entries, so this is read on demand (e.g. for the selected process) rather
+    /// than on every refresh. Lines that don't parse are skipped.
+    pub fn mounts(pid: u32) -> Result<Vec<MountEntry>, String> {
+        let content = std::fs::read_to_string(format!("/proc/{}/mountinfo", pid)).map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => format!("Permission denied reading the mounts of PID {} (run as root?)", pid),
+            _ => format!("Failed to read mountinfo of PID {}: {}", pid, e),
+        })?;
+        Ok(content.lines().filter_map(parse_mountinfo_line).collect())
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+
+use crate::process::Process; // Import the base Process struct
+
+/// Where a process sees the filesystem from, for spotting chroots and containers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceInfo {
+    pub root_path: Option<PathBuf>, // Target of /proc/[pid]/root ("/" unless chrooted or pivoted)
+    pub mnt_namespace: Option<String>, // Inode of /proc/[pid]/ns/mnt
+    pub same_mnt_namespace_as_us: Option<bool>, // None if either namespace is unreadable
+}
+
+impl NamespaceInfo {
+    /// A root other than "/" or a different mount namespace usually means a container or chroot
+    pub fn likely_containerized(&self) -> bool {
+        self.root_path.as_deref().is_some_and(|root| root != Path::new("/"))
+            || self.same_mnt_namespace_as_us == Some(false)
+    }
+}
+
+impl Process {
+    /// Root directory and mount namespace of the process. Both links need the same permissions
+    /// as ptrace, so for other users' processes they are usually None unless running as root.
+    /// Meant to be read on demand (e.g. for the selected process), not on every refresh.
+    pub fn namespaces(pid: u32) -> NamespaceInfo {
+        let root_path = std::fs::read_link(format!("/proc/{}/root", pid)).ok();
+        let mnt_namespace = mnt_namespace_inode(&format!("/proc/{}/ns/mnt", pid));
+        let ours = mnt_namespace_inode("/proc/self/ns/mnt");
+        let same_mnt_namespace_as_us = match (&mnt_namespace, &ours) {
+            (Some(theirs), Some(ours)) => Some(theirs == ours),
+            _ => None,
+        };
+        NamespaceInfo { root_path, mnt_namespace, same_mnt_namespace_as_us }
+    }
+}
+
+/// Read a namespace link such as "mnt:[4026531841]" and keep the inode number
+fn mnt_namespace_inode(link: &str) -> Option<String> {
+    let target = std::fs::read_link(link).ok()?;
+    let target = target.to_string_lossy();
+    let inode = target
+        .strip_prefix("mnt:[")
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(&target);
+    Some(inode.to_string())
+}
@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+
+use crate::process::Process; // Import the base Process struct
+
+impl Process {
+    /// NUMA node holding most of the process's resident memory, from /proc/[pid]/numa_maps.
+    /// The file is long, so this is meant to be read on demand (e.g. for the selected process)
+    /// rather than on every refresh. None on non-NUMA kernels, permission errors, or no resident pages.
+    pub fn numa_node(pid: u32) -> Option<i32> {
+        let maps = std::fs::read_to_string(format!("/proc/{}/numa_maps", pid)).ok()?;
+        dominant_node(&maps)
+    }
+}
+
+/// Sum the `N<node>=<pages>` counts of every mapping (weighted by page size) and pick the largest node
+fn dominant_node(numa_maps: &str) -> Option<i32> {
+    let mut kb_per_node: HashMap<i32, u64> = HashMap::new();
+
+    for line in numa_maps.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let page_kb = fields
+            .iter()
+            .find_map(|f| f.strip_prefix("kernelpagesize_kB="))
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(4);
+
+        for field in &fields {
+            if let Some((node, pages)) = field.strip_prefix('N').and_then(|f| f.split_once('='))
+                && let (Ok(node), Ok(pages)) = (node.parse::<i32>(), pages.parse::<u64>())
+            {
+                *kb_per_node.entry(node).or_insert(0) += pages * page_kb;
+            }
+        }
+    }
+
+    kb_per_node
+        .into_iter()
+        .max_by_key(|&(node, kb)| (kb, std::cmp::Reverse(node)))
+        .map(|(node, _)| node)
+}
@@ -1,10 +1,29 @@
 
 /// This holds key metrics retrieved from the Linux kernel via /proc.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PcbData {
     pub cpu_percent: f32, 
-    pub memory_rss_mb: u64, 
+    pub cpu_percent_smoothed: f32, // Exponential moving average of cpu_percent across refreshes
+    pub cpu_percent_delta: f32, // Change in cpu_percent since the previous refresh (0 until there are two real samples)
+    pub cpu_percent_since_seen: Option<f32>, // Average CPU% since this session first saw the process (None until the second sample)
+    pub utime_seconds: f64, // Total CPU time spent in user mode
+    pub stime_seconds: f64, // Total CPU time spent in the kernel (system calls, page faults, ...)
+    pub memory_rss_kb: u64, // Resident set size in kilobytes
     pub state: char,      
     pub priority: i32,
+    pub sched_policy: u32, // Scheduling policy number (0 = SCHED_OTHER, 1 = SCHED_FIFO, 2 = SCHED_RR, ...)
+    pub rt_priority: u32, // Real-time priority 1-99 under SCHED_FIFO/RR, 0 otherwise
     pub uptime_seconds: u64, // Process runtime in seconds
+    pub start_time_ticks: u64, // Start time in clock ticks since boot (finer than uptime_seconds for ordering)
+    pub last_cpu: i32, // CPU core the process last ran on (-1 if unknown)
+    pub blkio_delay_ticks: u64, // Cumulative block I/O delay in clock ticks (0 if the kernel doesn't report it)
+    pub blkio_wait_ms_per_sec: f32, // Block I/O delay accrued per second since the last refresh
+    pub num_threads: u64,
+    pub fd_count: Option<usize>, // Open file descriptors (None if /proc/[pid]/fd isn't readable)
+    pub io_read_bytes: Option<u64>, // Cumulative bytes fetched from storage (None if /proc/[pid]/io isn't readable)
+    pub io_write_bytes: Option<u64>, // Cumulative bytes sent to storage
+    pub pgrp: i32,   // Process group ID
+    pub tty_nr: i32, // Device number of the controlling terminal (0 if none)
+    pub tpgid: i32,  // Foreground process group of that terminal (-1 if none)
+    pub exit_code: Option<i32>, // wait()-style status of a zombie (None if unreadable or on kernels before 3.5)
 }
\ No newline at end of file
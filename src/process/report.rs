@@ -0,0 +1,132 @@
+use std::fmt::Write;
+
+use procfs::process::Process as ProcfsProcess;
+use procfs::ProcError;
+
+use crate::process::Process;
+
+/// Name of a Linux scheduling policy number (as in the `policy` field of /proc/[pid]/stat)
+pub fn sched_policy_name(policy: u32) -> &'static str {
+    match policy {
+        0 => "SCHED_OTHER",
+        1 => "SCHED_FIFO",
+        2 => "SCHED_RR",
+        3 => "SCHED_BATCH",
+        5 => "SCHED_IDLE",
+        6 => "SCHED_DEADLINE",
+        _ => "unknown",
+    }
+}
+
+/// Explain why a section could not be read
+fn unavailable(e: &ProcError) -> String {
+    match e {
+        ProcError::PermissionDenied(_) => "  (unavailable: permission denied)\n".to_string(),
+        ProcError::NotFound(_) => "  (unavailable: process has exited)\n".to_string(),
+        other => format!("  (unavailable: {})\n", other),
+    }
+}
+
+/// Format an optional kB value from /proc/[pid]/status
+fn kb(value: Option<u64>) -> String {
+    match value {
+        Some(kb) => format!("{} kB ({:.1} MB)", kb, kb as f64 / 1024.0),
+        None => "N/A".to_string(),
+    }
+}
+
+impl Process {
+    /// Everything we can read about this process from /proc, formatted as plain text.
+    /// Sections that can't be read (usually for lack of permission) say so instead of failing.
+    pub fn full_report(&self) -> String {
+        let mut report = String::new();
+        // Writing to a String can't fail, so the fmt::Results below are ignored
+        let _ = writeln!(report, "Process {} ({})", self.process_id, self.name);
+        let _ = writeln!(report, "State: {}", self.pcb_data.state);
+        let _ = writeln!(report, "Started: {} (running for {})", self.age_relative(), self.format_uptime());
+        let _ = writeln!(
+            report,
+            "Command line: {}",
+            if self.cmdline.is_empty() { "(none)".to_string() } else { self.cmdline_string() }
+        );
+
+        report.push_str("\n[Identity]\n");
+        let _ = writeln!(report, "  Parent PID: {}", self.parent_id.map(|p| p.to_string()).unwrap_or_else(|| "N/A".to_string()));
+        let _ = writeln!(report, "  Real UID: {}", self.user_id);
+        let _ = writeln!(report, "  Effective UID: {}", self.effective_uid);
+        let _ = writeln!(report, "  Saved UID: {}", self.saved_uid);
+        let groups: Vec<String> = self.groups.iter().map(|g| g.to_string()).collect();
+        let _ = writeln!(report, "  Groups: {}", if groups.is_empty() { "none".to_string() } else { groups.join(", ") });
+
+        report.push_str("\n[CPU]\n");
+        let _ = writeln!(report, "  CPU usage: {:.2}%", self.pcb_data.cpu_percent);
+        let _ = writeln!(report, "  Last CPU core: {}", self.pcb_data.last_cpu);
+        let _ = writeln!(report, "  Block I/O wait: {:.0} ms/s", self.pcb_data.blkio_wait_ms_per_sec);
+
+        // The remaining sections are read on demand
+        let proc = match ProcfsProcess::new(self.process_id as i32) {
+            Ok(proc) => proc,
+            Err(e) => {
+                report.push_str("\n[Memory, scheduling, cgroups, files]\n");
+                report.push_str(&unavailable(&e));
+                return report;
+            }
+        };
+
+        report.push_str("\n[Memory]\n");
+        match proc.status() {
+            Ok(status) => {
+                let _ = writeln!(report, "  Virtual size: {}", kb(status.vmsize));
+                let _ = writeln!(report, "  Peak virtual size: {}", kb(status.vmpeak));
+                let _ = writeln!(report, "  Resident (RSS): {}", kb(status.vmrss));
+                let _ = writeln!(report, "    Anonymous: {}", kb(status.rssanon));
+                let _ = writeln!(report, "    File-backed: {}", kb(status.rssfile));
+                let _ = writeln!(report, "    Shared memory: {}", kb(status.rssshmem));
+                let _ = writeln!(report, "  Swapped out: {}", kb(status.vmswap));
+            }
+            Err(e) => report.push_str(&unavailable(&e)),
+        }
+
+        report.push_str("\n[Scheduling]\n");
+        match proc.stat() {
+            Ok(stat) => {
+                let _ = writeln!(report, "  Nice: {}", stat.nice);
+                let _ = writeln!(report, "  Kernel priority: {}", stat.priority);
+                if let Some(policy) = stat.policy {
+                    let _ = writeln!(report, "  Policy: {}", sched_policy_name(policy));
+                }
+                if let Some(rt_priority) = stat.rt_priority {
+                    let _ = writeln!(report, "  Real-time priority: {}", rt_priority);
+                }
+                let _ = writeln!(report, "  Threads: {}", stat.num_threads);
+            }
+            Err(e) => report.push_str(&unavailable(&e)),
+        }
+
+        report.push_str("\n[Cgroups]\n");
+        match proc.cgroups() {
+            Ok(cgroups) if cgroups.0.is_empty() => report.push_str("  (none)\n"),
+            Ok(cgroups) => {
+                for cgroup in cgroups.0 {
+                    let controllers = if cgroup.controllers.is_empty() {
+                        "unified".to_string()
+                    } else {
+                        cgroup.controllers.join(",")
+                    };
+                    let _ = writeln!(report, "  {}: {}", controllers, cgroup.pathname);
+                }
+            }
+            Err(e) => report.push_str(&unavailable(&e)),
+        }
+
+        report.push_str("\n[Files]\n");
+        match proc.fd_count() {
+            Ok(count) => {
+                let _ = writeln!(report, "  Open file descriptors: {}", count);
+            }
+            Err(e) => report.push_str(&unavailable(&e)),
+        }
+
+        report
+    }
+}
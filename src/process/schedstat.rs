@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use crate::process::Process; // Import the base Process struct
+
+/// Scheduler statistics of a process from /proc/[pid]/schedstat, for the details panel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchedStats {
+    pub sched_run_ns: u64,  // Time spent running on a CPU
+    pub sched_wait_ns: u64, // Time spent runnable but waiting on a runqueue
+    pub timeslices: u64,    // Number of times it was scheduled onto a CPU
+}
+
+impl SchedStats {
+    /// Milliseconds spent waiting on the runqueue per second of wall time since an earlier
+    /// reading. A high value while the process wants to run means it is starved of CPU.
+    pub fn wait_ms_per_sec(&self, earlier: &SchedStats, elapsed: Duration) -> f64 {
+        let secs = elapsed.as_secs_f64();
+        if secs <= 0.0 {
+            return 0.0;
+        }
+        self.sched_wait_ns.saturating_sub(earlier.sched_wait_ns) as f64 / 1_000_000.0 / secs
+    }
+}
+
+impl Process {
+    /// Run/wait times of the process. Cheap, but only needed for the selected process, so it is
+    /// read on demand. None when the kernel was built without CONFIG_SCHED_INFO (no schedstat
+    /// file, or all zeros with scheduler statistics disabled) or the process is gone.
+    pub fn sched_stats(pid: u32) -> Option<SchedStats> {
+        let schedstat = std::fs::read_to_string(format!("/proc/{}/schedstat", pid)).ok()?;
+        parse_schedstat(&schedstat)
+    }
+}
+
+/// "<run ns> <wait ns> <timeslices>" on a single line
+fn parse_schedstat(schedstat: &str) -> Option<SchedStats> {
+    let mut fields = schedstat.split_whitespace().map(|f| f.parse::<u64>().ok());
+    let stats = SchedStats {
+        sched_run_ns: fields.next()??,
+        sched_wait_ns: fields.next()??,
+        timeslices: fields.next()??,
+    };
+    (stats != SchedStats::default()).then_some(stats)
+}
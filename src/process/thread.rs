@@ -0,0 +1,46 @@
+use procfs::process::Process as ProcfsProcess;
+
+use crate::process::Process; // Import the base Process struct
+
+/// A single thread (task) of a process, as read from /proc/[pid]/task/[tid]/stat
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub tid: u32,
+    pub name: String,
+    pub state: char,
+    pub cpu_time_jiffies: u64, // utime + stime
+}
+
+impl ThreadInfo {
+    /// Total CPU time in seconds
+    pub fn cpu_time_secs(&self) -> f64 {
+        self.cpu_time_jiffies as f64 / procfs::ticks_per_second() as f64
+    }
+}
+
+impl Process {
+    /// List the threads of a process, busiest (most CPU time) first.
+    /// Threads that exit while the list is being read are skipped.
+    pub fn threads(pid: u32) -> Result<Vec<ThreadInfo>, String> {
+        let procfs_proc = ProcfsProcess::new(pid as i32)
+            .map_err(|e| format!("Failed to open process {}: {}", pid, e))?;
+        let tasks = procfs_proc
+            .tasks()
+            .map_err(|e| format!("Failed to list threads of {}: {}", pid, e))?;
+
+        let mut threads: Vec<ThreadInfo> = tasks
+            .flatten()
+            .filter_map(|task| {
+                let stat = task.stat().ok()?;
+                Some(ThreadInfo {
+                    tid: task.tid as u32,
+                    name: stat.comm,
+                    state: stat.state,
+                    cpu_time_jiffies: stat.utime + stat.stime,
+                })
+            })
+            .collect();
+        threads.sort_by_key(|t| std::cmp::Reverse(t.cpu_time_jiffies));
+        Ok(threads)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::process::Process; // Import the base Process struct
+
+/// Name of a terminal from its device number as found in stat's tty_nr, as ps shows it:
+/// "pts/3", "tty1", "ttyS0", or "major:minor" for anything else. None for 0 (no terminal).
+pub fn tty_name(tty_nr: i32) -> Option<String> {
+    if tty_nr == 0 {
+        return None;
+    }
+    // Major in bits 15-8, minor in bits 31-20 and 7-0
+    let major = (tty_nr & 0xfff00) >> 8;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    Some(match major {
+        // Unix98 pseudo-terminals span majors 136-143, 256 minors each
+        136..=143 => format!("pts/{}", (major - 136) * 256 + minor),
+        4 if minor < 64 => format!("tty{}", minor),
+        4 => format!("ttyS{}", minor - 64),
+        _ => format!("{}:{}", major, minor),
+    })
+}
+
+impl Process {
+    /// Controlling terminal of the process, e.g. "pts/3" (None for daemons and kernel threads)
+    pub fn tty(&self) -> Option<String> {
+        tty_name(self.pcb_data.tty_nr)
+    }
+
+    /// Whether the process is in the foreground process group of its terminal, i.e. the group
+    /// that receives what is typed there and Ctrl+C
+    pub fn is_tty_foreground(&self) -> bool {
+        self.pcb_data.tty_nr != 0 && self.pcb_data.tpgid > 0 && self.pcb_data.pgrp == self.pcb_data.tpgid
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 // Defines the privilege level of the user.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Privilege {
@@ -28,3 +30,47 @@ impl User {
         self.privilege == Privilege::Admin
     }
 }
+
+//Looks up the UID of a login name in /etc/passwd
+pub fn uid_for_name(name: &str) -> Option<u32> {
+    passwd_entries()
+        .into_iter()
+        .find(|(entry_name, _)| entry_name == name)
+        .map(|(_, uid)| uid)
+}
+
+//Looks up the login name of a UID in /etc/passwd
+pub fn name_for_uid(uid: u32) -> Option<String> {
+    passwd_entries()
+        .into_iter()
+        .find(|(_, entry_uid)| *entry_uid == uid)
+        .map(|(name, _)| name)
+}
+
+//Reads (name, uid) pairs from /etc/passwd, skipping malformed lines
+fn passwd_entries() -> Vec<(String, u32)> {
+    let content = std::fs::read_to_string("/etc/passwd").unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((name.to_string(), uid))
+        })
+        .collect()
+}
+
+//Maps group IDs to names from /etc/group (read once per call, so look up many at a time)
+pub fn group_names() -> HashMap<u32, String> {
+    let content = std::fs::read_to_string("/etc/group").unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let gid = fields.nth(1)?.parse().ok()?;
+            Some((gid, name.to_string()))
+        })
+        .collect()
+}